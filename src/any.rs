@@ -0,0 +1,240 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use crate::{Bucket, ValueIndex};
+
+/// A handle to a value of type `T` stored in an `AnyBucket`.
+///
+/// The type parameter ties the handle to the `Bucket` holding values of
+/// type `T`, so using it against any other type does not compile:
+///
+/// ```compile_fail
+/// use onebuck::AnyBucket;
+///
+/// let mut bucket = AnyBucket::new(4);
+/// let index = bucket.insert(1u32);
+/// let value: Option<&String> = bucket.get(&index);
+/// ```
+pub struct TypedIndex<T> {
+    index: ValueIndex,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedIndex<T> {
+    /// Returns the untyped handle of the value.
+    pub const fn index(&self) -> &ValueIndex {
+        &self.index
+    }
+}
+
+impl<T> Debug for TypedIndex<T> {
+    /// Formats the handle for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypedIndex").field(&self.index).finish()
+    }
+}
+
+/// A type-erased `Bucket` holding values of a single type.
+trait ErasedBucket {
+    fn len(&self) -> usize;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> ErasedBucket for Bucket<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A heterogeneous data structure storing values of any `'static` type.
+///
+/// `AnyBucket` keeps one `Bucket` per stored type, created lazily on the
+/// first insertion of a value of that type, and hands out `TypedIndex`
+/// handles that can only be used with the type they were created for.
+pub struct AnyBucket {
+    buckets: HashMap<TypeId, Box<dyn ErasedBucket>>,
+    capacity: usize,
+}
+
+impl AnyBucket {
+    /// Creates a new `AnyBucket` whose per-type buckets have the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots of each per-type `Bucket`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the total number of elements stored across every type.
+    pub fn len(&self) -> usize {
+        self.buckets.values().map(|b| b.len()).sum()
+    }
+
+    /// Checks if the `AnyBucket` stores no values of any type.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the `Bucket` holding values of type `T`, if it was created.
+    pub fn bucket_of<T: 'static>(&self) -> Option<&Bucket<T>> {
+        self.buckets
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref()
+    }
+
+    /// Returns the `Bucket` holding values of type `T`, if it was created.
+    fn bucket_of_existing_mut<T: 'static>(&mut self) -> Option<&mut Bucket<T>> {
+        self.buckets
+            .get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut()
+    }
+
+    /// Returns the `Bucket` holding values of type `T`, creating it if needed.
+    fn bucket_of_mut<T: 'static>(&mut self) -> &mut Bucket<T> {
+        let capacity = self.capacity;
+
+        match self
+            .buckets
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Bucket::<T>::new(capacity)))
+            .as_any_mut()
+            .downcast_mut()
+        {
+            Some(bucket) => bucket,
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns the number of elements of type `T`.
+    pub fn len_of<T: 'static>(&self) -> usize {
+        self.bucket_of::<T>().map_or(0, Bucket::len)
+    }
+
+    /// Returns an iterator over the elements of type `T`.
+    pub fn iter_of<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.bucket_of::<T>()
+            .into_iter()
+            .flat_map(|b| b.data.iter().map(|v| &v.data))
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `TypedIndex` of the value to retrieve.
+    pub fn get<T: 'static>(&self, index: &TypedIndex<T>) -> Option<&T> {
+        let bucket = self.bucket_of::<T>()?;
+        let i = bucket.slot(&index.index.0)?;
+        Some(&bucket.data[i].data)
+    }
+
+    /// Retrieves a mutable reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `TypedIndex` of the value to retrieve.
+    pub fn get_mut<T: 'static>(&mut self, index: &TypedIndex<T>) -> Option<&mut T> {
+        let bucket = self.bucket_of_existing_mut::<T>()?;
+        let i = bucket.slot(&index.index.0)?;
+        bucket.data[i].mark();
+        Some(&mut bucket.data[i].data)
+    }
+
+    /// Inserts a new value into the `Bucket` of its type.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert<T: 'static>(&mut self, data: T) -> TypedIndex<T> {
+        TypedIndex {
+            index: self.bucket_of_mut().insert(data),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Removes the value at the specified index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `TypedIndex` of the value to remove.
+    pub fn remove<T: 'static>(&mut self, index: &TypedIndex<T>) -> Option<T> {
+        let bucket = self.bucket_of_existing_mut::<T>()?;
+        bucket.remove_index(&index.index.0)
+    }
+}
+
+impl Default for AnyBucket {
+    /// Creates an empty `AnyBucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_types_coexist() {
+        let mut bucket = AnyBucket::new(2);
+        let a = bucket.insert(1u32);
+        let b = bucket.insert(String::from("b"));
+        let c = bucket.insert(2u32);
+        let d = bucket.insert(3.5f64);
+
+        assert_eq!(bucket.len(), 4);
+        assert_eq!(bucket.len_of::<u32>(), 2);
+        assert_eq!(bucket.len_of::<u8>(), 0);
+        assert_eq!(bucket.get(&a), Some(&1));
+        assert_eq!(bucket.get(&b).map(String::as_str), Some("b"));
+        assert_eq!(bucket.get(&d), Some(&3.5));
+
+        *bucket.get_mut(&c).unwrap() += 10;
+        let numbers: Vec<_> = bucket.iter_of::<u32>().copied().collect();
+        assert_eq!(numbers, vec![1, 12]);
+        assert_eq!(bucket.iter_of::<i8>().count(), 0);
+    }
+
+    #[test]
+    fn test_remove_is_per_type() {
+        let mut bucket = AnyBucket::default();
+        let a = bucket.insert(1u32);
+        let b = bucket.insert(1i64);
+        let c = bucket.insert(2u32);
+
+        assert_eq!(bucket.remove(&a), Some(1));
+        assert_eq!(bucket.remove(&a), None);
+
+        // Both handles refer to slot 0 of their own type
+        assert_eq!(bucket.get(&b), Some(&1));
+        assert_eq!(bucket.get(&c), Some(&2));
+        assert_eq!(bucket.len(), 2);
+    }
+
+    #[cfg(feature = "dirty")]
+    #[test]
+    fn test_get_mut_marks_dirty() {
+        let mut bucket = AnyBucket::new(2);
+        let a = bucket.insert(1u32);
+        let b = bucket.insert(2u32);
+        *bucket.get_mut(&a).unwrap() += 1;
+
+        let numbers = bucket.bucket_of::<u32>().unwrap();
+        assert!(numbers.is_dirty(&a.index));
+        assert!(!numbers.is_dirty(&b.index));
+    }
+}
@@ -0,0 +1,186 @@
+use std::{cell::RefCell, fmt::Debug};
+
+/// An insert-only data structure handing out plain references to its values.
+///
+/// `ArenaBucket` stores its values in fixed-size chunks that are never
+/// reallocated, so every reference returned by [`ArenaBucket::insert`] stays
+/// valid until the arena itself is dropped. Values cannot be removed.
+pub struct ArenaBucket<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+    chunk: usize,
+}
+
+impl<T> ArenaBucket<T> {
+    /// Creates a new `ArenaBucket` allocating chunks of the specified size.
+    ///
+    /// # Arguments
+    /// * `capacity` - The number of slots in each chunk, at least one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            chunk: capacity.max(1),
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `ArenaBucket`.
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    /// Returns the number of slots allocated by the `ArenaBucket`.
+    pub fn capacity(&self) -> usize {
+        self.chunks.borrow().len() * self.chunk
+    }
+
+    /// Checks if the `ArenaBucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.borrow().is_empty()
+    }
+
+    /// Inserts a new value into the `ArenaBucket`, returning a reference to it.
+    ///
+    /// If the last chunk is full, a new one is allocated; existing values never move.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&self, data: T) -> &T {
+        let mut chunks = self.chunks.borrow_mut();
+
+        if chunks.last().is_none_or(|c| c.len() == c.capacity()) {
+            chunks.push(Vec::with_capacity(self.chunk));
+        }
+        let Some(chunk) = chunks.last_mut() else {
+            unreachable!()
+        };
+
+        // The chunk has spare capacity, so pushing never reallocates it
+        chunk.push(data);
+
+        // SAFETY: the value is never moved, mutated, or dropped until the arena is dropped
+        unsafe { &*chunk.as_ptr().add(chunk.len() - 1) }
+    }
+
+    /// Returns an iterator over the elements in the `ArenaBucket`, in insertion order.
+    ///
+    /// Elements inserted while iterating are yielded as well.
+    pub fn iter(&self) -> ArenaIter<'_, T> {
+        ArenaIter {
+            arena: self,
+            chunk: 0,
+            i: 0,
+        }
+    }
+}
+
+impl<T> Default for ArenaBucket<T> {
+    /// Creates an empty `ArenaBucket` with a default chunk size.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<T: Debug> Debug for ArenaBucket<T> {
+    /// Formats the arena for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaBucket<T> {
+    type Item = &'a T;
+    type IntoIter = ArenaIter<'a, T>;
+
+    /// Returns an iterator over the elements in the `ArenaBucket`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of an `ArenaBucket`.
+pub struct ArenaIter<'a, T> {
+    arena: &'a ArenaBucket<T>,
+    chunk: usize,
+    i: usize,
+}
+
+impl<'a, T> Iterator for ArenaIter<'a, T> {
+    type Item = &'a T;
+
+    /// Advances to the next element, if any.
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunks = self.arena.chunks.borrow();
+
+        loop {
+            let chunk = chunks.get(self.chunk)?;
+
+            if self.i < chunk.len() {
+                self.i += 1;
+
+                // SAFETY: the value is never moved, mutated, or dropped while the arena is borrowed
+                return Some(unsafe { &*chunk.as_ptr().add(self.i - 1) });
+            }
+            if chunk.len() < chunk.capacity() {
+                return None;
+            }
+            self.chunk += 1;
+            self.i = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arena_references_are_stable() {
+        let arena = ArenaBucket::new(3);
+        let refs: Vec<&String> = (0..100).map(|i| arena.insert(i.to_string())).collect();
+        let addresses: Vec<*const String> = refs.iter().map(|r| *r as *const _).collect();
+
+        // Many more growth events after the references were taken
+        for i in 100..1000 {
+            arena.insert(i.to_string());
+        }
+
+        for (i, r) in refs.iter().enumerate() {
+            assert_eq!(**r, i.to_string());
+            assert_eq!(*r as *const String, addresses[i]);
+        }
+        assert_eq!(arena.len(), 1000);
+        assert_eq!(arena.capacity(), 1002);
+    }
+
+    #[test]
+    fn test_arena_iter() {
+        let arena = ArenaBucket::new(2);
+        assert!(arena.is_empty());
+        assert_eq!(arena.iter().next(), None);
+
+        for i in 0..5 {
+            arena.insert(i);
+        }
+        let values: Vec<_> = arena.iter().copied().collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+
+        // Inserting while iterating is allowed
+        let mut seen = 0;
+        for &v in &arena {
+            if v < 3 {
+                arena.insert(v + 10);
+            }
+            seen += 1;
+        }
+        assert_eq!(seen, 8);
+        assert_eq!(format!("{:?}", arena), "[0, 1, 2, 3, 4, 10, 11, 12]");
+    }
+
+    #[test]
+    fn test_arena_zero_capacity() {
+        let arena = ArenaBucket::new(0);
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert_eq!((*a, *b), (1, 2));
+        assert_eq!(arena.capacity(), 2);
+    }
+}
@@ -0,0 +1,384 @@
+use std::{fmt, mem, num::NonZeroUsize, sync::atomic::Ordering};
+
+use crate::{Bucket, GrowthPolicy, Value, ValueIndex, DANGLING};
+
+/// The slot of the oldest value in an overwriting `Bucket`.
+///
+/// Overwriting values in place keeps the slots in insertion order, starting
+/// at the cursor. Any other insertion or removal moves values between slots,
+/// so the cursor is only trusted while the length and the sequence number
+/// are those it was recorded with.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Ring {
+    head: usize,
+    len: NonZeroUsize,
+    seq: u64,
+}
+
+/// The error returned by [`Bucket::checked_insert`] when the `Bucket` is full and cannot grow.
+///
+/// It hands back the value that could not be inserted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    value: T,
+    capacity: usize,
+}
+
+impl<T> CapacityError<T> {
+    /// Returns the capacity the `Bucket` could not grow past.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Consumes the error, returning the value that could not be inserted.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    /// Formats the error as a human-readable report.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "capacity exceeded: the bucket is full and cannot grow past {}",
+            self.capacity
+        )
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for CapacityError<T> {}
+
+impl<T> Bucket<T> {
+    /// Creates a new `Bucket` whose capacity never changes.
+    ///
+    /// Once full, [`Bucket::insert`] panics and [`Bucket::try_insert`] hands the
+    /// value back, until a value is removed.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of elements in the `Bucket`.
+    pub fn with_fixed_capacity(capacity: usize) -> Self {
+        Self::with_policy(capacity, GrowthPolicy::Linear(0))
+    }
+
+    /// Checks if the `Bucket` holds as many values as its current capacity.
+    ///
+    /// Unless its capacity is fixed or bounded, a full `Bucket` still grows on
+    /// the next insertion.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Inserts a new value, unless the `Bucket` is full and cannot grow.
+    ///
+    /// The value is handed back if the capacity is fixed, or if the `Bucket`
+    /// was created by [`Bucket::bounded_overwriting`], as no value is evicted.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn try_insert(&mut self, data: T) -> Result<ValueIndex, T> {
+        if self.is_full() && !self.can_grow() {
+            return Err(data);
+        }
+        Ok(self.insert(data))
+    }
+
+    /// Creates a new `Bucket` that never grows, overwriting its oldest value instead.
+    ///
+    /// Once full, [`Bucket::insert`] behaves like [`Bucket::insert_overwriting`]
+    /// and drops the evicted value.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of elements in the `Bucket`, at least one.
+    pub fn bounded_overwriting(capacity: usize) -> Self {
+        let mut bucket = Self::new(capacity.max(1));
+        bucket.overwrite = true;
+        bucket
+    }
+
+    /// Inserts a new value, unless the `Bucket` is full and cannot grow, describing why.
+    ///
+    /// This behaves like [`Bucket::try_insert`], with the value handed back
+    /// inside the error.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn checked_insert(&mut self, data: T) -> Result<ValueIndex, CapacityError<T>> {
+        self.try_insert(data).map_err(|value| CapacityError {
+            value,
+            capacity: self.capacity(),
+        })
+    }
+
+    /// Inserts a new value, first evicting the oldest value if the `Bucket` is full.
+    ///
+    /// The oldest value is the one inserted first among those still stored,
+    /// and its handles become invalid. The new value takes over its slot, so
+    /// the capacity never changes, except for an empty `Bucket` of zero
+    /// capacity, which grows if it can, as there is nothing to evict.
+    /// Otherwise, the value is handed back as an error.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert_overwriting(&mut self, data: T) -> Result<(ValueIndex, Option<T>), T> {
+        let len = self.len();
+        if len < self.capacity() {
+            let index = self.push(data);
+            self.paranoid();
+            return Ok((index, None));
+        }
+        let Some(len) = NonZeroUsize::new(len) else {
+            return match self.can_grow() {
+                true => Ok((self.insert(data), None)),
+                false => Err(data),
+            };
+        };
+
+        let i = self.ring_head();
+        let cell = self.new_cell(i);
+        let seq = self.next_seq();
+        let value = mem::replace(&mut self.data[i], Value::with_cell(data, cell.clone(), seq));
+        value.index.store(DANGLING, Ordering::Relaxed);
+        self.persisted.forget(&value.index);
+        self.interned.forget(&value.index);
+        let evicted = self.recycle(value);
+
+        // The next oldest value follows in slot order
+        self.ring = Some(Ring {
+            head: (i + 1) % len,
+            len,
+            seq: self.seq,
+        });
+        self.paranoid();
+        Ok((ValueIndex(cell), Some(evicted)))
+    }
+
+    /// Returns the slot of the value inserted first, if any.
+    pub(crate) fn oldest_slot(&self) -> Option<usize> {
+        if let Some(head) = self.ring_cursor() {
+            return Some(head);
+        }
+        self.data
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, v)| v.seq)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the slot of the value inserted first in a non-empty `Bucket`.
+    ///
+    /// If the cursor is stale, the values are first put back in insertion
+    /// order, so that the following overwrites advance through the slots.
+    fn ring_head(&mut self) -> usize {
+        if let Some(head) = self.ring_cursor() {
+            return head;
+        }
+        self.data.sort_unstable_by_key(|v| v.seq);
+        for (i, value) in self.data.iter().enumerate() {
+            value.index.store(i, Ordering::Relaxed)
+        }
+        0
+    }
+
+    /// Returns the slot of the oldest value, if the values were only overwritten in place since it was recorded.
+    fn ring_cursor(&self) -> Option<usize> {
+        let ring = self.ring?;
+        (ring.len.get() == self.len() && ring.seq == self.seq).then_some(ring.head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_overwriting() {
+        let mut bucket = Bucket::bounded_overwriting(4);
+        let mut evicted = Vec::new();
+        let mut handles = Vec::new();
+
+        for i in 0..14 {
+            let (idx, old) = bucket.insert_overwriting(i).unwrap();
+            evicted.extend(old);
+            handles.push(idx);
+        }
+
+        // Exactly the newest values survive, and the oldest were evicted in order
+        let mut values: Vec<_> = bucket.data.iter().map(|v| v.data).collect();
+        values.sort();
+        assert_eq!(values, vec![10, 11, 12, 13]);
+        assert_eq!(evicted, (0..10).collect::<Vec<_>>());
+        assert_eq!(bucket.capacity(), 4);
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.contains(idx), i >= 10);
+        }
+
+        // Plain insertion overwrites as well
+        let idx = bucket.insert(14);
+        assert!(bucket.contains(&idx));
+        assert!(!bucket.contains(&handles[10]));
+        assert_eq!((bucket.len(), bucket.capacity()), (4, 4));
+    }
+
+    #[test]
+    fn test_insert_overwriting_unbounded() {
+        let mut bucket = Bucket::new(2);
+        assert_eq!(bucket.insert_overwriting('a').unwrap().1, None);
+        assert_eq!(bucket.insert_overwriting('b').unwrap().1, None);
+        assert_eq!(bucket.insert_overwriting('c').unwrap().1, Some('a'));
+
+        // Only the plain insertion grows an unbounded bucket
+        bucket.insert('d');
+        assert_eq!(bucket.capacity(), 4);
+
+        assert_eq!(Bucket::<u8>::bounded_overwriting(0).capacity(), 1);
+    }
+
+    #[test]
+    fn test_fixed_capacity() {
+        let mut bucket = Bucket::with_fixed_capacity(3);
+        let handles: Vec<_> = (0..3).map(|i| bucket.try_insert(i).unwrap()).collect();
+        assert!(bucket.is_full());
+
+        // The value is handed back instead of growing
+        assert_eq!(bucket.try_insert(3).unwrap_err(), 3);
+        assert_eq!(bucket.capacity(), 3);
+
+        // Removals never shrink the capacity
+        bucket.remove_index(&handles[0].0);
+        bucket.remove_index(&handles[1].0);
+        assert!(!bucket.is_full());
+        assert_eq!(bucket.capacity(), 3);
+
+        let idx = bucket.try_insert(4).unwrap();
+        assert!(bucket.contains(&idx));
+        bucket.insert(5);
+        assert!(bucket.is_full());
+        assert_eq!(bucket.try_insert(6).unwrap_err(), 6);
+        assert_eq!(bucket.into_sorted_vec(), vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn test_try_insert_growable() {
+        let mut bucket = Bucket::new(1);
+        bucket.insert(1);
+        assert!(bucket.is_full());
+        assert!(bucket.try_insert(2).is_ok());
+        assert_eq!(bucket.capacity(), 2);
+
+        // A bounded bucket never evicts through `try_insert`
+        let mut bounded = Bucket::bounded_overwriting(1);
+        bounded.insert(1);
+        assert_eq!(bounded.try_insert(2).unwrap_err(), 2);
+        assert_eq!(bounded.into_sorted_vec(), vec![1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "full `Bucket` that cannot grow past 2")]
+    fn test_insert_fixed_full() {
+        let mut bucket = Bucket::with_fixed_capacity(2);
+        bucket.extend([1, 2, 3]);
+    }
+
+    #[test]
+    fn test_max_capacity() {
+        let mut bucket = Bucket::with_max_capacity(4, 10);
+        let handles: Vec<_> = (0..10).map(|i| bucket.checked_insert(i).unwrap()).collect();
+
+        // The last chunk is cut short at the maximum
+        assert_eq!(bucket.capacity(), 10);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        let err = bucket.checked_insert(10).unwrap_err();
+        assert_eq!(err.capacity(), 10);
+        assert_eq!(err.into_inner(), 10);
+        assert_eq!(bucket.try_insert(10).unwrap_err(), 10);
+        assert_eq!(bucket.len(), 10);
+
+        // Removing one value frees a slot at the maximum
+        bucket.remove_index(&handles[0].0);
+        assert_eq!(bucket.capacity(), 10);
+        let idx = bucket.checked_insert(11).unwrap();
+        assert!(bucket.contains(&idx));
+        assert!(bucket.is_full());
+
+        // The shortened chunk is released like any other one
+        for idx in &handles[1..3] {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.capacity(), 8);
+        bucket.reserve(100);
+        assert_eq!(bucket.capacity(), 10);
+        bucket.shrink_to_fit();
+        assert_eq!(bucket.capacity(), 8);
+        assert_eq!(bucket.max_capacity(), Some(10));
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_max_capacity_exact_chunks() {
+        let mut bucket = Bucket::with_max_capacity(3, 9);
+        bucket.extend(0..9);
+        assert_eq!(bucket.capacity(), 9);
+        assert!(bucket.checked_insert(9).is_err());
+
+        bucket.clear();
+        assert_eq!(bucket.capacity(), 3);
+        assert_eq!(bucket.max_capacity(), Some(9));
+        assert_eq!(Bucket::<u8>::new(3).max_capacity(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot grow past 5")]
+    fn test_insert_past_max_capacity() {
+        let mut bucket = Bucket::with_max_capacity(2, 5);
+        bucket.extend(0..6);
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum capacity 2 is less than the initial capacity 4")]
+    fn test_max_below_initial_capacity() {
+        Bucket::<u8>::with_max_capacity(4, 2);
+    }
+
+    #[test]
+    fn test_insert_overwriting_zero_capacity() {
+        let mut bucket = Bucket::new(0);
+        let (idx, evicted) = bucket.insert_overwriting(1).unwrap();
+        assert_eq!((bucket.try_get(&idx), evicted), (Some(&1), None));
+        assert_eq!(bucket.capacity(), 1);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        let mut fixed = Bucket::with_max_capacity(0, 0);
+        assert_eq!(fixed.insert_overwriting(1), Err(1));
+        assert!(fixed.is_empty());
+        assert_eq!(fixed.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_overwriting_ring_order() {
+        let mut bucket = Bucket::bounded_overwriting(4);
+        let handles: Vec<_> = (0..4).map(|i| bucket.insert(i)).collect();
+
+        // Overwrites take the slot of the evicted value, in insertion order
+        for i in 4..10 {
+            let (idx, evicted) = bucket.insert_overwriting(i).unwrap();
+            assert_eq!(evicted, Some(i - 4));
+            assert_eq!(bucket.slot(&idx.0), Some(i % 4));
+        }
+        assert!(handles.iter().all(|idx| !bucket.contains(idx)));
+
+        // Removing a value moves another one, but the oldest is still evicted first
+        bucket.remove_index(&bucket.data[3].index.clone());
+        bucket.insert(10);
+        assert_eq!(bucket.insert_overwriting(11).unwrap().1, Some(6));
+        assert_eq!(bucket.insert_overwriting(12).unwrap().1, Some(8));
+        assert_eq!(bucket.insert_overwriting(13).unwrap().1, Some(9));
+
+        let mut values: Vec<_> = bucket.data.iter().map(|v| v.data).collect();
+        values.sort();
+        assert_eq!(values, vec![10, 11, 12, 13]);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{
+    intern::{remap_interned, InternTable},
+    Bucket, Capacity, Value, DANGLING,
+};
+
+/// Identifies a `Bucket` to the checkpoints taken from it, assigned on first use.
+#[derive(Debug)]
+pub(crate) struct Origin(AtomicUsize);
+
+impl Origin {
+    /// Creates an unassigned identity.
+    pub(crate) const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// Returns the identity, assigning a fresh one if needed.
+    fn get(&self) -> usize {
+        static NEXT: AtomicUsize = AtomicUsize::new(1);
+
+        match self.0.load(Ordering::Relaxed) {
+            0 => {
+                let id = NEXT.fetch_add(1, Ordering::Relaxed);
+                match self
+                    .0
+                    .compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => id,
+                    Err(id) => id,
+                }
+            }
+            id => id,
+        }
+    }
+}
+
+/// A snapshot of the contents of a `Bucket`.
+///
+/// `Checkpoint` is created by [`Bucket::checkpoint`] and keeps the shared cell
+/// of every element alongside a copy of its data, so that restoring it
+/// revives the handles that were valid at the time it was taken.
+#[derive(Debug)]
+pub struct Checkpoint<T> {
+    origin: usize,
+    values: Vec<Value<T>>,
+    capacity: Capacity,
+    interned: InternTable,
+    persisted: Vec<(usize, u64)>,
+}
+
+impl<T: Clone> Bucket<T> {
+    /// Captures the current state of the `Bucket`.
+    pub fn checkpoint(&self) -> Checkpoint<T> {
+        Checkpoint {
+            origin: self.origin.get(),
+            values: self.data.clone(),
+            capacity: self.capacity.clone(),
+            interned: self.clone_interned(&self.data),
+            persisted: self.persisted_slots(),
+        }
+    }
+
+    /// Puts the `Bucket` back into the state captured by a `Checkpoint`.
+    ///
+    /// Handles that were valid when the checkpoint was taken resolve to their
+    /// checkpointed values again, while handles created afterwards become invalid.
+    /// Persistent identifiers and interned values are restored as well.
+    /// The `Checkpoint` is left intact so it can be restored again later.
+    ///
+    /// An element that was moved into another `Bucket` since is restored as a
+    /// copy with a fresh handle, so that its handles keep resolving against
+    /// the other `Bucket`. Restoring into another `Bucket` than the one the
+    /// checkpoint was taken from copies every element this way.
+    ///
+    /// # Arguments
+    /// * `checkpoint` - The `Checkpoint` to restore.
+    pub fn restore(&mut self, checkpoint: &Checkpoint<T>) {
+        let origin = checkpoint.origin == self.origin.get();
+
+        // A cell is only revived if no other bucket holds its element
+        let values: Vec<_> = checkpoint
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let owned =
+                    v.index.load(Ordering::Relaxed) == DANGLING || self.slot(&v.index).is_some();
+                match origin && owned {
+                    true => v.clone(),
+                    false => v.clone_into_slot(i),
+                }
+            })
+            .collect();
+
+        for value in self.data.drain(..) {
+            value.index.store(DANGLING, Ordering::Relaxed);
+            self.persisted.forget(&value.index)
+        }
+        self.interned.clear();
+
+        self.data
+            .extend(values.into_iter().enumerate().map(|(i, v)| {
+                v.index.store(i, Ordering::Relaxed);
+                v
+            }));
+
+        if origin {
+            self.interned = remap_interned(&checkpoint.interned, &checkpoint.values, &self.data);
+            for &(i, id) in &checkpoint.persisted {
+                self.persisted.register(id, &self.data[i].index)
+            }
+        }
+        self.ring = None;
+        self.set_capacity(checkpoint.capacity.clone());
+        self.paranoid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValueIndex;
+
+    use super::*;
+
+    fn resolve<'a>(bucket: &'a Bucket<String>, index: &ValueIndex) -> Option<&'a str> {
+        bucket.slot(&index.0).map(|i| bucket.data[i].data.as_str())
+    }
+
+    #[test]
+    fn test_restore_after_mutation() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i.to_string())).collect();
+        let checkpoint = bucket.checkpoint();
+
+        // Removals relocate the tail elements into the freed slots
+        for idx in handles.iter().step_by(3) {
+            bucket._remove(bucket.slot(&idx.0).unwrap());
+        }
+        let later: Vec<_> = (0..20).map(|i| bucket.insert(format!("new{i}"))).collect();
+        let i = bucket.slot(&handles[1].0).unwrap();
+        bucket.data[i].data = "changed".into();
+
+        bucket.restore(&checkpoint);
+
+        assert_eq!(bucket.len(), 10);
+        assert_eq!(bucket.capacity(), 12);
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(resolve(&bucket, idx), Some(i.to_string().as_str()));
+        }
+        for idx in &later {
+            assert_eq!(resolve(&bucket, idx), None);
+        }
+    }
+
+    #[test]
+    fn test_independent_checkpoints() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert("a".to_string());
+        let first = bucket.checkpoint();
+
+        let b = bucket.insert("b".to_string());
+        bucket._remove(bucket.slot(&a.0).unwrap());
+        let second = bucket.checkpoint();
+
+        bucket.restore(&first);
+        assert_eq!(resolve(&bucket, &a), Some("a"));
+        assert_eq!(resolve(&bucket, &b), None);
+
+        bucket.restore(&second);
+        assert_eq!(resolve(&bucket, &a), None);
+        assert_eq!(resolve(&bucket, &b), Some("b"));
+
+        bucket.restore(&first);
+        assert_eq!(bucket.len(), 1);
+        assert_eq!(resolve(&bucket, &a), Some("a"));
+    }
+
+    #[test]
+    fn test_restore_after_transfer() {
+        let mut a = Bucket::new(2);
+        let mut b = Bucket::new(2);
+        let x = a.insert("x".to_string());
+        let y = a.insert("y".to_string());
+        let checkpoint = a.checkpoint();
+
+        // The moved element stays in the other bucket, and is restored as a copy
+        a.transfer(&x, &mut b).unwrap();
+        a.restore(&checkpoint);
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(b.validate(), Ok(()));
+        assert_eq!(resolve(&b, &x), Some("x"));
+        assert_eq!(resolve(&a, &x), None);
+        assert_eq!(resolve(&a, &y), Some("y"));
+        assert_eq!(a, ["x", "y"]);
+
+        // Restoring into another bucket copies every element
+        let mut c = Bucket::new(1);
+        c.restore(&checkpoint);
+        assert_eq!(c.validate(), Ok(()));
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(resolve(&a, &y), Some("y"));
+        assert_eq!(resolve(&c, &y), None);
+        assert_eq!(c.len(), 2);
+    }
+
+    #[test]
+    fn test_restore_persisted() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert("a".to_string());
+        let b = bucket.insert("b".to_string());
+        let id_a = bucket.persist(&a).unwrap();
+        let checkpoint = bucket.checkpoint();
+
+        bucket._remove(bucket.slot(&a.0).unwrap());
+        let id_b = bucket.persist(&b).unwrap();
+        assert!(bucket.resolve(id_a).is_none());
+
+        bucket.restore(&checkpoint);
+        assert_eq!(bucket.resolve(id_a).as_ref(), Some(&a));
+        assert!(bucket.resolve(id_b).is_none());
+        assert_eq!(bucket.persisted_slots().len(), 1);
+
+        // Identifiers issued after the checkpoint are not reused
+        assert_eq!(bucket.persist(&b).unwrap().get(), 2);
+    }
+
+    #[test]
+    fn test_restore_interned() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern("a".to_string());
+        let checkpoint = bucket.checkpoint();
+
+        assert_eq!(bucket.release(&a).as_deref(), Some("a"));
+        bucket.restore(&checkpoint);
+        assert_eq!(bucket.intern("a".to_string()), a);
+        assert_eq!(bucket.len(), 1);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Bucket, Value};
+
+/// A contiguous block of slots of a `Bucket`, borrowed mutably.
+///
+/// `ChunkMut` is created by [`Bucket::chunks_mut`]. Chunks borrow disjoint
+/// slot ranges, so they can be processed on separate threads.
+#[derive(Debug)]
+pub struct ChunkMut<'a, T> {
+    values: &'a mut [Value<T>],
+    offset: usize,
+}
+
+// SAFETY: a chunk only ever accesses the data of its values, never their
+// shared cells, so it is as thread-safe as a `&mut [T]`
+unsafe impl<T: Send> Send for ChunkMut<'_, T> {}
+
+impl<'a, T> ChunkMut<'a, T> {
+    /// Returns the slot of the first element of the chunk.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of elements in the chunk.
+    pub const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if the chunk is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the elements in the chunk.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter().map(|v| &v.data)
+    }
+
+    /// Returns an iterator over mutable references to the elements in the chunk.
+    ///
+    /// Every yielded element is flagged as modified.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.values.iter_mut().map(|v| {
+            v.mark();
+            &mut v.data
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for ChunkMut<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = std::iter::Map<std::slice::IterMut<'a, Value<T>>, fn(&mut Value<T>) -> &mut T>;
+
+    /// Returns an iterator over mutable references to the elements in the chunk.
+    ///
+    /// Every yielded element is flagged as modified.
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter_mut().map(|v| {
+            v.mark();
+            &mut v.data
+        })
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Returns an iterator over mutable chunks of `n` elements, in slot order.
+    ///
+    /// The last chunk holds the remaining elements if the length of the
+    /// `Bucket` is not a multiple of `n`.
+    ///
+    /// # Arguments
+    /// * `n` - The number of elements in each chunk.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    pub fn chunks_mut(&mut self, n: usize) -> impl Iterator<Item = ChunkMut<'_, T>> {
+        assert!(n != 0, "chunk size must be non-zero");
+
+        self.data
+            .chunks_mut(n)
+            .enumerate()
+            .map(move |(i, values)| ChunkMut {
+                values,
+                offset: i * n,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_chunks_mut_on_scoped_threads() {
+        let mut bucket = Bucket::new(8);
+        for _ in 0..103 {
+            bucket.insert(0u32);
+        }
+
+        let bounds = thread::scope(|s| {
+            let threads: Vec<_> = bucket
+                .chunks_mut(10)
+                .map(|mut chunk| {
+                    s.spawn(move || {
+                        chunk.iter_mut().for_each(|v| *v += 1);
+                        (chunk.offset(), chunk.len())
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|t| t.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        // The chunks are contiguous and cover the ragged tail
+        assert_eq!(bounds.len(), 11);
+        assert!(bounds.windows(2).all(|w| w[0].0 + w[0].1 == w[1].0));
+        assert_eq!(bounds.last(), Some(&(100, 3)));
+        assert!(bucket.data.iter().all(|v| v.data == 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn test_chunks_mut_zero() {
+        Bucket::<u8>::new(1).chunks_mut(0).for_each(drop);
+    }
+
+    #[test]
+    fn test_chunks_mut_into_iter() {
+        let mut bucket = Bucket::new(4);
+        (0..5).for_each(|i| drop(bucket.insert(i)));
+
+        for chunk in bucket.chunks_mut(2) {
+            let offset = chunk.offset() as i32;
+            chunk.into_iter().for_each(|v| *v -= offset);
+        }
+        let values: Vec<_> = bucket.data.iter().map(|v| v.data).collect();
+        assert_eq!(values, vec![0, 1, 0, 1, 0]);
+        assert_eq!(Bucket::<u8>::new(1).chunks_mut(3).count(), 0);
+    }
+}
@@ -0,0 +1,284 @@
+//! Comparisons of buckets with each other and with the standard sequence types.
+//!
+//! Against the standard sequence types, values are compared in slot order,
+//! which depends on the removal history of the `Bucket`, so these are mainly
+//! useful for buckets that were only inserted into. Two buckets are instead
+//! compared as multisets, regardless of the order of their values.
+//!
+//! Handles are compared by the identity of their shared cell, so that they
+//! can key maps and sets kept alongside a `Bucket`.
+
+use std::{
+    cmp::Ordering,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{Bucket, Index, ValueIndex};
+
+impl PartialEq for ValueIndex {
+    /// Checks if both handles share the same cell, and thus refer to the same element.
+    ///
+    /// Every dangling handle is equal to every other dangling handle.
+    fn eq(&self, other: &Self) -> bool {
+        Index::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ValueIndex {}
+
+impl Hash for ValueIndex {
+    /// Hashes the address of the shared cell, which does not change when the element is relocated.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Index::as_ptr(&self.0).hash(state)
+    }
+}
+
+impl PartialOrd for ValueIndex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueIndex {
+    /// Orders handles by the address of their shared cell.
+    ///
+    /// The order is arbitrary, but stable for as long as the handles are alive.
+    fn cmp(&self, other: &Self) -> Ordering {
+        Index::as_ptr(&self.0).cmp(&Index::as_ptr(&other.0))
+    }
+}
+
+impl<T: PartialEq> PartialEq for Bucket<T> {
+    /// Checks if both buckets hold equal values, the same number of times each, in any order.
+    ///
+    /// The capacity and the handles of the buckets are not compared.
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let mut matched = vec![false; other.len()];
+
+        self.data.iter().all(|v| {
+            let found = other
+                .data
+                .iter()
+                .enumerate()
+                .position(|(i, o)| !matched[i] && o.data == v.data);
+            found.map(|i| matched[i] = true).is_some()
+        })
+    }
+}
+
+impl<T: Eq> Eq for Bucket<T> {}
+
+impl<T: Hash> Hash for Bucket<T> {
+    /// Hashes the values regardless of their order, so that it agrees with `PartialEq`.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sum = self.data.iter().fold(0u64, |sum, v| {
+            let mut hasher = DefaultHasher::new();
+            v.data.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+}
+
+/// Compares the values of a `Bucket` in slot order with the elements of a slice.
+fn eq_slice<T: PartialEq<U>, U>(bucket: &Bucket<T>, other: &[U]) -> bool {
+    bucket.len() == other.len() && bucket.data.iter().zip(other).all(|(v, o)| v.data == *o)
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for Bucket<T> {
+    /// Compares the values in slot order with the elements of the slice.
+    fn eq(&self, other: &[U]) -> bool {
+        eq_slice(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<&[U]> for Bucket<T> {
+    /// Compares the values in slot order with the elements of the slice.
+    fn eq(&self, other: &&[U]) -> bool {
+        eq_slice(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for Bucket<T> {
+    /// Compares the values in slot order with the elements of the array.
+    fn eq(&self, other: &[U; N]) -> bool {
+        eq_slice(self, other)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for Bucket<T> {
+    /// Compares the values in slot order with the elements of the vector.
+    fn eq(&self, other: &Vec<U>) -> bool {
+        eq_slice(self, other)
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Bucket<U>> for [T] {
+    /// Compares the elements of the slice with the values of the `Bucket` in slot order.
+    fn eq(&self, other: &Bucket<U>) -> bool {
+        eq_slice(other, self)
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Bucket<U>> for &[T] {
+    /// Compares the elements of the slice with the values of the `Bucket` in slot order.
+    fn eq(&self, other: &Bucket<U>) -> bool {
+        eq_slice(other, self)
+    }
+}
+
+impl<T, U: PartialEq<T>, const N: usize> PartialEq<Bucket<U>> for [T; N] {
+    /// Compares the elements of the array with the values of the `Bucket` in slot order.
+    fn eq(&self, other: &Bucket<U>) -> bool {
+        eq_slice(other, self)
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Bucket<U>> for Vec<T> {
+    /// Compares the elements of the vector with the values of the `Bucket` in slot order.
+    fn eq(&self, other: &Bucket<U>) -> bool {
+        eq_slice(other, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::*;
+
+    fn bucket_of(values: &[i32]) -> Bucket<i32> {
+        let mut bucket = Bucket::new(2);
+        values.iter().for_each(|&v| drop(bucket.insert(v)));
+        bucket
+    }
+
+    #[test]
+    fn test_eq_std_containers() {
+        let bucket = bucket_of(&[1, 2, 3]);
+
+        assert_eq!(bucket, vec![1, 2, 3]);
+        assert_eq!(bucket, [1, 2, 3]);
+        assert_eq!(bucket, [1, 2, 3][..]);
+        assert_eq!(bucket, &[1, 2, 3][..]);
+        assert_eq!(vec![1, 2, 3], bucket);
+        assert_eq!([1, 2, 3], bucket);
+        assert_eq!([1, 2, 3][..], bucket);
+        assert_eq!(&[1, 2, 3][..], bucket);
+
+        let empty = bucket_of(&[]);
+        assert_eq!(empty, Vec::<i32>::new());
+        assert_eq!(empty, [0; 0]);
+    }
+
+    #[test]
+    fn test_ne_std_containers() {
+        let bucket = bucket_of(&[1, 2, 3]);
+
+        // Unequal lengths
+        assert_ne!(bucket, vec![1, 2]);
+        assert_ne!(bucket, [1, 2, 3, 4]);
+        assert_ne!(bucket, [1, 2][..]);
+        assert_ne!(Vec::<i32>::new(), bucket);
+
+        // Unequal contents
+        assert_ne!(bucket, vec![1, 2, 4]);
+        assert_ne!(bucket, [3, 2, 1]);
+        assert_ne!(bucket, [0, 2, 3][..]);
+        assert_ne!([1, 5, 3], bucket);
+    }
+
+    #[test]
+    fn test_eq_follows_slot_order() {
+        let mut bucket = bucket_of(&[1, 2, 3]);
+        let first = bucket.data[0].index();
+        bucket.remove_index(&first.0);
+
+        // The last value was moved into the freed slot
+        assert_eq!(bucket, [3, 2]);
+        assert_ne!(bucket, [2, 3]);
+    }
+
+    fn hash_of(bucket: &Bucket<i32>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bucket.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_eq_buckets() {
+        let mut a = bucket_of(&[1, 2, 3, 2]);
+        let b = bucket_of(&[2, 3, 2, 1]);
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+
+        // Interleaved removals reorder the slots
+        let first = a.data[0].index();
+        a.remove_index(&first.0);
+        a.insert(5);
+        a.insert(1);
+        let third = a.data[2].index();
+        a.remove_index(&third.0);
+        assert_eq!(a, [2, 2, 1, 5][..]);
+
+        let mut c = Bucket::new(16);
+        [2, 1, 5, 2].iter().for_each(|&v| drop(c.insert(v)));
+        assert_ne!(a.capacity(), c.capacity());
+        assert_eq!(a, c);
+        assert_eq!(hash_of(&a), hash_of(&c));
+    }
+
+    #[test]
+    fn test_ne_buckets() {
+        let a = bucket_of(&[1, 2, 2]);
+        assert_ne!(a, bucket_of(&[1, 1, 2]));
+        assert_ne!(a, bucket_of(&[1, 2]));
+        assert_ne!(a, bucket_of(&[1, 2, 2, 3]));
+        assert_ne!(hash_of(&a), hash_of(&bucket_of(&[1, 1, 2])));
+        assert_eq!(bucket_of(&[]), bucket_of(&[]));
+    }
+
+    #[test]
+    fn test_eq_borrowed_forms() {
+        let mut bucket = Bucket::new(2);
+        bucket.insert(String::from("a"));
+        bucket.insert(String::from("b"));
+        assert_eq!(bucket, ["a", "b"]);
+        assert_eq!(bucket, vec![String::from("a"), String::from("b")]);
+    }
+
+    #[test]
+    // Handles are keyed by the address of their cell, not by the slot it holds
+    #[allow(clippy::mutable_key_type)]
+    fn test_handles_key_maps() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..16).map(|i| bucket.insert(i)).collect();
+        let set: HashSet<_> = bucket.indices().collect();
+        let map: BTreeMap<_, _> = bucket.iter_indexed().map(|(h, &v)| (h, v)).collect();
+        assert_eq!(set.len(), 16);
+
+        // Relocating elements does not change their keys
+        for idx in handles.iter().step_by(3) {
+            bucket.remove_index(&idx.0);
+        }
+        for (i, idx) in handles.iter().enumerate() {
+            assert!(set.contains(idx));
+            assert_eq!(map.get(idx), Some(&i));
+            assert_eq!(bucket.contains(idx), i % 3 != 0);
+        }
+        for (idx, v) in bucket.iter_indexed() {
+            assert!(set.contains(&idx));
+            assert_eq!(map[&idx], *v);
+        }
+
+        // Handles to distinct elements are distinct, even with the same slot
+        let new = bucket.insert(16);
+        assert!(!set.contains(&new));
+        assert_ne!(new, handles[15]);
+        assert_eq!(ValueIndex::dangling(), ValueIndex::default());
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A global allocator counting the allocations performed by each thread.
+struct CountingAlloc;
+
+impl CountingAlloc {
+    fn count() {
+        // The counter is unavailable while the thread is being torn down
+        _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Returns the number of allocations and reallocations performed by `f` on the current thread.
+pub fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Asserts that `f` performs at most `bound` allocations, reporting the actual count otherwise.
+///
+/// # Arguments
+/// * `what` - A description of the measured operation.
+/// * `bound` - The maximum number of allocations and reallocations.
+/// * `f` - The operation to measure.
+#[track_caller]
+pub fn assert_allocations(what: &str, bound: usize, f: impl FnOnce()) {
+    let actual = allocations(f);
+    assert!(
+        actual <= bound,
+        "{what} performed {actual} allocations, exceeding the bound of {bound}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bucket, ValueIndex};
+
+    use super::*;
+
+    const N: usize = 64;
+
+    #[test]
+    fn test_insert_allocations() {
+        let mut handles = Vec::with_capacity(2 * N);
+        let mut reserved = None;
+
+        // One allocation for the slots, then one for each shared cell
+        assert_allocations("pre-reserved inserts", N + 1, || {
+            let bucket = reserved.insert(Bucket::new(N));
+            handles.extend((0..N).map(|i| bucket.insert(i)));
+        });
+
+        // Growing reallocates at most once per chunk
+        let mut bucket = Bucket::new(8);
+        assert_allocations("growing inserts", N + N / 8, || {
+            handles.extend((0..N).map(|i| bucket.insert(i)));
+        });
+    }
+
+    #[test]
+    fn test_extend_allocations() {
+        let mut src = Bucket::new(N);
+        (0..N).for_each(|i| drop(src.insert(i)));
+        let mut bucket = Bucket::new(4);
+        bucket.insert(0);
+
+        // The capacity is reserved once up front
+        assert_allocations("extend_from_bucket", N + 2, || {
+            drop(bucket.extend_from_bucket(&src));
+        });
+    }
+
+    #[test]
+    fn test_non_allocating_operations() {
+        let mut bucket = Bucket::new(N);
+        let mut handles: Vec<_> = (0..N).map(|i| bucket.insert(i)).collect();
+        let mut removed = Vec::with_capacity(N);
+
+        assert_allocations("lookups", 0, || {
+            assert!(handles.iter().all(|h| bucket.contains(h)));
+            assert_eq!(bucket.iter().count(), N);
+        });
+        assert_allocations("replacements", 0, || {
+            for h in &handles {
+                bucket.replace(h, 0);
+                bucket.take(h);
+                bucket.replace_with(h, |v| v + 1);
+            }
+        });
+
+        // Removals stay within the first chunk, so nothing is shrunk
+        assert_allocations("removals", 0, || {
+            removed.extend(handles.drain(1..).map(|h| bucket.remove_index(&h.0)));
+        });
+
+        // Every dangling handle shares one cell, created on first use
+        drop(ValueIndex::dangling());
+        assert_allocations("dangling handles", 0, || {
+            handles.push(ValueIndex::dangling());
+        });
+    }
+
+    #[test]
+    fn test_cell_reuse() {
+        let mut bucket = Bucket::new(N);
+        let mut handles: Vec<_> = (0..N).map(|i| bucket.insert(i)).collect();
+
+        // Removing through consumed handles recycles their cells into a pool allocated once
+        assert_allocations("removals through owned handles", 1, || {
+            handles.drain(..).for_each(|h| _ = bucket.remove(h));
+        });
+        assert_allocations("inserts reusing cells", 0, || {
+            handles.extend((0..N).map(|i| bucket.insert(i)));
+        });
+
+        // Cells still referenced by a handle are never reused
+        let kept: Vec<_> = handles.iter().map(|h| ValueIndex(h.0.clone())).collect();
+        for h in handles.drain(..) {
+            _ = bucket.remove(h);
+        }
+        assert_eq!(allocations(|| drop(bucket.insert(0))), 1);
+        assert!(kept.iter().all(|h| h.is_dangling()));
+    }
+
+    #[test]
+    fn test_cell_pool_bounds() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..N).map(|i| bucket.insert(i)).collect();
+        for h in handles {
+            _ = bucket.remove(h);
+        }
+        assert_eq!(bucket.free_cells.len(), 4);
+
+        bucket.clear();
+        assert_eq!(bucket.free_cells.capacity(), 0);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Bucket, ValueIndex};
+
+/// A cursor over the slots of a `Bucket` that can remove the value it points at.
+///
+/// `CursorMut` is created by [`Bucket::cursor_mut`] and starts at the first
+/// slot. Removing the current value moves the last value into its slot, so
+/// the cursor stays in place and that value becomes the current one instead
+/// of being skipped.
+pub struct CursorMut<'a, T> {
+    bucket: &'a mut Bucket<T>,
+    slot: usize,
+}
+
+impl<T> Bucket<T> {
+    /// Returns a cursor pointing at the first slot of the `Bucket`.
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            bucket: self,
+            slot: 0,
+        }
+    }
+}
+
+impl<T> CursorMut<'_, T> {
+    /// Returns the slot the cursor points at, which is the length of the `Bucket` once exhausted.
+    pub const fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// Returns a mutable reference to the current value, if any.
+    ///
+    /// The value is flagged as modified.
+    pub fn current(&mut self) -> Option<&mut T> {
+        let value = self.bucket.data.get_mut(self.slot)?;
+        value.mark();
+        Some(&mut value.data)
+    }
+
+    /// Returns a handle to the current value, if any.
+    pub fn current_index(&self) -> Option<ValueIndex> {
+        self.bucket.data.get(self.slot).map(|v| v.index())
+    }
+
+    /// Moves the cursor to the next slot, unless it is already exhausted.
+    pub fn advance(&mut self) {
+        self.slot = (self.slot + 1).min(self.bucket.len())
+    }
+
+    /// Removes and returns the current value, leaving the cursor on the value moved into its slot.
+    ///
+    /// # Panics
+    /// Panics if the cursor is exhausted.
+    pub fn remove_current(&mut self) -> T {
+        if self.slot >= self.bucket.len() {
+            panic!("attempted to remove past the end of the bucket")
+        }
+        let value = self.bucket._remove(self.slot);
+        self.bucket.paranoid();
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Index;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_every_other() {
+        let mut bucket = Bucket::new(8);
+        let handles: Vec<_> = (0..100).map(|i| bucket.insert(i)).collect();
+
+        let mut cursor = bucket.cursor_mut();
+        let mut removed = Vec::new();
+        while let Some(v) = cursor.current() {
+            if *v % 2 == 0 {
+                removed.push(cursor.remove_current());
+            } else {
+                *v *= 10;
+                cursor.advance();
+            }
+        }
+        assert_eq!(cursor.slot(), 50);
+
+        removed.sort();
+        assert_eq!(removed, (0..100).step_by(2).collect::<Vec<_>>());
+        assert_eq!(bucket.len(), 50);
+        assert_eq!(bucket.capacity(), 56);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        for (i, idx) in handles.iter().enumerate() {
+            match i % 2 {
+                0 => assert!(!bucket.contains(idx)),
+                _ => assert_eq!(bucket.try_get(idx), Some(&(i * 10))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_edges() {
+        let mut bucket = Bucket::new(2);
+        let mut cursor = bucket.cursor_mut();
+        assert!(cursor.current().is_none());
+        cursor.advance();
+        assert_eq!(cursor.slot(), 0);
+
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let mut cursor = bucket.cursor_mut();
+        assert!(cursor
+            .current_index()
+            .is_some_and(|idx| Index::ptr_eq(&idx.0, &a.0)));
+
+        // Removing the last value exhausts the cursor
+        cursor.advance();
+        assert_eq!(cursor.remove_current(), 'b');
+        assert!(cursor.current().is_none());
+        assert!(cursor.current_index().is_none());
+        assert!(!bucket.contains(&b));
+        assert_eq!(bucket, ['a']);
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end")]
+    fn test_remove_exhausted() {
+        let mut bucket = Bucket::new(1);
+        bucket.insert(1);
+        let mut cursor = bucket.cursor_mut();
+        cursor.advance();
+        cursor.remove_current();
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(feature = "dirty")]
+use std::sync::atomic::Ordering;
+
+use crate::Value;
+#[cfg(feature = "dirty")]
+use crate::{Bucket, ValueIndex};
+
+impl<T> Value<T> {
+    /// Flags the value as modified.
+    ///
+    /// This is a no-op unless the `dirty` feature is enabled.
+    #[inline(always)]
+    pub(crate) fn mark(&self) {
+        #[cfg(feature = "dirty")]
+        self.dirty.store(true, Ordering::Relaxed)
+    }
+
+    /// Checks if the value is flagged as modified.
+    ///
+    /// This is always `false` unless the `dirty` feature is enabled.
+    #[inline(always)]
+    pub(crate) fn is_marked(&self) -> bool {
+        #[cfg(feature = "dirty")]
+        return self.dirty.load(Ordering::Relaxed);
+        #[cfg(not(feature = "dirty"))]
+        false
+    }
+
+    /// Sets whether the value is flagged as modified.
+    ///
+    /// This is a no-op unless the `dirty` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `dirty` - The flag to set.
+    #[inline(always)]
+    pub(crate) fn set_marked(&self, #[allow(unused_variables)] dirty: bool) {
+        #[cfg(feature = "dirty")]
+        self.dirty.store(dirty, Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "dirty")]
+impl<T> Bucket<T> {
+    /// Flags the value at the specified index as modified, returning whether it exists.
+    ///
+    /// Values are also flagged automatically by every method handing out or
+    /// replacing their data mutably, while newly inserted values start clean.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to flag.
+    pub fn mark_dirty(&self, index: &ValueIndex) -> bool {
+        let Some(i) = self.slot(&index.0) else {
+            return false;
+        };
+        self.data[i].mark();
+        true
+    }
+
+    /// Checks if the value at the specified index was modified since the last `take_dirty`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to check.
+    pub fn is_dirty(&self, index: &ValueIndex) -> bool {
+        self.slot(&index.0)
+            .is_some_and(|i| self.data[i].dirty.load(Ordering::Relaxed))
+    }
+
+    /// Returns handles to every modified value, in slot order, and flags them as clean.
+    pub fn take_dirty(&mut self) -> Vec<ValueIndex> {
+        self.data
+            .iter_mut()
+            .filter_map(|v| std::mem::take(v.dirty.get_mut()).then_some(&*v))
+            .map(|v| v.index())
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "dirty"))]
+mod tests {
+    use crate::Index;
+
+    use super::*;
+
+    fn dirty_slots(bucket: &mut Bucket<u32>, handles: &[ValueIndex]) -> Vec<usize> {
+        let dirty = bucket.take_dirty();
+        let mut named: Vec<_> = dirty
+            .iter()
+            .map(|d| {
+                handles
+                    .iter()
+                    .position(|h| Index::ptr_eq(&h.0, &d.0))
+                    .unwrap()
+            })
+            .collect();
+        named.sort();
+        named
+    }
+
+    #[test]
+    fn test_take_dirty() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..8).map(|i| bucket.insert(i)).collect();
+        assert!(bucket.take_dirty().is_empty());
+
+        bucket.replace(&handles[1], 10);
+        assert!(bucket.replace_with(&handles[3], |v| v * 2));
+        assert!(bucket.mark_dirty(&handles[5]));
+        assert!(bucket.is_dirty(&handles[5]));
+        assert!(!bucket.is_dirty(&handles[4]));
+        bucket
+            .transaction(|tx| {
+                tx.update(&handles[6], |v| *v += 1);
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+        for chunk in bucket.chunks_mut(3).skip(2) {
+            chunk.into_iter().for_each(|v| *v += 1);
+        }
+
+        assert_eq!(dirty_slots(&mut bucket, &handles), vec![1, 3, 5, 6, 7]);
+        assert!(bucket.take_dirty().is_empty());
+    }
+
+    #[test]
+    fn test_dirty_follows_relocation() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..6).map(|i| bucket.insert(i)).collect();
+        bucket.mark_dirty(&handles[5]);
+        bucket.mark_dirty(&handles[0]);
+
+        // The last element moves into the slot of the removed, dirty one
+        assert_eq!(bucket.remove_index(&handles[0].0), Some(0));
+        assert!(bucket.is_dirty(&handles[5]));
+        assert!(!bucket.is_dirty(&handles[1]));
+        assert!(!bucket.mark_dirty(&handles[0]));
+
+        assert_eq!(dirty_slots(&mut bucket, &handles), vec![5]);
+    }
+
+    #[test]
+    fn test_dirty_downcast() {
+        let mut bucket: Bucket<Box<dyn std::any::Any>> = Bucket::new(4);
+        let a = bucket.insert(Box::new(1u32));
+        let b = bucket.insert(Box::new("b"));
+        let c = bucket.insert(Box::new(3u32));
+
+        // A failed downcast does not flag the value
+        assert!(bucket.get_downcast_mut::<u8>(&a).is_none());
+        assert!(!bucket.is_dirty(&a));
+        *bucket.get_downcast_mut::<u32>(&a).unwrap() += 1;
+        assert!(bucket.is_dirty(&a));
+        assert_eq!(bucket.take_dirty().len(), 1);
+
+        bucket.iter_downcast_mut::<u32>().for_each(|v| *v += 1);
+        assert!(bucket.is_dirty(&a) && bucket.is_dirty(&c));
+        assert!(!bucket.is_dirty(&b));
+    }
+}
@@ -0,0 +1,102 @@
+use std::any::Any;
+
+use crate::{Bucket, Value, ValueIndex};
+
+/// Implements the downcasting helpers for buckets of boxed trait objects.
+macro_rules! impl_downcast {
+    ($($t:ty),*) => {$(
+        impl Bucket<Box<$t>> {
+            /// Retrieves a reference to the value at the given index, if it exists and is a `U`.
+            ///
+            /// # Arguments
+            /// * `index` - The `ValueIndex` of the value to retrieve.
+            pub fn get_downcast_ref<U: 'static>(&self, index: &ValueIndex) -> Option<&U> {
+                let i = self.slot(&index.0)?;
+                (*self.data[i].data).downcast_ref()
+            }
+
+            /// Retrieves a mutable reference to the value at the given index, if it exists and is a `U`.
+            ///
+            /// The value is flagged as modified only if it is a `U`.
+            ///
+            /// # Arguments
+            /// * `index` - The `ValueIndex` of the value to retrieve.
+            pub fn get_downcast_mut<U: 'static>(&mut self, index: &ValueIndex) -> Option<&mut U> {
+                let i = self.slot(&index.0)?;
+                Self::downcast_marked(&mut self.data[i])
+            }
+
+            /// Returns an iterator over the elements in the `Bucket` that are a `U`.
+            pub fn iter_downcast<U: 'static>(&self) -> impl Iterator<Item = &U> {
+                self.data.iter().filter_map(|v| (*v.data).downcast_ref())
+            }
+
+            /// Returns a mutable iterator over the elements in the `Bucket` that are a `U`.
+            pub fn iter_downcast_mut<U: 'static>(&mut self) -> impl Iterator<Item = &mut U> {
+                self.data.iter_mut().filter_map(Self::downcast_marked)
+            }
+
+            /// Downcasts the data of a value, flagging it as modified if it is a `U`.
+            fn downcast_marked<U: 'static>(value: &mut Value<Box<$t>>) -> Option<&mut U> {
+                if !(*value.data).is::<U>() {
+                    return None;
+                }
+                value.mark();
+                (*value.data).downcast_mut()
+            }
+        }
+    )*};
+}
+
+impl_downcast!(dyn Any, dyn Any + Send, dyn Any + Send + Sync);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32, i32);
+
+    #[test]
+    fn test_downcast() {
+        let mut bucket: Bucket<Box<dyn Any>> = Bucket::new(4);
+        let a = bucket.insert(Box::new(1u32));
+        let b = bucket.insert(Box::new(String::from("name")));
+        let c = bucket.insert(Box::new(Position(1, 2)));
+        let d = bucket.insert(Box::new(2u32));
+
+        assert_eq!(bucket.get_downcast_ref::<u32>(&a), Some(&1));
+        assert_eq!(bucket.get_downcast_ref::<String>(&b).unwrap(), "name");
+        assert_eq!(bucket.get_downcast_ref::<u32>(&b), None);
+        assert_eq!(bucket.get_downcast_ref::<Position>(&a), None);
+
+        bucket.get_downcast_mut::<Position>(&c).unwrap().0 = 10;
+        assert!(bucket.get_downcast_mut::<String>(&c).is_none());
+        assert_eq!(bucket.get_downcast_ref(&c), Some(&Position(10, 2)));
+
+        for v in bucket.iter_downcast_mut::<u32>() {
+            *v *= 3
+        }
+        let numbers: Vec<_> = bucket.iter_downcast::<u32>().collect();
+        assert_eq!(numbers, vec![&3, &6]);
+        assert_eq!(bucket.iter_downcast::<String>().count(), 1);
+        assert_eq!(bucket.iter_downcast::<u8>().count(), 0);
+
+        // Stale handles miss regardless of the type
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove(a);
+        assert_eq!(bucket.get_downcast_ref::<u32>(&stale), None);
+        assert_eq!(bucket.get_downcast_ref::<u32>(&d), Some(&6));
+    }
+
+    #[test]
+    fn test_downcast_send_sync() {
+        let mut bucket: Bucket<Box<dyn Any + Send + Sync>> = Bucket::new(2);
+        let a = bucket.insert(Box::new(1.5f64));
+        bucket.insert(Box::new("text"));
+
+        *bucket.get_downcast_mut::<f64>(&a).unwrap() += 1.0;
+        assert_eq!(bucket.get_downcast_ref::<f64>(&a), Some(&2.5));
+        assert_eq!(bucket.iter_downcast::<&str>().next(), Some(&"text"));
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    ops::{Bound, RangeBounds},
+    sync::atomic::Ordering,
+};
+
+use crate::{Bucket, DANGLING};
+
+impl<T> Bucket<T> {
+    /// Removes every value, returning them lazily in slot order.
+    ///
+    /// Once the iterator is dropped, even early, the `Bucket` is empty, its
+    /// capacity is back to the original one, as by [`Bucket::clear`], even if
+    /// automatic shrinking is disabled, and no handle to a drained value
+    /// resolves again. Otherwise, this is equivalent to `drain_slots(..)`.
+    pub fn drain(&mut self) -> DrainSlots<'_, T> {
+        let mut drain = self.drain_slots(..);
+        drain.reset = true;
+        drain
+    }
+
+    /// Removes the values in the given range of slots, returning them lazily in slot order.
+    ///
+    /// The values after the range are moved down to close the gap right away,
+    /// keeping their relative order and handles. The drained values are removed
+    /// even if the iterator is dropped early, after which the capacity shrinks
+    /// by every unused chunk, unless automatic shrinking is disabled.
+    ///
+    /// # Arguments
+    /// * `range` - The range of slots to drain.
+    ///
+    /// # Panics
+    /// Panics if the range starts after it ends, or ends after the last slot.
+    pub fn drain_slots<R: RangeBounds<usize>>(&mut self, range: R) -> DrainSlots<'_, T> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.checked_add(1).expect("range start overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.checked_add(1).expect("range end overflowed"),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end,
+            "slot range starts at {start} but ends at {end}"
+        );
+        assert!(
+            end <= len,
+            "slot range end {end} out of range for length {len}"
+        );
+
+        // Move the drained values behind the tail, in reverse so they are popped in slot order
+        self.data[start..].rotate_left(end - start);
+        self.data[len - (end - start)..].reverse();
+
+        // The drained values stay resolvable until popped, in case the iterator is leaked
+        for (i, value) in self.data.iter().enumerate().skip(start) {
+            value.index.store(i, Ordering::Relaxed)
+        }
+
+        DrainSlots {
+            bucket: self,
+            remaining: end - start,
+            reset: false,
+        }
+    }
+}
+
+/// A draining iterator over a range of slots of a `Bucket`, created by [`Bucket::drain_slots`].
+pub struct DrainSlots<'a, T> {
+    bucket: &'a mut Bucket<T>,
+    remaining: usize,
+    reset: bool,
+}
+
+impl<T> Iterator for DrainSlots<'_, T> {
+    type Item = T;
+
+    /// Removes and returns the next drained value, if any.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let value = self.bucket.data.pop()?;
+        value.index.store(DANGLING, Ordering::Relaxed);
+        self.bucket.persisted.forget(&value.index);
+        self.bucket.interned.forget(&value.index);
+        Some(value.data)
+    }
+
+    /// Returns the exact number of values left to drain.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for DrainSlots<'_, T> {}
+
+impl<T> Drop for DrainSlots<'_, T> {
+    /// Removes the values that were not yet drained and shrinks the `Bucket`.
+    fn drop(&mut self) {
+        self.for_each(drop);
+
+        match self.reset {
+            true => self.bucket.set_capacity(self.bucket.capacity.reset()),
+            false => self.bucket.shrink_unused(),
+        }
+        self.bucket.paranoid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Index, ValueIndex};
+
+    use super::*;
+
+    fn filled(n: usize) -> (Bucket<usize>, Vec<ValueIndex>) {
+        let mut bucket = Bucket::new(4);
+        let handles = (0..n).map(|i| bucket.insert(i)).collect();
+        (bucket, handles)
+    }
+
+    fn values(bucket: &Bucket<usize>) -> Vec<usize> {
+        bucket.data.iter().map(|v| v.data).collect()
+    }
+
+    #[test]
+    fn test_drain_middle() {
+        let (mut bucket, handles) = filled(10);
+
+        assert_eq!(bucket.drain_slots(2..5).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(values(&bucket), vec![0, 1, 5, 6, 7, 8, 9]);
+        assert_eq!(bucket.capacity(), 8);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        for (i, idx) in handles.iter().enumerate() {
+            match (2..5).contains(&i) {
+                true => assert!(!bucket.contains(idx)),
+                false => assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i),
+            }
+        }
+
+        assert_eq!(bucket.drain_slots(5..=6).collect::<Vec<_>>(), vec![8, 9]);
+        assert_eq!(values(&bucket), vec![0, 1, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_drain_full_and_empty() {
+        let (mut bucket, handles) = filled(10);
+
+        assert_eq!(bucket.drain_slots(3..3).len(), 0);
+        assert_eq!(bucket.drain_slots(10..).count(), 0);
+        assert_eq!(bucket.len(), 10);
+        assert!(handles.iter().all(|h| bucket.contains(h)));
+
+        assert_eq!(
+            bucket.drain_slots(..).collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 4);
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+
+        // New elements never resolve through drained handles
+        let idx = bucket.insert(10);
+        assert!(handles
+            .iter()
+            .all(|h| !Index::ptr_eq(&h.0, &idx.0) && !bucket.contains(h)));
+    }
+
+    #[test]
+    fn test_drain_dropped_early() {
+        let (mut bucket, handles) = filled(12);
+
+        let mut drain = bucket.drain_slots(1..9);
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.len(), 6);
+        drop(drain);
+
+        assert_eq!(values(&bucket), vec![0, 9, 10, 11]);
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.validate(), Ok(()));
+        assert_eq!(handles.iter().filter(|h| bucket.contains(h)).count(), 4);
+
+        // Leaking the iterator leaves the undrained values in the `Bucket`
+        std::mem::forget(bucket.drain_slots(1..3));
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_drain_all() {
+        let (mut bucket, handles) = filled(10);
+
+        assert_eq!(
+            bucket.drain().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 4);
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+
+        // Refilling never resolves the drained handles
+        let (mut bucket, handles) = filled(10);
+        let mut drain = bucket.drain();
+        assert_eq!(drain.next(), Some(0));
+        drop(drain);
+
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 4);
+        let refilled: Vec<_> = (0..10).map(|i| bucket.insert(i + 10)).collect();
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+        assert!(refilled.iter().all(|h| bucket.contains(h)));
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // The capacity is reset even without automatic shrinking
+        let (mut bucket, _) = filled(10);
+        bucket.set_auto_shrink(false);
+        assert_eq!(bucket.drain().count(), 10);
+        assert_eq!(bucket.capacity(), 4);
+        bucket.extend(0..10);
+        assert_eq!(bucket.drain_slots(..).count(), 10);
+        assert_eq!(bucket.capacity(), 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_drain_out_of_range() {
+        let (mut bucket, _) = filled(3);
+        bucket.drain_slots(1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "starts at 2 but ends at 1")]
+    fn test_drain_inverted_range() {
+        let (mut bucket, _) = filled(3);
+        #[allow(clippy::reversed_empty_ranges)]
+        bucket.drain_slots(2..1);
+    }
+}
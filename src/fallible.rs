@@ -0,0 +1,304 @@
+use std::{fmt, sync::atomic::Ordering};
+
+use crate::{Bucket, ValueIndex, DANGLING};
+
+/// Describes why a value could not be removed from a `Bucket`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoveError {
+    /// The handle was created by [`ValueIndex::dangling`], or its element was removed.
+    Dangling,
+
+    /// The handle refers to a slot past the last one.
+    OutOfBounds { slot: usize, len: usize },
+
+    /// The slot of the handle holds another element, as its own belongs to another `Bucket`.
+    Stale { slot: usize },
+}
+
+impl fmt::Display for RemoveError {
+    /// Formats the error as a human-readable report.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dangling => write!(f, "dangling handle: the handle refers to no element"),
+            Self::OutOfBounds { slot, len } => write!(
+                f,
+                "out of bounds: slot {slot} does not exist in a bucket of length {len}"
+            ),
+            Self::Stale { slot } => write!(
+                f,
+                "stale handle: slot {slot} holds another element than the one of the handle"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RemoveError {}
+
+impl<T> Bucket<T> {
+    /// Removes the value at the specified index, describing why it could not be removed otherwise.
+    ///
+    /// Unlike [`Bucket::remove`], the handle is only borrowed and the `Bucket`
+    /// is left untouched on error, with the same behavior regardless of the
+    /// enabled features.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn try_remove(&mut self, index: &ValueIndex) -> Result<T, RemoveError> {
+        let (slot, len) = (index.0.load(Ordering::Relaxed), self.len());
+
+        if slot == DANGLING {
+            return Err(RemoveError::Dangling);
+        }
+        if slot >= len {
+            return Err(RemoveError::OutOfBounds { slot, len });
+        }
+        self.remove_index(&index.0)
+            .ok_or(RemoveError::Stale { slot })
+    }
+
+    /// Calls `f` on every value in slot order, stopping at the first error.
+    ///
+    /// # Arguments
+    /// * `f` - The closure called on each value.
+    pub fn try_for_each<E>(&self, f: impl FnMut(&T) -> Result<(), E>) -> Result<(), E> {
+        self.data.iter().map(|v| &v.data).try_for_each(f)
+    }
+
+    /// Calls `f` on a mutable reference to every value in slot order, stopping at the first error.
+    ///
+    /// Every value passed to `f` counts as modified, including the one it failed on.
+    ///
+    /// # Arguments
+    /// * `f` - The closure called on each value.
+    pub fn try_for_each_mut<E>(
+        &mut self,
+        mut f: impl FnMut(&mut T) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.data.iter_mut().try_for_each(|v| {
+            v.mark();
+            f(&mut v.data)
+        })
+    }
+
+    /// Calls `f` on a mutable reference to the value at the specified index, returning whether it exists.
+    ///
+    /// The error of `f` is propagated, and any changes it made before failing are kept.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to update.
+    /// * `f` - The closure updating the value.
+    pub fn try_update<E>(
+        &mut self,
+        index: &ValueIndex,
+        f: impl FnOnce(&mut T) -> Result<(), E>,
+    ) -> Result<bool, E> {
+        let Some(i) = self.slot(&index.0) else {
+            return Ok(false);
+        };
+        self.data[i].mark();
+        f(&mut self.data[i].data)?;
+        Ok(true)
+    }
+
+    /// Keeps only the values for which `f` returns `Ok(true)`, returning how many were removed.
+    ///
+    /// Each removed value is replaced by the last one, which is visited next,
+    /// so every value is passed to `f` exactly once but not in slot order.
+    ///
+    /// If `f` fails, processing stops and the error is returned: the values
+    /// removed so far stay removed, the failing and unvisited values are kept,
+    /// and every handle keeps resolving to its value. In either case, the
+    /// capacity shrinks by every unused chunk.
+    ///
+    /// # Arguments
+    /// * `f` - The closure deciding whether each value is kept.
+    pub fn try_retain<E>(
+        &mut self,
+        mut f: impl FnMut(&mut T) -> Result<bool, E>,
+    ) -> Result<usize, E> {
+        let (mut i, mut removed) = (0, 0);
+
+        let result = loop {
+            let Some(value) = self.data.get_mut(i) else {
+                break Ok(removed);
+            };
+            value.mark();
+
+            match f(&mut value.data) {
+                Ok(true) => i += 1,
+                Ok(false) => {
+                    let value = self._swap_remove(i);
+                    drop(self.recycle(value));
+                    removed += 1;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        self.shrink_unused();
+        self.paranoid();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(n: usize) -> (Bucket<usize>, Vec<ValueIndex>) {
+        let mut bucket = Bucket::new(2);
+        let handles = (0..n).map(|i| bucket.insert(i)).collect();
+        (bucket, handles)
+    }
+
+    /// Asserts that every handle resolves to its value or to nothing at all.
+    fn assert_consistent(bucket: &Bucket<usize>, handles: &[ValueIndex], removed: &[usize]) {
+        assert_eq!(bucket.validate(), Ok(()));
+        for (i, idx) in handles.iter().enumerate() {
+            match bucket.slot(&idx.0) {
+                Some(slot) => assert_eq!(bucket.data[slot].data, i),
+                None => assert!(removed.contains(&i)),
+            }
+        }
+        assert_eq!(bucket.len(), handles.len() - removed.len());
+    }
+
+    #[test]
+    fn test_try_remove() {
+        let (mut bucket, handles) = filled(5);
+
+        // Relocated elements are removed through their own handles
+        assert_eq!(bucket.try_remove(&handles[0]), Ok(0));
+        assert_eq!(bucket.try_remove(&handles[4]), Ok(4));
+        assert_eq!(bucket.try_remove(&handles[2]), Ok(2));
+        assert_consistent(&bucket, &handles, &[0, 2, 4]);
+
+        // Removed elements leave their handles dangling
+        assert_eq!(bucket.try_remove(&handles[0]), Err(RemoveError::Dangling));
+        assert_eq!(bucket.try_remove(&handles[2]), Err(RemoveError::Dangling));
+        assert_eq!(
+            bucket.try_remove(&ValueIndex::dangling()),
+            Err(RemoveError::Dangling)
+        );
+
+        let mut other = Bucket::new(2);
+        other.insert(8);
+        other.insert(9);
+        assert_eq!(
+            other.try_remove(&handles[1]),
+            Err(RemoveError::Stale { slot: 1 })
+        );
+        assert_eq!(other.len(), 2);
+
+        let foreign = other.insert(10);
+        assert_eq!(
+            bucket.try_remove(&foreign),
+            Err(RemoveError::OutOfBounds { slot: 2, len: 2 })
+        );
+
+        bucket.clear();
+        assert_eq!(bucket.try_remove(&handles[1]), Err(RemoveError::Dangling));
+    }
+
+    #[test]
+    fn test_try_for_each() {
+        let (mut bucket, _) = filled(5);
+
+        for fail in [0, 2, 4] {
+            let mut seen = Vec::new();
+            let result = bucket.try_for_each(|&v| {
+                seen.push(v);
+                if v == fail {
+                    Err(v)
+                } else {
+                    Ok(())
+                }
+            });
+            assert_eq!(result, Err(fail));
+            assert_eq!(seen, (0..=fail).collect::<Vec<_>>());
+        }
+
+        assert_eq!(
+            bucket.try_for_each_mut(|v| {
+                if *v == 3 {
+                    return Err("three");
+                }
+                *v *= 10;
+                Ok(())
+            }),
+            Err("three")
+        );
+        assert_eq!(bucket.into_sorted_vec(), vec![0, 3, 4, 10, 20]);
+    }
+
+    #[test]
+    fn test_try_update() {
+        let (mut bucket, handles) = filled(3);
+
+        assert_eq!(
+            bucket.try_update(&handles[1], |v| {
+                *v += 5;
+                Ok::<_, ()>(())
+            }),
+            Ok(true)
+        );
+        assert_eq!(
+            bucket.try_update(&handles[2], |v| {
+                *v = 7;
+                Err("failed")
+            }),
+            Err("failed")
+        );
+
+        let stale = ValueIndex(handles[0].0.clone());
+        bucket.remove_index(&handles[0].0);
+        assert_eq!(bucket.try_update(&stale, |_| Err(())), Ok(false));
+        assert_eq!(bucket.into_sorted_vec(), vec![6, 7]);
+    }
+
+    #[test]
+    fn test_try_retain() {
+        let (mut bucket, handles) = filled(9);
+
+        assert_eq!(bucket.try_retain(|v| Ok::<_, ()>(*v % 3 != 0)), Ok(3));
+        assert_consistent(&bucket, &handles, &[0, 3, 6]);
+        assert_eq!(bucket.capacity(), 6);
+
+        // The shared cells no handle refers to anymore are reused, like `retain` does
+        drop(handles);
+        assert_eq!(bucket.try_retain(|v| Ok::<_, ()>(*v < 5)), Ok(3));
+        assert_eq!(bucket.free_cells.len(), 2);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_try_retain_errors() {
+        let keep = |v: &usize| *v % 2 == 1;
+
+        // Record the order in which a successful pass visits the values
+        let mut order = Vec::new();
+        filled(9)
+            .0
+            .try_retain(|v| {
+                order.push(*v);
+                Ok::<_, ()>(keep(v))
+            })
+            .unwrap();
+
+        // Fail on the first, a middle and the last visited value
+        for at in [0, order.len() / 2, order.len() - 1] {
+            let (mut bucket, handles) = filled(9);
+            let fail = order[at];
+
+            let result = bucket.try_retain(|v| match *v == fail {
+                true => Err(*v),
+                false => Ok(keep(v)),
+            });
+            assert_eq!(result, Err(fail));
+
+            let removed: Vec<_> = order[..at].iter().copied().filter(|v| !keep(v)).collect();
+            assert_consistent(&bucket, &handles, &removed);
+            assert!(bucket.contains(&handles[fail]));
+        }
+    }
+}
@@ -0,0 +1,378 @@
+//! C bindings for buckets of `u64` values and of byte buffers.
+//!
+//! Every function catches panics at the boundary, and handles are validated
+//! against the bucket on every use, so a stale handle is reported rather
+//! than trusted.
+
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{Bucket, ValueIndex};
+
+/// The outcome of a fallible FFI call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnebuckStatus {
+    /// The call succeeded.
+    Ok = 0,
+
+    /// A required pointer argument was null.
+    NullPointer = 1,
+
+    /// The handle does not refer to an element of the bucket.
+    StaleHandle = 2,
+
+    /// The call panicked and was aborted at the boundary.
+    Panic = 3,
+}
+
+/// An opaque bucket of `u64` values.
+pub struct OnebuckU64(Bucket<u64>);
+
+/// An opaque bucket of byte buffers.
+pub struct OnebuckBytes(Bucket<Vec<u8>>);
+
+/// An opaque, reference-counted handle to an element of a bucket.
+pub struct OnebuckHandle(ValueIndex);
+
+/// Runs `f`, returning `default` if it panics.
+fn guard<R>(default: R, f: impl FnOnce() -> R) -> R {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+/// Runs `f`, reporting a panic as `OnebuckStatus::Panic`.
+fn guard_status(f: impl FnOnce() -> OnebuckStatus) -> OnebuckStatus {
+    guard(OnebuckStatus::Panic, f)
+}
+
+/// Moves a handle onto the heap, returning an owning pointer to it.
+fn into_raw(index: ValueIndex) -> *mut OnebuckHandle {
+    Box::into_raw(Box::new(OnebuckHandle(index)))
+}
+
+/// Creates a new bucket of `u64` values, or returns null on failure.
+#[no_mangle]
+pub extern "C" fn onebuck_u64_new(capacity: usize) -> *mut OnebuckU64 {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(OnebuckU64(Bucket::new(capacity))))
+    })
+}
+
+/// Frees a bucket of `u64` values. Outstanding handles must still be dropped.
+///
+/// # Safety
+/// `bucket` must be null or a pointer returned by `onebuck_u64_new` that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_u64_free(bucket: *mut OnebuckU64) {
+    if !bucket.is_null() {
+        guard((), || drop(Box::from_raw(bucket)))
+    }
+}
+
+/// Returns the number of values in the bucket, or zero if it is null.
+///
+/// # Safety
+/// `bucket` must be null or a valid pointer returned by `onebuck_u64_new`.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_u64_len(bucket: *const OnebuckU64) -> usize {
+    bucket.as_ref().map_or(0, |b| b.0.len())
+}
+
+/// Inserts a value, returning a new handle to it, or null on failure.
+///
+/// # Safety
+/// `bucket` must be null or a valid pointer returned by `onebuck_u64_new`.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_u64_insert(
+    bucket: *mut OnebuckU64,
+    value: u64,
+) -> *mut OnebuckHandle {
+    let Some(bucket) = bucket.as_mut() else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || into_raw(bucket.0.insert(value)))
+}
+
+/// Writes the value referenced by `handle` into `out`.
+///
+/// # Safety
+/// `bucket` and `handle` must be null or valid pointers obtained from this
+/// module, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_u64_get(
+    bucket: *const OnebuckU64,
+    handle: *const OnebuckHandle,
+    out: *mut u64,
+) -> OnebuckStatus {
+    let (Some(bucket), Some(handle), false) = (bucket.as_ref(), handle.as_ref(), out.is_null())
+    else {
+        return OnebuckStatus::NullPointer;
+    };
+    guard_status(|| match bucket.0.slot(&handle.0 .0) {
+        Some(i) => {
+            out.write(bucket.0.data[i].data);
+            OnebuckStatus::Ok
+        }
+        None => OnebuckStatus::StaleHandle,
+    })
+}
+
+/// Removes the value referenced by `handle`, writing it into `out` unless it is null.
+///
+/// The handle itself remains owned by the caller and must still be dropped.
+///
+/// # Safety
+/// `bucket` and `handle` must be null or valid pointers obtained from this
+/// module, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_u64_remove(
+    bucket: *mut OnebuckU64,
+    handle: *const OnebuckHandle,
+    out: *mut u64,
+) -> OnebuckStatus {
+    let (Some(bucket), Some(handle)) = (bucket.as_mut(), handle.as_ref()) else {
+        return OnebuckStatus::NullPointer;
+    };
+    guard_status(|| match bucket.0.remove_index(&handle.0 .0) {
+        Some(value) => {
+            if !out.is_null() {
+                out.write(value)
+            }
+            OnebuckStatus::Ok
+        }
+        None => OnebuckStatus::StaleHandle,
+    })
+}
+
+/// Creates a new bucket of byte buffers, or returns null on failure.
+#[no_mangle]
+pub extern "C" fn onebuck_bytes_new(capacity: usize) -> *mut OnebuckBytes {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(OnebuckBytes(Bucket::new(capacity))))
+    })
+}
+
+/// Frees a bucket of byte buffers. Outstanding handles must still be dropped.
+///
+/// # Safety
+/// `bucket` must be null or a pointer returned by `onebuck_bytes_new` that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_bytes_free(bucket: *mut OnebuckBytes) {
+    if !bucket.is_null() {
+        guard((), || drop(Box::from_raw(bucket)))
+    }
+}
+
+/// Returns the number of buffers in the bucket, or zero if it is null.
+///
+/// # Safety
+/// `bucket` must be null or a valid pointer returned by `onebuck_bytes_new`.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_bytes_len(bucket: *const OnebuckBytes) -> usize {
+    bucket.as_ref().map_or(0, |b| b.0.len())
+}
+
+/// Copies `len` bytes into a new buffer, returning a new handle to it, or null on failure.
+///
+/// # Safety
+/// `bucket` must be null or a valid pointer returned by `onebuck_bytes_new`,
+/// and `data` must be valid for reads of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_bytes_insert(
+    bucket: *mut OnebuckBytes,
+    data: *const u8,
+    len: usize,
+) -> *mut OnebuckHandle {
+    let Some(bucket) = bucket.as_mut() else {
+        return ptr::null_mut();
+    };
+    if data.is_null() && len > 0 {
+        return ptr::null_mut();
+    }
+    guard(ptr::null_mut(), || {
+        let buffer = match len {
+            0 => Vec::new(),
+            _ => slice::from_raw_parts(data, len).to_vec(),
+        };
+        into_raw(bucket.0.insert(buffer))
+    })
+}
+
+/// Writes a pointer to the buffer referenced by `handle` and its length into `data` and `len`.
+///
+/// The pointer remains valid until the bucket is next mutated or freed.
+///
+/// # Safety
+/// `bucket` and `handle` must be null or valid pointers obtained from this
+/// module, and `data` and `len` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_bytes_get(
+    bucket: *const OnebuckBytes,
+    handle: *const OnebuckHandle,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> OnebuckStatus {
+    let (Some(bucket), Some(handle), false) = (
+        bucket.as_ref(),
+        handle.as_ref(),
+        data.is_null() || len.is_null(),
+    ) else {
+        return OnebuckStatus::NullPointer;
+    };
+    guard_status(|| match bucket.0.slot(&handle.0 .0) {
+        Some(i) => {
+            let buffer = &bucket.0.data[i].data;
+            data.write(buffer.as_ptr());
+            len.write(buffer.len());
+            OnebuckStatus::Ok
+        }
+        None => OnebuckStatus::StaleHandle,
+    })
+}
+
+/// Removes and frees the buffer referenced by `handle`.
+///
+/// The handle itself remains owned by the caller and must still be dropped.
+///
+/// # Safety
+/// `bucket` and `handle` must be null or valid pointers obtained from this module.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_bytes_remove(
+    bucket: *mut OnebuckBytes,
+    handle: *const OnebuckHandle,
+) -> OnebuckStatus {
+    let (Some(bucket), Some(handle)) = (bucket.as_mut(), handle.as_ref()) else {
+        return OnebuckStatus::NullPointer;
+    };
+    guard_status(|| match bucket.0.remove_index(&handle.0 .0) {
+        Some(_) => OnebuckStatus::Ok,
+        None => OnebuckStatus::StaleHandle,
+    })
+}
+
+/// Returns a new handle referring to the same element, or null on failure.
+///
+/// # Safety
+/// `handle` must be null or a valid pointer obtained from this module.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_handle_clone(handle: *const OnebuckHandle) -> *mut OnebuckHandle {
+    let Some(handle) = handle.as_ref() else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || {
+        into_raw(ValueIndex(handle.0 .0.clone()))
+    })
+}
+
+/// Drops a handle.
+///
+/// # Safety
+/// `handle` must be null or a pointer obtained from this module that was not dropped.
+#[no_mangle]
+pub unsafe extern "C" fn onebuck_handle_drop(handle: *mut OnebuckHandle) {
+    if !handle.is_null() {
+        guard((), || drop(Box::from_raw(handle)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u64() {
+        unsafe {
+            let bucket = onebuck_u64_new(2);
+            let a = onebuck_u64_insert(bucket, 10);
+            let b = onebuck_u64_insert(bucket, 20);
+            let c = onebuck_u64_insert(bucket, 30);
+            assert_eq!(onebuck_u64_len(bucket), 3);
+
+            let a_clone = onebuck_handle_clone(a);
+            let mut out = 0;
+            assert_eq!(onebuck_u64_remove(bucket, a, &mut out), OnebuckStatus::Ok);
+            assert_eq!(out, 10);
+
+            // The relocated element still resolves, the removed one does not
+            assert_eq!(onebuck_u64_get(bucket, c, &mut out), OnebuckStatus::Ok);
+            assert_eq!(out, 30);
+            assert_eq!(
+                onebuck_u64_get(bucket, a_clone, &mut out),
+                OnebuckStatus::StaleHandle
+            );
+            assert_eq!(
+                onebuck_u64_remove(bucket, a_clone, ptr::null_mut()),
+                OnebuckStatus::StaleHandle
+            );
+            assert_eq!(
+                onebuck_u64_get(bucket, b, ptr::null_mut()),
+                OnebuckStatus::NullPointer
+            );
+            assert_eq!(
+                onebuck_u64_get(ptr::null(), b, &mut out),
+                OnebuckStatus::NullPointer
+            );
+            assert!(onebuck_u64_insert(ptr::null_mut(), 1).is_null());
+
+            for handle in [a, a_clone, b, c] {
+                onebuck_handle_drop(handle)
+            }
+            onebuck_u64_free(bucket);
+            onebuck_u64_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_handles_outlive_bucket() {
+        unsafe {
+            let first = onebuck_u64_new(2);
+            let handle = onebuck_u64_insert(first, 1);
+            onebuck_u64_free(first);
+
+            // A handle from another bucket is rejected rather than trusted
+            let second = onebuck_u64_new(2);
+            onebuck_handle_drop(onebuck_u64_insert(second, 2));
+            let mut out = 0;
+            assert_eq!(
+                onebuck_u64_get(second, handle, &mut out),
+                OnebuckStatus::StaleHandle
+            );
+
+            onebuck_handle_drop(handle);
+            onebuck_u64_free(second);
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        unsafe {
+            let bucket = onebuck_bytes_new(4);
+            let a = onebuck_bytes_insert(bucket, b"hello".as_ptr(), 5);
+            let b = onebuck_bytes_insert(bucket, ptr::null(), 0);
+            assert!(onebuck_bytes_insert(bucket, ptr::null(), 3).is_null());
+            assert_eq!(onebuck_bytes_len(bucket), 2);
+
+            let (mut data, mut len) = (ptr::null(), 0);
+            assert_eq!(
+                onebuck_bytes_get(bucket, a, &mut data, &mut len),
+                OnebuckStatus::Ok
+            );
+            assert_eq!(slice::from_raw_parts(data, len), b"hello");
+            assert_eq!(
+                onebuck_bytes_get(bucket, b, &mut data, &mut len),
+                OnebuckStatus::Ok
+            );
+            assert_eq!(len, 0);
+
+            assert_eq!(onebuck_bytes_remove(bucket, a), OnebuckStatus::Ok);
+            assert_eq!(onebuck_bytes_remove(bucket, a), OnebuckStatus::StaleHandle);
+            assert_eq!(onebuck_bytes_len(bucket), 1);
+
+            onebuck_handle_drop(a);
+            onebuck_handle_drop(b);
+            onebuck_bytes_free(bucket);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use core::sync::atomic::Ordering;
+
+use defmt::{write, Format, Formatter};
+
+use crate::{Bucket, Index, Value, ValueIndex, ValueRef};
+
+/// The number of leading elements included in the `defmt` summary of a `Bucket`.
+const SUMMARY_LEN: usize = 3;
+
+impl Format for ValueIndex {
+    /// Formats the slot of the handle and the number of handles sharing its cell.
+    fn format(&self, f: Formatter<'_>) {
+        write!(
+            f,
+            "ValueIndex {{ slot: {=usize}, handles: {=usize} }}",
+            self.0.load(Ordering::Relaxed),
+            Index::strong_count(&self.0)
+        )
+    }
+}
+
+impl<T: Format> Format for Value<T> {
+    /// Formats the underlying data.
+    fn format(&self, f: Formatter<'_>) {
+        self.data.format(f)
+    }
+}
+
+impl<T: Format> Format for ValueRef<'_, T> {
+    /// Formats the referenced data.
+    fn format(&self, f: Formatter<'_>) {
+        self.data.format(f)
+    }
+}
+
+impl<T: Format> Format for Bucket<T> {
+    /// Formats the length and capacity of the bucket together with its first few values.
+    fn format(&self, f: Formatter<'_>) {
+        let n = self.len().min(SUMMARY_LEN);
+        let more = match self.len() > n {
+            true => ", ..",
+            false => "",
+        };
+
+        write!(
+            f,
+            "Bucket {{ len: {=usize}, capacity: {=usize}, data: {=[?]}{=str} }}",
+            self.len(),
+            self.capacity(),
+            &self.data[..n],
+            more
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_format<T: Format + ?Sized>(_: &T) {}
+
+    #[test]
+    fn test_format_impls() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert(1u32);
+        bucket.insert(2);
+
+        assert_format(&bucket);
+        assert_format(&idx);
+        assert_format(&bucket.data[0]);
+        let value_ref: ValueRef<'_, u32> = (&bucket.data[1]).into();
+        assert_format(&value_ref);
+        assert_format(&Bucket::<Bucket<u8>>::new(1));
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Debug;
+
+use crate::Bucket;
+
+/// The reason the logical capacity of a `Bucket` changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapacityChangeReason {
+    /// The `Bucket` grew to make room for new elements.
+    Grow,
+
+    /// The `Bucket` shrank after an entire chunk became unused.
+    Shrink,
+
+    /// Capacity was reserved up front, or a previously recorded, larger capacity was restored.
+    Reserve,
+
+    /// The capacity was shrunk on request, or a previously recorded, smaller capacity was restored.
+    ShrinkToFit,
+}
+
+/// A change of the logical capacity of a `Bucket`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityChange {
+    /// The capacity before the change.
+    pub old: usize,
+
+    /// The capacity after the change.
+    pub new: usize,
+
+    /// The reason for the change.
+    pub reason: CapacityChangeReason,
+}
+
+#[cfg(not(feature = "atomic"))]
+type Hook = Box<dyn FnMut(CapacityChange)>;
+
+#[cfg(feature = "atomic")]
+type Hook = Box<dyn FnMut(CapacityChange) + Send>;
+
+/// The callback registered through [`Bucket::set_on_capacity_change`].
+pub(crate) struct CapacityHook(Hook);
+
+// SAFETY: the callback is only ever reached through `&mut Bucket`, so sharing
+// a `&Bucket` across threads never gives access to it
+#[cfg(feature = "atomic")]
+unsafe impl Sync for CapacityHook {}
+
+impl Debug for CapacityHook {
+    /// Formats the hook for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CapacityHook")
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Registers a callback invoked whenever the capacity of the `Bucket` changes.
+    ///
+    /// The callback runs after the backing storage has been adjusted, and
+    /// replaces any previously registered one.
+    ///
+    /// # Arguments
+    /// * `f` - The callback receiving each `CapacityChange`.
+    #[cfg(not(feature = "atomic"))]
+    pub fn set_on_capacity_change(&mut self, f: impl FnMut(CapacityChange) + 'static) {
+        self.hook = Some(CapacityHook(Box::new(f)))
+    }
+
+    /// Registers a callback invoked whenever the capacity of the `Bucket` changes.
+    ///
+    /// The callback runs after the backing storage has been adjusted, and
+    /// replaces any previously registered one.
+    ///
+    /// # Arguments
+    /// * `f` - The callback receiving each `CapacityChange`.
+    #[cfg(feature = "atomic")]
+    pub fn set_on_capacity_change(&mut self, f: impl FnMut(CapacityChange) + Send + 'static) {
+        self.hook = Some(CapacityHook(Box::new(f)))
+    }
+
+    /// Removes the callback registered through [`Bucket::set_on_capacity_change`], if any.
+    pub fn clear_on_capacity_change(&mut self) {
+        self.hook = None
+    }
+
+    /// Notifies the registered callback if the capacity differs from `old`.
+    ///
+    /// # Arguments
+    /// * `old` - The capacity before the change.
+    /// * `reason` - The reason for the change.
+    pub(crate) fn capacity_changed(&mut self, old: usize, reason: CapacityChangeReason) {
+        let new = self.capacity.current;
+
+        if let (Some(hook), true) = (&mut self.hook, old != new) {
+            (hook.0)(CapacityChange { old, new, reason })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Records every capacity change of `bucket` into the returned log.
+    fn record<T>(bucket: &mut Bucket<T>) -> Arc<Mutex<Vec<(usize, usize, CapacityChangeReason)>>> {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let events = log.clone();
+        bucket
+            .set_on_capacity_change(move |c| events.lock().unwrap().push((c.old, c.new, c.reason)));
+        log
+    }
+
+    #[test]
+    fn test_capacity_change_events() {
+        use CapacityChangeReason::*;
+
+        let mut bucket = Bucket::new(2);
+        let log = record(&mut bucket);
+
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        for idx in &handles[..3] {
+            assert!(bucket.remove_index(&idx.0).is_some());
+        }
+        let mut src = Bucket::new(4);
+        (5..9).for_each(|i| drop(src.insert(i)));
+        bucket.extend_from_bucket(&src);
+        bucket.clear();
+        bucket.clear();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                (2, 4, Grow),
+                (4, 6, Grow),
+                (6, 4, Shrink),
+                (4, 2, Shrink),
+                (2, 6, Grow),
+                (6, 2, ShrinkToFit),
+            ]
+        );
+        assert_eq!(bucket.capacity(), 2);
+    }
+
+    #[test]
+    fn test_capacity_change_restore() {
+        use CapacityChangeReason::*;
+
+        let mut bucket = Bucket::new(1);
+        bucket.insert(1);
+        let cp = bucket.checkpoint();
+        bucket.insert(2);
+        bucket.insert(3);
+
+        let log = record(&mut bucket);
+        bucket.restore(&cp);
+        bucket.insert(2);
+        bucket.restore(&cp);
+        bucket.clear_on_capacity_change();
+        bucket.insert(2);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![(3, 1, ShrinkToFit), (1, 2, Grow), (2, 1, ShrinkToFit)]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, DefaultHasher, Hash, Hasher},
+};
+
+use crate::{Bucket, Index, Value, ValueIndex};
+
+type Map<K, V> = HashMap<K, V, BuildHasherDefault<DefaultHasher>>;
+
+/// An interned element together with the hash it was interned under and the number of times it was interned.
+#[derive(Clone, Debug)]
+pub(crate) struct Interned {
+    index: Index,
+    hash: u64,
+    refs: usize,
+}
+
+/// The interned elements of a `Bucket`, keyed both by shared cell and by value hash.
+///
+/// Entries are found by the address of their shared cell, so that mutating
+/// an interned value, which changes its hash, does not lose its entry.
+#[derive(Debug, Default)]
+struct Tables {
+    cells: Map<usize, Interned>,
+    hashes: Map<u64, Vec<usize>>,
+}
+
+/// The intern table of a `Bucket`, allocated on first use.
+#[derive(Debug)]
+pub(crate) struct InternTable(Option<Box<Tables>>);
+
+/// Returns the address of a shared cell, which identifies its element.
+fn address(index: &Index) -> usize {
+    Index::as_ptr(index) as usize
+}
+
+/// Hashes a value for the intern table.
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl InternTable {
+    /// Creates an empty, unallocated table.
+    pub(crate) const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Adds an entry for an element.
+    ///
+    /// # Arguments
+    /// * `entry` - The entry of the element.
+    pub(crate) fn insert(&mut self, entry: Interned) {
+        let tables = self.0.get_or_insert_with(Box::default);
+        let address = address(&entry.index);
+        tables.hashes.entry(entry.hash).or_default().push(address);
+        tables.cells.insert(address, entry);
+    }
+
+    /// Returns a copy of the entry of an element, if it was interned.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the element.
+    pub(crate) fn entry(&self, index: &Index) -> Option<Interned> {
+        self.0.as_ref()?.cells.get(&address(index)).cloned()
+    }
+
+    /// Returns the shared cells of the elements interned under a hash.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the value.
+    fn lookup(&self, hash: u64) -> impl Iterator<Item = &Index> {
+        self.0.iter().flat_map(move |tables| {
+            let addresses = tables.hashes.get(&hash).map_or(&[][..], Vec::as_slice);
+            addresses.iter().map(|a| &tables.cells[a].index)
+        })
+    }
+
+    /// Adds a reference to an interned element.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the element.
+    fn acquire(&mut self, index: &Index) {
+        if let Some(entry) = self.entry_mut(index) {
+            entry.refs += 1
+        }
+    }
+
+    /// Drops a reference to an interned element, returning the number of references left.
+    ///
+    /// Returns `None` if the element was not interned.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the element.
+    fn unref(&mut self, index: &Index) -> Option<usize> {
+        let entry = self.entry_mut(index)?;
+        entry.refs -= 1;
+        Some(entry.refs)
+    }
+
+    /// Returns the entry of an element, if it was interned.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the element.
+    fn entry_mut(&mut self, index: &Index) -> Option<&mut Interned> {
+        self.0.as_mut()?.cells.get_mut(&address(index))
+    }
+
+    /// Forgets the entry of a removed element, if it has one.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the removed element.
+    pub(crate) fn forget(&mut self, index: &Index) {
+        let Some(tables) = &mut self.0 else {
+            return;
+        };
+        let address = address(index);
+        let Some(entry) = tables.cells.remove(&address) else {
+            return;
+        };
+
+        if let Some(addresses) = tables.hashes.get_mut(&entry.hash) {
+            addresses.retain(|&a| a != address);
+            if addresses.is_empty() {
+                tables.hashes.remove(&entry.hash);
+            }
+        }
+    }
+
+    /// Forgets every entry.
+    pub(crate) fn clear(&mut self) {
+        if let Some(tables) = &mut self.0 {
+            tables.cells.clear();
+            tables.hashes.clear();
+        }
+    }
+
+    /// Returns the shared cells of the interned elements.
+    pub(crate) fn cells(&self) -> impl Iterator<Item = &Index> {
+        self.0
+            .iter()
+            .flat_map(|t| t.cells.values().map(|e| &e.index))
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Copies the intern table over to a clone of the elements of this `Bucket`.
+    ///
+    /// # Arguments
+    /// * `data` - The elements of the clone, in the slot order of this `Bucket`.
+    pub(crate) fn clone_interned(&self, data: &[Value<T>]) -> InternTable {
+        remap_interned(&self.interned, &self.data, data)
+    }
+}
+
+/// Copies an intern table over to other elements, matched by slot.
+///
+/// Entries whose element is not among `from` are dropped.
+///
+/// # Arguments
+/// * `table` - The intern table referring to the elements of `from`.
+/// * `from` - The elements the table refers to, in slot order.
+/// * `to` - The elements to refer to instead, in the same order.
+pub(crate) fn remap_interned<T>(
+    table: &InternTable,
+    from: &[Value<T>],
+    to: &[Value<T>],
+) -> InternTable {
+    let mut remapped = InternTable::new();
+
+    for (i, value) in from.iter().enumerate() {
+        if let Some(entry) = table.entry(&value.index) {
+            remapped.insert(Interned {
+                index: to[i].index.clone(),
+                ..entry
+            });
+        }
+    }
+    remapped
+}
+
+impl<T: Hash + Eq> Bucket<T> {
+    /// Inserts a value unless an equal one was already interned, returning a handle to the stored one.
+    ///
+    /// Every call counts as one reference to the element, which is removed
+    /// once [`Bucket::release`] was called as many times.
+    ///
+    /// # Arguments
+    /// * `value` - The value to intern.
+    pub fn intern(&mut self, value: T) -> ValueIndex {
+        let hash = hash_of(&value);
+        let existing = self
+            .interned
+            .lookup(hash)
+            .find(|index| self.slot(index).is_some_and(|i| self.data[i].data == value));
+
+        if let Some(index) = existing.cloned() {
+            self.interned.acquire(&index);
+            return ValueIndex(index);
+        }
+
+        let index = self.insert(value);
+        self.interned.insert(Interned {
+            index: index.0.clone(),
+            hash,
+            refs: 1,
+        });
+        index
+    }
+
+    /// Drops one reference to an interned element, removing and returning it at the last one.
+    ///
+    /// Returns `None` while the element is still referenced, or if it was not interned.
+    /// The element is found by its handle, so it can be released even after
+    /// its value was modified.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the interned element.
+    pub fn release(&mut self, index: &ValueIndex) -> Option<T> {
+        self.slot(&index.0)?;
+        match self.interned.unref(&index.0)? {
+            0 => self.remove_index(&index.0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A value whose hashes always collide.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Colliding(&'static str);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u8(0)
+        }
+    }
+
+    /// Asserts that a table holds no entry under any key.
+    fn assert_empty(table: &InternTable) {
+        let tables = table.0.as_ref().unwrap();
+        assert!(tables.cells.is_empty() && tables.hashes.is_empty());
+    }
+
+    #[test]
+    fn test_intern_and_release() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..5).map(|_| bucket.intern(String::from("foo"))).collect();
+        let bar = bucket.intern(String::from("bar"));
+
+        assert_eq!(bucket.len(), 2);
+        assert!(handles.iter().all(|h| Index::ptr_eq(&h.0, &handles[0].0)));
+        assert!(!Index::ptr_eq(&bar.0, &handles[0].0));
+
+        for h in &handles[1..] {
+            assert_eq!(bucket.release(h), None);
+        }
+        assert_eq!(bucket.release(&handles[0]).as_deref(), Some("foo"));
+        assert_eq!(bucket.release(&handles[0]), None);
+        assert_eq!(bucket.len(), 1);
+
+        // A released value is interned afresh
+        let foo = bucket.intern(String::from("foo"));
+        assert!(!Index::ptr_eq(&foo.0, &handles[0].0));
+        assert_eq!(bucket.release(&bar).as_deref(), Some("bar"));
+        assert!(bucket.contains(&foo));
+    }
+
+    #[test]
+    fn test_intern_collisions_and_removal() {
+        let mut bucket = Bucket::new(4);
+        let a = bucket.intern(Colliding("a"));
+        let b = bucket.intern(Colliding("b"));
+        assert!(Index::ptr_eq(&bucket.intern(Colliding("a")).0, &a.0));
+        assert_eq!(bucket.interned.0.as_ref().unwrap().hashes.len(), 1);
+
+        // An element removed through other means is forgotten right away
+        assert_eq!(bucket.remove_index(&a.0), Some(Colliding("a")));
+        assert_eq!(bucket.interned.cells().count(), 1);
+        let c = bucket.intern(Colliding("a"));
+        assert!(!Index::ptr_eq(&c.0, &a.0));
+        assert_eq!(
+            bucket.interned.0.as_ref().unwrap().hashes[&hash_of(&Colliding("a"))].len(),
+            2
+        );
+
+        assert_eq!(bucket.release(&b), Some(Colliding("b")));
+        assert_eq!(bucket.release(&c), Some(Colliding("a")));
+        assert_empty(&bucket.interned);
+
+        bucket.insert(Colliding("d"));
+        assert_eq!(bucket.release(&bucket.data[0].index()), None);
+    }
+
+    #[test]
+    fn test_release_after_mutation() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern(String::from("a"));
+        bucket.intern(String::from("a"));
+        bucket.replace(&a, String::from("a!"));
+
+        assert_eq!(bucket.release(&a), None);
+        assert_eq!(bucket.release(&a).as_deref(), Some("a!"));
+        assert_empty(&bucket.interned);
+    }
+
+    #[test]
+    fn test_remove_interned() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern(1);
+        bucket.intern(2);
+        bucket.intern(3);
+
+        assert_eq!(bucket.remove(a), Some(1));
+        bucket.retain(|&v| v != 2);
+        assert_eq!(bucket.pop(), Some(3));
+        assert_empty(&bucket.interned);
+
+        // The cells are no longer held by the table, so they are recycled
+        assert_eq!(bucket.free_cells.len(), 2);
+    }
+}
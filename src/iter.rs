@@ -0,0 +1,247 @@
+use std::slice;
+
+use crate::{Bucket, Value, ValueRef, ValueRefMut};
+
+/// An iterator over the values of a `Bucket`, in slot order, created by [`Bucket::iter`].
+pub struct Iter<'a, T>(pub(crate) slice::Iter<'a, Value<T>>);
+
+/// A mutable iterator over the values of a `Bucket`, in slot order, created by [`Bucket::iter_mut`].
+pub struct IterMut<'a, T>(pub(crate) slice::IterMut<'a, Value<T>>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = ValueRef<'a, T>;
+
+    /// Returns the next value, if any.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Into::into)
+    }
+
+    /// Returns the exact number of values left.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = ValueRefMut<'a, T>;
+
+    /// Returns the next value, flagging it as modified, if any.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Into::into)
+    }
+
+    /// Returns the exact number of values left.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a Bucket<T> {
+    type Item = <Iter<'a, T> as Iterator>::Item;
+    type IntoIter = Iter<'a, T>;
+
+    /// Returns an iterator over the values in the `Bucket`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Bucket<T> {
+    type Item = <IterMut<'a, T> as Iterator>::Item;
+    type IntoIter = IterMut<'a, T>;
+
+    /// Returns a mutable iterator over the values in the `Bucket`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the values of a `Bucket`, in slot order.
+///
+/// The values that are not yielded are dropped together with the iterator.
+pub struct IntoIter<T>(std::vec::IntoIter<Value<T>>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    /// Returns the next value, if any.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| v.data)
+    }
+
+    /// Returns the exact number of values left.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    /// Returns the last remaining value, if any.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|v| v.data)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for Bucket<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the `Bucket`, returning its values in slot order.
+    fn into_iter(mut self) -> Self::IntoIter {
+        IntoIter(std::mem::take(&mut self.data).into_iter())
+    }
+}
+
+impl<T> FromIterator<T> for Bucket<T> {
+    /// Creates a `Bucket` holding the values of the iterator, in slot order.
+    ///
+    /// The initial capacity is the lower bound of the size hint of the
+    /// iterator, or the default one if the lower bound is zero.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut bucket = match iter.size_hint().0 {
+            0 => Self::default(),
+            n => Self::new(n),
+        };
+        bucket.extend(iter);
+        bucket
+    }
+}
+
+impl<T> Extend<T> for Bucket<T> {
+    /// Inserts every value of the iterator, dropping their handles.
+    ///
+    /// Capacity for the lower bound of the size hint of the iterator is
+    /// reserved up front, in whole steps of the growth policy.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        if !self.overwrite {
+            self.grow_to(self.len() + iter.size_hint().0);
+        }
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Borrow,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_into_iter() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[0].0);
+
+        let iter = bucket.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![4, 1, 2, 3]);
+
+        let mut bucket = Bucket::new(2);
+        bucket.insert('a');
+        bucket.insert('b');
+        assert_eq!(bucket.into_iter().rev().collect::<String>(), "ba");
+    }
+
+    #[test]
+    fn test_borrowing_loops() {
+        fn count(values: impl IntoIterator) -> usize {
+            values.into_iter().count()
+        }
+
+        let mut bucket = Bucket::new(2);
+        for i in 1..=5 {
+            bucket.insert(i);
+        }
+
+        #[allow(unused_mut)]
+        for mut v in &mut bucket {
+            *v *= 10;
+        }
+
+        let mut seen = Vec::new();
+        for v in &bucket {
+            seen.push(*Borrow::<i32>::borrow(&v));
+        }
+        seen.sort();
+        assert_eq!(seen, vec![10, 20, 30, 40, 50]);
+        assert_eq!(count(&bucket), 5);
+        assert_eq!(count(&mut bucket), 5);
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let bucket: Bucket<_> = (0..1000).collect();
+        assert_eq!(bucket.len(), 1000);
+        assert_eq!(bucket.capacity(), 1000);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // Without a size hint, the default capacity is used
+        let mut bucket: Bucket<_> = (0..1000).filter(|i| i % 2 == 0).collect();
+        assert_eq!(bucket.len(), 500);
+        assert_eq!(bucket.capacity(), 512);
+
+        let idx = bucket.insert(-1);
+        assert!(bucket.contains(&idx));
+        assert_eq!(bucket.as_entries()[idx.0.load(Ordering::Relaxed)].data, -1);
+        bucket.remove_index(&idx.0);
+        assert_eq!(bucket.len(), 500);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut bucket = Bucket::new(8);
+        let idx = bucket.insert(0);
+        let changes = Arc::new(AtomicUsize::new(0));
+        let counter = changes.clone();
+        bucket.set_on_capacity_change(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        // The capacity grows once, in whole chunks
+        bucket.extend(1..100);
+        assert_eq!(bucket.len(), 100);
+        assert_eq!(bucket.capacity(), 104);
+        assert_eq!(changes.load(Ordering::Relaxed), 1);
+        assert!(bucket.contains(&idx));
+        assert_eq!(bucket.validate(), Ok(()));
+
+        let mut bounded = Bucket::bounded_overwriting(3);
+        bounded.extend(0..10);
+        assert_eq!(bounded.into_sorted_vec(), vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_into_iter_dropped_early() {
+        let counter = Rc::new(());
+        let mut bucket = Bucket::new(2);
+        for _ in 0..5 {
+            bucket.insert(counter.clone());
+        }
+
+        let mut iter = bucket.into_iter();
+        let first = iter.next().unwrap();
+        assert_eq!(iter.len(), 4);
+        drop(iter);
+
+        assert_eq!(Rc::strong_count(&counter), 2);
+        drop(first);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
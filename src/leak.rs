@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{Bucket, Index};
+
+impl<T> Bucket<T> {
+    /// Returns the number of handles that still exist outside of the `Bucket`.
+    ///
+    /// The references kept by the `Bucket` itself, such as those of its
+    /// persistent identifiers and interned values, are not counted.
+    pub fn outstanding_handles(&self) -> usize {
+        self.external_refs().into_iter().sum()
+    }
+
+    /// Returns the number of references to every element held outside of the `Bucket`, in slot order.
+    fn external_refs(&self) -> Vec<usize> {
+        let mut internal = HashMap::new();
+        for cell in self.persisted.cells().chain(self.interned.cells()) {
+            *internal.entry(Index::as_ptr(cell)).or_insert(0) += 1;
+        }
+
+        self.data
+            .iter()
+            .map(|v| {
+                let kept = internal.get(&Index::as_ptr(&v.index)).copied();
+                Index::strong_count(&v.index) - 1 - kept.unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Describes every element whose handles still exist outside of the `Bucket`, if any.
+    #[cfg_attr(not(feature = "leak-check"), allow(dead_code))]
+    fn leak_report(&self) -> Option<String> {
+        let leaks: Vec<_> = self
+            .external_refs()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, n)| n > 0)
+            .map(|(i, n)| format!("slot {i} ({n})"))
+            .collect();
+
+        (!leaks.is_empty()).then(|| {
+            format!(
+                "bucket dropped with {} outstanding handle(s): {}",
+                self.outstanding_handles(),
+                leaks.join(", ")
+            )
+        })
+    }
+}
+
+#[cfg(feature = "leak-check")]
+impl<T> Drop for Bucket<T> {
+    /// Reports every element whose handles outlive the `Bucket`.
+    fn drop(&mut self) {
+        if let Some(report) = self.leak_report() {
+            #[cfg(test)]
+            tests::REPORTS.with(|r| r.borrow_mut().push(report));
+
+            #[cfg(not(test))]
+            eprintln!("{report}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "leak-check")]
+    thread_local! {
+        pub(super) static REPORTS: std::cell::RefCell<Vec<String>> = const {
+            std::cell::RefCell::new(Vec::new())
+        };
+    }
+
+    #[test]
+    fn test_outstanding_handles() {
+        let mut bucket = Bucket::new(4);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        assert_eq!(bucket.outstanding_handles(), 2);
+
+        let a_clone = crate::ValueIndex(a.0.clone());
+        assert_eq!(bucket.outstanding_handles(), 3);
+        assert_eq!(
+            bucket.leak_report().as_deref(),
+            Some("bucket dropped with 3 outstanding handle(s): slot 0 (2), slot 1 (1)")
+        );
+
+        drop((a, a_clone, b));
+        assert_eq!(bucket.outstanding_handles(), 0);
+        assert_eq!(bucket.leak_report(), None);
+    }
+
+    #[test]
+    fn test_outstanding_handles_ignores_tables() {
+        let mut bucket = Bucket::new(4);
+        let a = bucket.intern("a");
+        bucket.intern("a");
+        let b = bucket.insert("b");
+        bucket.persist(&a);
+        bucket.persist(&b);
+        assert_eq!(bucket.outstanding_handles(), 2);
+
+        drop((a, b));
+        assert_eq!(bucket.outstanding_handles(), 0);
+        assert_eq!(bucket.leak_report(), None);
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[test]
+    fn test_leak_check_on_drop() {
+        let take = || REPORTS.with(|r| std::mem::take(&mut *r.borrow_mut()));
+
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        bucket.insert(2);
+        drop(bucket);
+        assert_eq!(
+            take(),
+            vec!["bucket dropped with 1 outstanding handle(s): slot 0 (1)"]
+        );
+        drop(a);
+
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        drop(a);
+        drop(bucket);
+        assert!(take().is_empty());
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[test]
+    fn test_leak_check_ignores_internal_handles() {
+        let take = || REPORTS.with(|r| std::mem::take(&mut *r.borrow_mut()));
+
+        let mut set = crate::BucketSet::new(2);
+        set.insert(1).unwrap();
+        drop(set);
+
+        let mut ordered = crate::OrderedBucket::new(2);
+        ordered.insert(1, 'a');
+        drop(ordered);
+
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern(1);
+        bucket.persist(&a);
+        drop(a);
+        drop(bucket);
+        assert!(take().is_empty());
+    }
+}
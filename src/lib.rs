@@ -1,8 +1,105 @@
 #![allow(clippy::from_over_into)]
 
+mod any;
+mod arena;
+mod bounded;
+mod checkpoint;
+mod chunks;
+mod cmp;
+#[cfg(test)]
+mod counting_alloc;
+mod cursor;
+mod dirty;
+mod downcast;
+mod drain;
+mod fallible;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "defmt")]
+mod format;
+mod hook;
+mod intern;
+mod iter;
+mod leak;
+#[cfg(feature = "atomic")]
+mod local;
+mod lock;
+#[cfg(feature = "lru")]
+mod lru;
+mod map;
+mod ordered;
+#[cfg(feature = "rayon")]
+mod par;
+mod permute;
+mod persist;
+mod pinned;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod policy;
+mod pool;
+mod raw;
+mod search;
+#[cfg(feature = "serde")]
+mod serialize;
+mod set;
+#[cfg(feature = "atomic")]
+mod sharded;
+#[cfg(feature = "atomic")]
+mod shared;
+mod small;
+mod snapshot;
+mod transfer;
+#[cfg(feature = "ttl")]
+mod ttl;
+mod tx;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod weak;
+
+pub use any::{AnyBucket, TypedIndex};
+pub use arena::{ArenaBucket, ArenaIter};
+pub use bounded::CapacityError;
+pub use checkpoint::Checkpoint;
+pub use chunks::ChunkMut;
+pub use cursor::CursorMut;
+pub use drain::DrainSlots;
+pub use fallible::RemoveError;
+pub use hook::{CapacityChange, CapacityChangeReason};
+pub use iter::{IntoIter, Iter, IterMut};
+#[cfg(feature = "atomic")]
+pub use local::{LocalBucket, LocalIndex, LocalRemap, SyncRemap};
+pub use ordered::OrderedBucket;
+#[cfg(feature = "rayon")]
+pub use par::{ParDrain, ParIter, ParIterMut};
+pub use permute::PermutationError;
+pub use persist::PersistentId;
+pub use pinned::PinnedBucket;
+#[cfg(feature = "bytemuck")]
+pub use pod::PodBucket;
+pub use policy::GrowthPolicy;
+pub use pool::{BucketPool, PoolStats, PooledBucket};
+pub use raw::RawParts;
+pub use set::BucketSet;
+#[cfg(feature = "atomic")]
+pub use sharded::{ShardIndex, ShardedBucket};
+#[cfg(feature = "atomic")]
+pub use shared::SyncBucket;
+pub use small::SmallBucket;
+pub use snapshot::{Remap, Snapshot};
+pub use transfer::TransferError;
+#[cfg(feature = "ttl")]
+pub use ttl::{Clock, SystemClock};
+pub use tx::BucketTx;
+pub use validate::InvariantViolation;
+pub use weak::WeakIndex;
+
 use std::{
+    borrow::{Borrow, BorrowMut},
     fmt::Debug,
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     sync::atomic::{AtomicUsize, Ordering},
 };
 
@@ -12,13 +109,115 @@ pub type Index = std::rc::Rc<AtomicUsize>;
 #[cfg(feature = "atomic")]
 pub type Index = std::sync::Arc<AtomicUsize>;
 
+/// The slot stored in the shared cell of a handle that no longer refers to any element.
+const DANGLING: usize = usize::MAX;
+
 /// Represents an index in a data structure.
 ///
 /// `ValueIndex` is used to identify a position in the data structure uniquely.
 /// It provides access to elements stored in a `Bucket`.
+///
+/// A handle is resolved by the identity of its shared cell rather than by the
+/// slot the cell holds, so a handle kept past the removal of its element never
+/// resolves to another element that was later moved or inserted into that slot.
+/// For the same reason, a handle passed to another `Bucket` than the one that
+/// issued it is treated as stale.
 #[derive(Debug)]
 pub struct ValueIndex(pub(crate) Index);
 
+impl ValueIndex {
+    /// Returns a handle that never resolves against any `Bucket`.
+    ///
+    /// Every dangling handle shares a single sentinel cell, so this does not
+    /// allocate. It can be used to initialize a handle that is assigned later.
+    pub fn dangling() -> Self {
+        Self(sentinel())
+    }
+
+    /// Checks if this handle no longer refers to any element.
+    ///
+    /// This is the case for a handle created by [`ValueIndex::dangling`], and
+    /// for every handle to an element once it was removed from its `Bucket`,
+    /// including clones of the handle that was used to remove it.
+    pub fn is_dangling(&self) -> bool {
+        self.0.load(Ordering::Relaxed) == DANGLING
+    }
+
+    /// Consumes the handle, returning a pointer that owns it.
+    ///
+    /// Like `Rc::into_raw`, the handle is kept alive until the pointer is
+    /// passed to [`ValueIndex::from_raw`] exactly once, so every pointer
+    /// returned by this method must eventually be reconstituted to avoid a leak.
+    /// The pointer identifies the element but must not be dereferenced.
+    pub fn into_raw(self) -> *const () {
+        Index::into_raw(self.0).cast()
+    }
+
+    /// Reconstitutes a handle from a pointer returned by [`ValueIndex::into_raw`].
+    ///
+    /// # Safety
+    /// The pointer must have been returned by `ValueIndex::into_raw` with the
+    /// same `atomic` feature selection, and it takes back the ownership given
+    /// up there: each call to `into_raw` must be matched by at most one call to
+    /// `from_raw`, after which the pointer must not be reconstituted again.
+    pub unsafe fn from_raw(ptr: *const ()) -> Self {
+        Self(Index::from_raw(ptr.cast()))
+    }
+
+    /// Creates a new handle from a pointer returned by [`ValueIndex::into_raw`], leaving it owned.
+    ///
+    /// Unlike [`ValueIndex::from_raw`], the pointer keeps its ownership and
+    /// can be materialized any number of times, and must still be reconstituted
+    /// by `from_raw` once.
+    ///
+    /// # Safety
+    /// The pointer must have been returned by `ValueIndex::into_raw` with the
+    /// same `atomic` feature selection, and must not have been reconstituted by
+    /// `ValueIndex::from_raw` yet.
+    pub unsafe fn clone_raw(ptr: *const ()) -> Self {
+        Index::increment_strong_count(ptr.cast::<AtomicUsize>());
+        Self::from_raw(ptr)
+    }
+}
+
+impl Default for ValueIndex {
+    /// Creates a dangling handle.
+    fn default() -> Self {
+        Self::dangling()
+    }
+}
+
+/// Resolves the slot referenced by `index` among `values`, as done by [`Bucket::slot`].
+///
+/// # Arguments
+/// * `values` - The stored values, in slot order.
+/// * `index` - The shared cell of the handle to resolve.
+fn slot_in<T>(values: &[Value<T>], index: &Index) -> Option<usize> {
+    let i = index.load(Ordering::Relaxed);
+    values
+        .get(i)
+        .filter(|v| Index::ptr_eq(&v.index, index))
+        .map(|_| i)
+}
+
+/// Returns the shared cell of every dangling handle.
+#[cfg(not(feature = "atomic"))]
+fn sentinel() -> Index {
+    thread_local! {
+        static SENTINEL: Index = Index::new(AtomicUsize::new(DANGLING));
+    }
+    SENTINEL.with(Index::clone)
+}
+
+/// Returns the shared cell of every dangling handle.
+#[cfg(feature = "atomic")]
+fn sentinel() -> Index {
+    static SENTINEL: std::sync::OnceLock<Index> = std::sync::OnceLock::new();
+    SENTINEL
+        .get_or_init(|| Index::new(AtomicUsize::new(DANGLING)))
+        .clone()
+}
+
 #[cfg(feature = "clone")]
 impl Clone for ValueIndex {
     fn clone(&self) -> Self {
@@ -33,6 +232,13 @@ impl Into<Index> for ValueIndex {
     }
 }
 
+impl Into<Index> for &ValueIndex {
+    /// Shares the underlying `Index` of a borrowed `ValueIndex`, so it can be passed to [`Bucket::remove`].
+    fn into(self) -> Index {
+        self.0.clone()
+    }
+}
+
 /// Represents a value stored in the `Bucket` data structure.
 ///
 /// `Value` holds both the actual data and the index pointing to its position
@@ -41,6 +247,107 @@ impl Into<Index> for ValueIndex {
 pub struct Value<T> {
     data: T,
     index: Index,
+    seq: u64,
+    #[cfg(feature = "lru")]
+    used: u64,
+    #[cfg(feature = "dirty")]
+    dirty: std::sync::atomic::AtomicBool,
+    #[cfg(feature = "ttl")]
+    deadline: Option<std::time::Instant>,
+}
+
+impl<T> Value<T> {
+    /// Creates a `Value` that does not belong to any `Bucket`.
+    ///
+    /// Its handle is dangling, so it never resolves against a `Bucket`.
+    ///
+    /// # Arguments
+    /// * `data` - The value to store.
+    pub fn new(data: T) -> Self {
+        Self::with_cell(data, ValueIndex::dangling().0, 0)
+    }
+
+    /// Creates a new, clean `Value` inserted with the given sequence number.
+    ///
+    /// # Arguments
+    /// * `data` - The value to store.
+    /// * `index` - The shared cell of the value.
+    /// * `seq` - The insertion sequence number of the value.
+    pub(crate) fn with_cell(data: T, index: Index, seq: u64) -> Self {
+        Self {
+            data,
+            index,
+            seq,
+            #[cfg(feature = "lru")]
+            used: seq,
+            #[cfg(feature = "dirty")]
+            dirty: false.into(),
+            #[cfg(feature = "ttl")]
+            deadline: None,
+        }
+    }
+
+    /// Returns a handle to this value.
+    pub fn index(&self) -> ValueIndex {
+        ValueIndex(self.index.clone())
+    }
+
+    /// Returns the slot currently occupied by this value.
+    pub fn slot(&self) -> usize {
+        self.index.load(Ordering::Relaxed)
+    }
+
+    /// Unwraps this value into its data, dropping its handle.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Splits this value into its data and a handle to the element it was taken from.
+    pub fn detach(self) -> (T, ValueIndex) {
+        (self.data, ValueIndex(self.index))
+    }
+
+    /// Copies this value into its data and a handle to the element it belongs to.
+    pub fn to_detached(&self) -> (T, ValueIndex)
+    where
+        T: Clone,
+    {
+        (self.data.clone(), self.index())
+    }
+
+    /// Clones this value into a new element with its own shared cell, storing the given slot.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot of the new element.
+    fn clone_into_slot(&self, slot: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            index: Index::new(AtomicUsize::new(slot)),
+            ..self.clone()
+        }
+    }
+}
+
+impl<T: Clone> Clone for Value<T> {
+    /// Clones the data of the value.
+    ///
+    /// The clone shares the shared cell of the original, so it keeps pointing
+    /// at the slot of the live element rather than being a separate entry.
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            index: self.index.clone(),
+            seq: self.seq,
+            #[cfg(feature = "lru")]
+            used: self.used,
+            #[cfg(feature = "dirty")]
+            dirty: self.dirty.load(Ordering::Relaxed).into(),
+            #[cfg(feature = "ttl")]
+            deadline: self.deadline,
+        }
+    }
 }
 
 impl<'a, T> Into<ValueRef<'a, T>> for &'a Value<T> {
@@ -84,17 +391,55 @@ impl<T: Debug> Debug for Value<T> {
     }
 }
 
+impl<T> AsRef<T> for Value<T> {
+    /// Provides immutable access to the underlying data.
+    fn as_ref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> AsMut<T> for Value<T> {
+    /// Provides mutable access to the underlying data.
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<T> Borrow<T> for Value<T> {
+    /// Borrows the underlying data.
+    fn borrow(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> BorrowMut<T> for Value<T> {
+    /// Mutably borrows the underlying data.
+    fn borrow_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
 /// A reference type for borrowed access to a `Value` within a `Bucket`.
 ///
 /// `ValueRef` is used to provide access to both the data and the index
 /// associated with a `Value` without transferring ownership.
 pub struct ValueRef<'a, T> {
     data: &'a T,
-
-    #[allow(dead_code)]
     index: &'a Index,
 }
 
+impl<T> ValueRef<'_, T> {
+    /// Returns a handle to the referenced value.
+    pub fn index(&self) -> ValueIndex {
+        ValueIndex(self.index.clone())
+    }
+
+    /// Returns the slot currently occupied by the referenced value.
+    pub fn slot(&self) -> usize {
+        self.index.load(Ordering::Relaxed)
+    }
+}
+
 #[cfg(feature = "clone")]
 impl<'a, T> Into<ValueIndex> for ValueRef<'a, T> {
     /// Converts a `ValueRef` into a `ValueIndex` for indexing operations.
@@ -112,20 +457,128 @@ impl<'a, T> Deref for ValueRef<'a, T> {
     }
 }
 
-impl<'a, T> DerefMut for ValueRef<'a, T> {
+impl<'a, T: Debug> Debug for ValueRef<'a, T> {
+    /// Formats the referenced value for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:?}", self.data))
+    }
+}
+
+impl<T> AsRef<T> for ValueRef<'_, T> {
+    /// Provides immutable access to the referenced data.
+    fn as_ref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T> Borrow<T> for ValueRef<'_, T> {
+    /// Borrows the referenced data.
+    fn borrow(&self) -> &T {
+        self.data
+    }
+}
+
+/// A reference type for mutable access to a `Value` within a `Bucket`.
+///
+/// `ValueRefMut` gives mutable access to the data only, so the index
+/// associated with the `Value` can be read but never modified.
+pub struct ValueRefMut<'a, T> {
+    data: &'a mut T,
+    index: &'a Index,
+}
+
+impl<T> ValueRefMut<'_, T> {
+    /// Returns a handle to the referenced value.
+    pub fn index(&self) -> ValueIndex {
+        ValueIndex(self.index.clone())
+    }
+
+    /// Returns the slot currently occupied by the referenced value.
+    pub fn slot(&self) -> usize {
+        self.index.load(Ordering::Relaxed)
+    }
+}
+
+impl<'a, T> Into<ValueRefMut<'a, T>> for &'a mut Value<T> {
+    /// Converts a mutable reference to `Value` into a `ValueRefMut`, flagging it as modified.
+    fn into(self) -> ValueRefMut<'a, T> {
+        self.mark();
+        ValueRefMut {
+            data: &mut self.data,
+            index: &self.index,
+        }
+    }
+}
+
+#[cfg(feature = "clone")]
+impl<'a, T> Into<ValueIndex> for ValueRefMut<'a, T> {
+    /// Converts a `ValueRefMut` into a `ValueIndex` for indexing operations.
+    fn into(self) -> ValueIndex {
+        ValueIndex(self.index.clone())
+    }
+}
+
+impl<T> Deref for ValueRefMut<'_, T> {
+    type Target = T;
+
+    /// Provides immutable access to the referenced data.
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+impl<T> DerefMut for ValueRefMut<'_, T> {
     /// Provides mutable access to the referenced data.
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.data
+        self.data
     }
 }
 
-impl<'a, T: Debug> Debug for ValueRef<'a, T> {
+impl<T: Debug> Debug for ValueRefMut<'_, T> {
     /// Formats the referenced value for debugging purposes.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("{:?}", self.data))
     }
 }
 
+/// Implements the comparison and hashing traits by delegating to the underlying data,
+/// so that they agree with the `Borrow` implementations.
+macro_rules! impl_delegating_cmp {
+    ($($t:ty),*) => {$(
+        impl<T: PartialEq> PartialEq for $t {
+            /// Compares the underlying data for equality.
+            fn eq(&self, other: &Self) -> bool {
+                self.data == other.data
+            }
+        }
+
+        impl<T: Eq> Eq for $t {}
+
+        impl<T: PartialOrd> PartialOrd for $t {
+            /// Compares the underlying data.
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.data.partial_cmp(&other.data)
+            }
+        }
+
+        impl<T: Ord> Ord for $t {
+            /// Compares the underlying data.
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.data.cmp(&other.data)
+            }
+        }
+
+        impl<T: Hash> Hash for $t {
+            /// Hashes the underlying data.
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.data.hash(state)
+            }
+        }
+    )*};
+}
+
+impl_delegating_cmp!(Value<T>, ValueRef<'_, T>);
+
 /// Manages the capacity of a dynamic data structure.
 ///
 /// Tracks the original and current capacity and provides methods to adjust the capacity.
@@ -133,28 +586,119 @@ impl<'a, T: Debug> Debug for ValueRef<'a, T> {
 struct Capacity {
     original: usize,
     current: usize,
+    policy: GrowthPolicy,
+    max: Option<usize>,
+    slack: usize,
+    auto_shrink: bool,
 }
 
 impl Capacity {
-    /// Creates a new `Capacity` with the given initial size.
+    /// Creates a new `Capacity` with the given initial size, growing by chunks of that size.
+    ///
+    /// An empty initial size grows by chunks of a single slot instead.
     ///
     /// # Arguments
     /// * `original` - The initial capacity of the data structure.
     const fn new(original: usize) -> Self {
+        let chunk = if original > 0 { original } else { 1 };
+        Self::with_policy(original, GrowthPolicy::Linear(chunk))
+    }
+
+    /// Creates a new `Capacity` with the given initial size and growth policy.
+    ///
+    /// # Arguments
+    /// * `original` - The initial capacity of the data structure.
+    /// * `policy` - How the capacity grows and shrinks.
+    const fn with_policy(original: usize, policy: GrowthPolicy) -> Self {
         Self {
             original,
             current: original,
+            policy,
+            max: None,
+            slack: 0,
+            auto_shrink: true,
+        }
+    }
+
+    /// Returns the original capacity with every other setting kept.
+    fn reset(&self) -> Self {
+        Self {
+            current: self.original,
+            ..self.clone()
         }
     }
 
-    /// Reduces the current capacity by the original size.
+    /// Limits a capacity to the maximum, if any.
+    ///
+    /// # Arguments
+    /// * `capacity` - The capacity to limit.
+    fn clamp(&self, capacity: usize) -> usize {
+        self.max.map_or(capacity, |max| capacity.min(max))
+    }
+
+    /// Returns the capacity following the current one, which is the current one if it cannot grow.
+    pub fn next(&self) -> usize {
+        self.clamp(self.policy.grown(self.current))
+            .max(self.current)
+    }
+
+    /// Returns the capacity preceding the current one.
+    ///
+    /// A capacity that was limited by the maximum steps back down as if it had not been.
+    pub fn previous(&self) -> usize {
+        let unclamped = self.policy.grown_to(self.original, self.current);
+        self.policy.shrunk(unclamped)
+    }
+
+    /// Returns the smallest capacity reachable from the original one that holds `n` elements.
+    ///
+    /// # Arguments
+    /// * `n` - The number of elements to make room for.
+    pub fn fitting(&self, n: usize) -> usize {
+        self.clamp(self.policy.grown_to(self.original, n))
+    }
+
+    /// Checks if the current capacity can be reached from the original one.
+    pub fn is_reachable(&self) -> bool {
+        self.policy.reaches(self.original, self.current)
+            || (self.current >= self.original && Some(self.current) == self.max)
+    }
+
+    /// Reduces the current capacity by one step of the growth policy.
     pub fn shrink(&mut self) {
-        self.current -= self.original;
+        self.current = self.previous();
     }
 
-    /// Increases the current capacity by the original size.
+    /// Increases the current capacity by one step of the growth policy, up to the maximum.
     pub fn grow(&mut self) {
-        self.current += self.original;
+        self.current = self.next();
+    }
+
+    /// Increases the current capacity by whole steps of the growth policy until it holds `n`, up to the maximum.
+    pub fn grow_to(&mut self, n: usize) {
+        self.current = self
+            .clamp(self.policy.grown_to(self.current, n))
+            .max(self.current);
+    }
+
+    /// Returns the number of elements at which the current capacity steps back down.
+    ///
+    /// This is the capacity preceding the current one, lowered by as many
+    /// further steps as the slack.
+    pub fn shrink_threshold(&self) -> usize {
+        let mut n = self.previous();
+        for _ in 0..self.slack {
+            if n == 0 {
+                break;
+            }
+            n = self.policy.shrunk(n)
+        }
+        n
+    }
+
+    /// Checks if the current capacity can step back down while holding `n` elements.
+    pub fn can_shrink_to(&self, n: usize) -> bool {
+        self.auto_shrink && self.current > self.original && n <= self.shrink_threshold()
     }
 }
 
@@ -163,21 +707,100 @@ impl Capacity {
 /// `Bucket` is designed to manage elements dynamically with efficient allocation
 /// and deallocation of space. It automatically adjusts its capacity based on the
 /// number of elements.
-#[derive(Debug)]
 pub struct Bucket<T> {
     data: Vec<Value<T>>,
     capacity: Capacity,
+    seq: u64,
+    hook: Option<hook::CapacityHook>,
+    overwrite: bool,
+    ring: Option<bounded::Ring>,
+    interned: intern::InternTable,
+    persisted: persist::PersistTable,
+    origin: checkpoint::Origin,
+    free_cells: Vec<Index>,
+    #[cfg(feature = "ttl")]
+    clock: Option<std::sync::Arc<dyn Clock>>,
 }
 
 impl<T> Bucket<T> {
     /// Creates a new `Bucket` with the specified initial capacity.
     ///
+    /// The capacity grows and shrinks by chunks of the initial capacity, or of
+    /// a single slot if the initial capacity is zero.
+    ///
     /// # Arguments
     /// * `capacity` - The initial number of slots in the `Bucket`.
     pub fn new(capacity: usize) -> Self {
         Self {
             data: Vec::with_capacity(capacity),
             capacity: Capacity::new(capacity),
+            seq: 0,
+            hook: None,
+            overwrite: false,
+            ring: None,
+            interned: intern::InternTable::new(),
+            persisted: persist::PersistTable::new(),
+            origin: checkpoint::Origin::new(),
+            free_cells: Vec::new(),
+            #[cfg(feature = "ttl")]
+            clock: None,
+        }
+    }
+
+    /// Creates a new `Bucket` with the specified initial capacity and growth policy.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `Bucket`.
+    /// * `policy` - How the capacity grows once full, and shrinks back.
+    pub fn with_policy(capacity: usize, policy: GrowthPolicy) -> Self {
+        let mut bucket = Self::new(capacity);
+        bucket.capacity.policy = policy;
+        bucket
+    }
+
+    /// Creates a new `Bucket` whose capacity grows as usual, but never past a maximum.
+    ///
+    /// If the maximum is not a whole number of chunks of the initial capacity,
+    /// the last chunk is cut short. Once full at the maximum, [`Bucket::insert`]
+    /// panics, while [`Bucket::checked_insert`] and [`Bucket::try_insert`] fail.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `Bucket`.
+    /// * `max` - The maximum number of slots in the `Bucket`.
+    ///
+    /// # Panics
+    /// Panics if `max` is less than `capacity`.
+    pub fn with_max_capacity(capacity: usize, max: usize) -> Self {
+        if max < capacity {
+            panic!("maximum capacity {max} is less than the initial capacity {capacity}")
+        }
+        let mut bucket = Self::new(capacity);
+        bucket.capacity.max = Some(max);
+        bucket
+    }
+
+    /// Creates a new, unallocated `Bucket` with the specified initial capacity.
+    ///
+    /// Unlike [`Bucket::new`], this can be used to initialize a `static`. The
+    /// capacity is recorded but only reserved by the first insertion, so
+    /// [`Bucket::allocated_capacity`] is zero until then.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `Bucket`.
+    pub const fn new_const(capacity: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            capacity: Capacity::new(capacity),
+            seq: 0,
+            hook: None,
+            overwrite: false,
+            ring: None,
+            interned: intern::InternTable::new(),
+            persisted: persist::PersistTable::new(),
+            origin: checkpoint::Origin::new(),
+            free_cells: Vec::new(),
+            #[cfg(feature = "ttl")]
+            clock: None,
         }
     }
 
@@ -191,251 +814,2312 @@ impl<T> Bucket<T> {
         self.capacity.current
     }
 
-    /// Checks if the `Bucket` is empty.
-    pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+    /// Returns the growth policy of the `Bucket`.
+    pub const fn policy(&self) -> GrowthPolicy {
+        self.capacity.policy
     }
 
-    /// Returns an iterator over the values in the `Bucket`.
-    #[cfg(feature = "clone")]
-    pub fn iter(&self) -> impl Iterator<Item = ValueRef<'_, T>> {
-        self.data.iter().map(Into::into)
+    /// Returns the maximum capacity of the `Bucket`, if any.
+    pub const fn max_capacity(&self) -> Option<usize> {
+        self.capacity.max
     }
 
-    /// Returns an iterator over the elements in the `Bucket`.
-    #[cfg(not(feature = "clone"))]
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.data.iter().map(|v| &v.data)
+    /// Returns the number of extra steps the length must fall below the previous capacity before shrinking.
+    pub const fn shrink_slack(&self) -> usize {
+        self.capacity.slack
     }
 
-    /// Retrieves a reference to the value at the given index.
+    /// Delays shrinking until the length falls the given number of extra steps below the previous capacity.
+    ///
+    /// By default, the capacity steps back down as soon as the last step of
+    /// growth is entirely unused, so a `Bucket` whose length oscillates around
+    /// that boundary grows and shrinks over and over. A slack of `k` only
+    /// shrinks once `k` further steps are unused as well, e.g. once the length
+    /// falls to `current - (k + 1) * chunk` with [`GrowthPolicy::Linear`].
     ///
     /// # Arguments
-    /// * `index` - The `ValueIndex` of the value to retrieve.
-    #[cfg(feature = "get")]
-    pub fn get(&self, index: &ValueIndex) -> &T {
-        &self.data[index.0.load(Ordering::Relaxed)].data
+    /// * `chunks` - The number of extra steps to keep unused.
+    pub fn set_shrink_slack(&mut self, chunks: usize) {
+        self.capacity.slack = chunks
     }
 
-    /// Inserts a new value into the `Bucket`.
+    /// Checks if removals shrink the capacity of the `Bucket` on their own.
+    pub const fn auto_shrink(&self) -> bool {
+        self.capacity.auto_shrink
+    }
+
+    /// Enables or disables shrinking the capacity on removal, which is enabled by default.
     ///
-    /// If the `Bucket` is full, it will automatically grow to accommodate the new value.
+    /// While disabled, removals never reallocate the backing storage and the
+    /// capacity never decreases on its own. It can still be reclaimed with
+    /// [`Bucket::shrink_to_fit`] or [`Bucket::shrink_to`].
     ///
     /// # Arguments
-    /// * `data` - The value to insert.
-    pub fn insert(&mut self, data: T) -> ValueIndex {
-        let n = self.len();
-
-        if n == self.capacity() {
-            self.grow();
-        }
-        let index_shared = Index::new(AtomicUsize::new(n));
+    /// * `enabled` - Whether removals may shrink the capacity.
+    pub fn set_auto_shrink(&mut self, enabled: bool) {
+        self.capacity.auto_shrink = enabled
+    }
 
-        self.data.push(Value {
-            data,
-            index: index_shared.clone(),
-        });
+    /// Returns the number of slots actually allocated by the backing storage.
+    ///
+    /// Whereas [`Bucket::capacity`] follows the growth policy, this is what the
+    /// allocator handed out. The backing storage is grown and shrunk exactly to
+    /// the capacity, so both match in practice, but the allocator is free to
+    /// hand out more. The exceptions are a `Bucket` created by
+    /// [`Bucket::new_const`] that has not been inserted into yet, which has
+    /// nothing allocated, and zero-sized values, which never need allocating.
+    pub fn allocated_capacity(&self) -> usize {
+        self.data.capacity()
+    }
 
-        ValueIndex(index_shared)
+    /// Checks if the `Bucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
     }
 
-    /// Removes the value at the specified index.
-    ///
-    /// The slot is freed for future use, and the internal array may be compacted.
+    /// Returns the most recently assigned sequence number, or zero if none was assigned.
     ///
-    /// # Arguments
-    /// * `index` - The `ValueIndex` of the value to remove.
-    #[cfg(not(feature = "clone"))]
-    pub fn remove(&mut self, index: impl Into<Index>) -> T {
-        let index = index.into().load(Ordering::Relaxed);
-        self._remove(index)
+    /// Every insertion is assigned a sequence number greater than all previous
+    /// ones, so this can be recorded as a high-water mark for [`Bucket::iter_since`].
+    pub const fn current_seq(&self) -> u64 {
+        self.seq
     }
 
-    /// Removes the value at the specified index, if it exists.
+    /// Returns the raw entries of the `Bucket`, in slot order.
+    ///
+    /// Each entry carries its value together with its shared cell, so
+    /// `as_entries()[i].slot() == i` always holds.
+    pub fn as_entries(&self) -> &[Value<T>] {
+        &self.data
+    }
+
+    /// Returns an iterator over every value together with a handle to it, in slot order.
+    ///
+    /// Each handle shares the cell of its element, regardless of the enabled
+    /// features, so it can be kept to remove the element later on.
+    pub fn iter_indexed(&self) -> impl ExactSizeIterator<Item = (ValueIndex, &T)> {
+        self.data.iter().map(|v| (v.index(), &v.data))
+    }
+
+    /// Returns an iterator over a handle to every element, in slot order.
+    ///
+    /// Like [`Bucket::iter_indexed`], this does not depend on the enabled features.
+    pub fn indices(&self) -> impl ExactSizeIterator<Item = ValueIndex> + '_ {
+        self.data.iter().map(Value::index)
+    }
+
+    /// Consumes the `Bucket`, returning every value together with its handle, in slot order.
+    ///
+    /// The handles no longer resolve against any `Bucket`, but still identify
+    /// the elements they were issued for.
+    pub fn into_entries(mut self) -> impl ExactSizeIterator<Item = (ValueIndex, T)> {
+        std::mem::take(&mut self.data)
+            .into_iter()
+            .map(|v| (ValueIndex(v.index), v.data))
+    }
+
+    /// Consumes the `Bucket`, returning its values sorted by the key computed by `key`.
+    ///
+    /// The sort is stable, so values with equal keys keep their slot order.
+    ///
+    /// # Arguments
+    /// * `key` - The closure computing the key of each value.
+    pub fn into_sorted_vec_by_key<K: Ord>(self, key: impl FnMut(&T) -> K) -> Vec<T> {
+        let mut values: Vec<T> = self.into_entries().map(|(_, v)| v).collect();
+        values.sort_by_key(key);
+        values
+    }
+
+    /// Consumes the `Bucket`, returning its values in ascending order.
+    ///
+    /// The sort is stable, so equal values keep their slot order.
+    pub fn into_sorted_vec(self) -> Vec<T>
+    where
+        T: Ord,
+    {
+        let mut values: Vec<T> = self.into_entries().map(|(_, v)| v).collect();
+        values.sort();
+        values
+    }
+
+    /// Returns an iterator over the values inserted after the given sequence number.
+    ///
+    /// Each value is yielded together with its insertion sequence number, in
+    /// slot order. Values that have since been removed are simply absent.
+    ///
+    /// # Arguments
+    /// * `seq` - The sequence number after which values are yielded.
+    pub fn iter_since(&self, seq: u64) -> impl Iterator<Item = (u64, ValueRef<'_, T>)> {
+        self.data
+            .iter()
+            .filter(move |v| v.seq > seq)
+            .map(|v| (v.seq, v.into()))
+    }
+
+    /// Returns an iterator over the values in the `Bucket`.
+    ///
+    /// Each value is yielded as a `ValueRef`, which dereferences to a plain
+    /// reference, regardless of the enabled features.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.data.iter())
+    }
+
+    /// Returns a mutable iterator over the values in the `Bucket`.
+    ///
+    /// Only the data can be modified, so every handle remains valid. Each value
+    /// is yielded as a `ValueRefMut`, which dereferences to the value,
+    /// regardless of the enabled features.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.data.iter_mut())
+    }
+
+    /// Retrieves a reference to the value at the given index.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    ///
+    /// # Panics
+    /// Panics if the handle is stale or dangling, see [`Bucket::try_get`] for
+    /// a non-panicking lookup.
+    #[cfg(feature = "get")]
+    pub fn get(&self, index: &ValueIndex) -> &T {
+        match self.slot(&index.0) {
+            Some(i) => &self.data[i].data,
+            None => panic!("attempted to get a stale or dangling `ValueIndex`"),
+        }
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// Returns `None` if the handle is stale or dangling.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn try_get(&self, index: &ValueIndex) -> Option<&T> {
+        self.slot(&index.0).map(|i| &self.data[i].data)
+    }
+
+    /// Retrieves a mutable reference to the value at the given index.
+    ///
+    /// The element keeps its slot, so every handle to it remains valid.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    ///
+    /// # Panics
+    /// Panics if the handle is stale or dangling.
+    #[cfg(feature = "get")]
+    pub fn get_mut(&mut self, index: &ValueIndex) -> &mut T {
+        match self.slot(&index.0) {
+            Some(i) => {
+                self.data[i].mark();
+                &mut self.data[i].data
+            }
+            None => panic!("attempted to get a stale or dangling `ValueIndex`"),
+        }
+    }
+
+    /// Retrieves mutable references to the values at several indices at once.
+    ///
+    /// Returns `None` if any handle is stale or dangling, or if two handles
+    /// refer to the same element.
+    ///
+    /// # Arguments
+    /// * `indices` - The `ValueIndex` of each value to retrieve.
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        indices: [&ValueIndex; N],
+    ) -> Option<[&mut T; N]> {
+        let mut slots = [0; N];
+        for (slot, index) in slots.iter_mut().zip(indices) {
+            *slot = self.slot(&index.0)?;
+        }
+        let values = self.data.get_disjoint_mut(slots).ok()?;
+
+        Some(values.map(|v| {
+            v.mark();
+            &mut v.data
+        }))
+    }
+
+    /// Checks if the value at the given index is still stored in the `Bucket`.
+    ///
+    /// This never panics. It returns `false` for dangling handles, for handles
+    /// whose element was removed, even if their slot now holds another element,
+    /// and for handles issued by another `Bucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to look up.
+    pub fn contains(&self, index: &ValueIndex) -> bool {
+        self.slot(&index.0).is_some()
+    }
+
+    /// Inserts a new value into the `Bucket`.
+    ///
+    /// If the `Bucket` is full, it will automatically grow to accommodate the new value,
+    /// unless it was created by [`Bucket::bounded_overwriting`].
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    ///
+    /// # Panics
+    /// Panics if the `Bucket` is full and its capacity is fixed, see
+    /// [`Bucket::try_insert`] for a non-panicking alternative.
+    pub fn insert(&mut self, data: T) -> ValueIndex {
+        if self.overwrite {
+            let Ok((index, _)) = self.insert_overwriting(data) else {
+                panic!("attempted to insert into a `Bucket` of zero capacity that cannot grow")
+            };
+            return index;
+        }
+        let n = self.len();
+
+        if n == self.capacity() {
+            if !self.can_grow() {
+                panic!("attempted to insert into a full `Bucket` that cannot grow past {n}")
+            }
+            self.grow();
+        }
+        let index = self.push(data);
+        self.paranoid();
+        index
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    ///
+    /// The capacity grows by whole steps of the growth policy, and the backing
+    /// storage is allocated for all of it, so inserting up to the new capacity
+    /// neither grows the `Bucket` nor reallocates. As usual, the capacity steps
+    /// back down by every step that becomes unused after removals. The
+    /// capacity of a bounded `Bucket` never changes.
+    ///
+    /// # Arguments
+    /// * `additional` - The number of values to make room for.
+    pub fn reserve(&mut self, additional: usize) {
+        self.reserve_steps(additional);
+        self.data.reserve_exact(self.capacity.current - self.len());
+        self.paranoid();
+    }
+
+    /// Reserves capacity for at least `additional` more values, like [`Bucket::reserve`].
+    ///
+    /// Unlike [`Vec::reserve_exact`], the resulting capacity is not exact: it
+    /// still grows by whole steps of the growth policy, and the backing storage
+    /// is allocated for all of it, so inserting up to the new capacity never
+    /// reallocates.
+    ///
+    /// # Arguments
+    /// * `additional` - The number of values to make room for.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    /// Grows the capacity by whole steps of the growth policy to hold `additional` more values.
+    ///
+    /// # Arguments
+    /// * `additional` - The number of values to make room for.
+    fn reserve_steps(&mut self, additional: usize) {
+        if self.overwrite {
+            return;
+        }
+        let old = self.capacity.current;
+        self.capacity.grow_to(self.len() + additional);
+        self.capacity_changed(old, CapacityChangeReason::Reserve);
+    }
+
+    /// Shrinks the capacity as much as possible.
+    ///
+    /// The capacity drops to the smallest one reachable by the growth policy
+    /// that holds every value, and the backing storage is shrunk to match. The
+    /// capacity of a bounded `Bucket` never changes.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0)
+    }
+
+    /// Shrinks the capacity with a lower bound.
+    ///
+    /// The capacity drops to the smallest one reachable by the growth policy
+    /// that holds every value and at least `min_capacity` slots, but never
+    /// grows. The backing storage is shrunk to match. The capacity of a
+    /// bounded `Bucket` never changes.
+    ///
+    /// # Arguments
+    /// * `min_capacity` - The number of slots to keep at least.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if self.overwrite {
+            return;
+        }
+        let old = self.capacity.current;
+        let target = self.capacity.fitting(self.len().max(min_capacity));
+
+        self.capacity.current = target.min(old);
+        self.data.shrink_to(self.capacity.current);
+        self.capacity_changed(old, CapacityChangeReason::ShrinkToFit);
+        self.paranoid();
+    }
+
+    /// Inserts every value of the iterator, returning their handles in insertion order.
+    ///
+    /// Capacity for the lower bound of the size hint of the iterator is
+    /// reserved once up front, in whole steps of the growth policy.
+    ///
+    /// # Arguments
+    /// * `iter` - The values to insert.
+    pub fn insert_many(&mut self, iter: impl IntoIterator<Item = T>) -> Vec<ValueIndex> {
+        let iter = iter.into_iter();
+        if !self.overwrite {
+            self.grow_to(self.len() + iter.size_hint().0);
+        }
+        iter.map(|data| self.insert(data)).collect()
+    }
+
+    /// Clones every value of another `Bucket` into this one.
+    ///
+    /// Capacity is reserved once up front, and the returned handles are
+    /// aligned with the slot order of `src`, which is left untouched.
+    ///
+    /// # Arguments
+    /// * `src` - The `Bucket` to clone the values from.
+    pub fn extend_from_bucket(&mut self, src: &Bucket<T>) -> Vec<ValueIndex>
+    where
+        T: Clone,
+    {
+        let n = self.len() + src.len();
+        self.grow_to(n);
+        if n > self.capacity() {
+            panic!(
+                "attempted to insert {n} values into a `Bucket` that cannot grow past {}",
+                self.capacity()
+            )
+        }
+        let handles = src.data.iter().map(|v| self.push(v.data.clone())).collect();
+        self.paranoid();
+        handles
+    }
+
+    /// Advances the sequence counter of the `Bucket`, returning the new sequence number.
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+
+    /// Takes a shared cell from the pool, or allocates a new one, storing the given slot.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot of the element the cell is for.
+    fn new_cell(&mut self, slot: usize) -> Index {
+        match self.free_cells.pop() {
+            Some(cell) => {
+                cell.store(slot, Ordering::Relaxed);
+                cell
+            }
+            None => Index::new(AtomicUsize::new(slot)),
+        }
+    }
+
+    /// Pushes a new value into the next slot without checking the capacity.
+    ///
+    /// # Arguments
+    /// * `data` - The value to push.
+    fn push(&mut self, data: T) -> ValueIndex {
+        // Perform the reservation deferred by `new_const`
+        if self.data.capacity() == 0 {
+            self.data.reserve_exact(self.capacity.current)
+        }
+        let index_shared = self.new_cell(self.len());
+        let seq = self.next_seq();
+
+        self.data
+            .push(Value::with_cell(data, index_shared.clone(), seq));
+
+        ValueIndex(index_shared)
+    }
+
+    /// Removes the value at the specified index, if it exists.
     ///
     /// The slot is freed for future use, and the internal array may be compacted.
+    /// Returns `None` if the handle is stale or dangling, regardless of the
+    /// enabled features. The handle can either be moved or borrowed, and a
+    /// borrowed handle is left dangling.
     ///
     /// # Arguments
     /// * `index` - The `ValueIndex` of the value to remove.
-    #[cfg(feature = "clone")]
     pub fn remove(&mut self, index: impl Into<Index>) -> Option<T> {
-        let index = index.into().load(Ordering::Relaxed);
-        self.data.get(index).is_some().then(|| self._remove(index))
+        // The handle is released first, so its cell can be reused
+        let slot = self.slot(&index.into());
+        let value = slot.map(|i| self._remove(i));
+        self.paranoid();
+        value
+    }
+
+    /// Removes the value at the specified index without checking that the handle resolves.
+    ///
+    /// This skips resolving the handle against the `Bucket` and the bounds
+    /// check of the removal, which [`Bucket::remove`] and [`Bucket::try_remove`]
+    /// perform. It is only worth it where profiling shows those checks matter.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    ///
+    /// # Safety
+    /// The handle must have been issued by this `Bucket`, and its element must
+    /// still be stored in it, as checked by [`Bucket::contains`]. Otherwise, the
+    /// slot read from the handle may be out of bounds.
+    pub unsafe fn remove_unchecked(&mut self, index: &ValueIndex) -> T {
+        debug_assert!(
+            self.contains(index),
+            "attempted to remove a stale or dangling `ValueIndex`"
+        );
+        let i = index.0.load(Ordering::Relaxed);
+
+        // SAFETY: the caller guarantees that the element of the handle is in slot `i`
+        let value = unsafe { self._swap_remove_unchecked(i) };
+        self.shrink_unused();
+        self.paranoid();
+        value.data
+    }
+
+    /// Removes every value from the `Bucket` and resets its capacity.
+    ///
+    /// Every outstanding handle becomes invalid, so none of them can resolve
+    /// to values inserted afterwards.
+    pub fn clear(&mut self) {
+        for value in self.data.drain(..) {
+            value.index.store(DANGLING, Ordering::Relaxed);
+            self.persisted.forget(&value.index)
+        }
+        self.interned.clear();
+        self.free_cells = Vec::new();
+        self.set_capacity(self.capacity.reset());
+        self.paranoid()
+    }
+
+    /// Replaces the value at the specified index, returning the previous value if it exists.
+    ///
+    /// The element keeps its slot, so every handle to it remains valid.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to replace.
+    /// * `data` - The new value.
+    pub fn replace(&mut self, index: &ValueIndex, data: T) -> Option<T> {
+        let i = self.slot(&index.0)?;
+        let value = std::mem::replace(&mut self.data[i].data, data);
+        self.data[i].mark();
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Puts a value back at the specified index, returning the previous value if it exists.
+    ///
+    /// This is the counterpart of [`Bucket::take`] and behaves exactly like [`Bucket::replace`].
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to put back.
+    /// * `data` - The value to put back.
+    pub fn put_back(&mut self, index: &ValueIndex, data: T) -> Option<T> {
+        self.replace(index, data)
+    }
+
+    /// Takes the value at the specified index, leaving `T::default()` in its place.
+    ///
+    /// The element keeps its slot and the capacity is unchanged, so the handle
+    /// remains valid and resolves to the default value.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to take.
+    pub fn take(&mut self, index: &ValueIndex) -> Option<T>
+    where
+        T: Default,
+    {
+        self.replace(index, T::default())
+    }
+
+    /// Transforms the value at the specified index in place, returning whether it exists.
+    ///
+    /// The value is moved into `f` and the result is moved back into the same slot,
+    /// so every handle to it remains valid.
+    ///
+    /// If `f` panics, the consumed element is removed from the `Bucket` before the
+    /// panic is resumed: its handles become invalid and no slot is ever left
+    /// without a value.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to transform.
+    /// * `f` - The closure producing the new value from the old one.
+    pub fn replace_with(&mut self, index: &ValueIndex, f: impl FnOnce(T) -> T) -> bool {
+        let Some(i) = self.slot(&index.0) else {
+            return false;
+        };
+        let j = self.len() - 1;
+
+        // Move the element into the last slot so it can be taken out without leaving a gap
+        self.data.swap(i, j);
+        self.data[i].index.store(i, Ordering::Relaxed);
+
+        let Some(mut value) = self.data.pop() else {
+            unreachable!()
+        };
+
+        match catch_unwind(AssertUnwindSafe(|| f(value.data))) {
+            Ok(data) => {
+                // Move the element back into its original slot
+                value.data = data;
+                self.data.push(value);
+                self.data.swap(i, j);
+                self.data[j].index.store(j, Ordering::Relaxed);
+                self.data[i].mark();
+                self.paranoid();
+                true
+            }
+            Err(payload) => {
+                value.index.store(DANGLING, Ordering::Relaxed);
+                self.persisted.forget(&value.index);
+                self.interned.forget(&value.index);
+                self.shrink_unused();
+                resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Exchanges the slots of the values at the specified indices.
+    ///
+    /// Both elements take their shared cells with them, so every handle keeps
+    /// resolving to the same value. Swapping a value with itself does nothing.
+    ///
+    /// # Arguments
+    /// * `a` - The `ValueIndex` of the first value.
+    /// * `b` - The `ValueIndex` of the second value.
+    ///
+    /// # Panics
+    /// Panics if either handle is stale or dangling.
+    pub fn swap(&mut self, a: &ValueIndex, b: &ValueIndex) {
+        let (Some(i), Some(j)) = (self.slot(&a.0), self.slot(&b.0)) else {
+            panic!("attempted to swap a stale or dangling `ValueIndex`")
+        };
+        self.data.swap(i, j);
+        self.data[i].index.store(i, Ordering::Relaxed);
+        self.data[j].index.store(j, Ordering::Relaxed);
+        self.ring = None;
+        self.paranoid();
+    }
+
+    /// Removes and returns the value in the last slot, if any.
+    ///
+    /// No other element is moved, so every other handle keeps its slot. The
+    /// handles of the popped value become dangling.
+    pub fn pop(&mut self) -> Option<T> {
+        let i = self.len().checked_sub(1)?;
+        let value = self._remove(i);
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Removes and returns the value with the smallest key, if any.
+    ///
+    /// When several values share the smallest key, the one in the lowest slot is removed.
+    ///
+    /// # Arguments
+    /// * `key` - The closure computing the key of each value.
+    pub fn pop_min_by_key<K: Ord>(&mut self, mut key: impl FnMut(&T) -> K) -> Option<T> {
+        let (_, i) = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (key(&v.data), i))
+            .min()?;
+
+        let value = self._remove(i);
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Removes and returns the value with the largest key, if any.
+    ///
+    /// When several values share the largest key, the one in the lowest slot is removed.
+    ///
+    /// # Arguments
+    /// * `key` - The closure computing the key of each value.
+    pub fn pop_max_by_key<K: Ord>(&mut self, mut key: impl FnMut(&T) -> K) -> Option<T> {
+        let (_, i) = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (key(&v.data), i))
+            .max_by(|(a, i), (b, j)| a.cmp(b).then(j.cmp(i)))?;
+
+        let value = self._remove(i);
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Keeps only the values for which `f` returns `true`.
+    ///
+    /// Each removed value is replaced by the last one, which is visited next,
+    /// so every value is passed to `f` exactly once but not in slot order. The
+    /// capacity shrinks by every unused chunk once all values were visited.
+    ///
+    /// # Arguments
+    /// * `f` - The closure deciding whether each value is kept.
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let mut i = 0;
+
+        while let Some(value) = self.data.get(i) {
+            match f(&value.data) {
+                true => i += 1,
+                false => {
+                    let value = self._swap_remove(i);
+                    drop(self.recycle(value))
+                }
+            }
+        }
+        self.shrink_unused();
+        self.paranoid()
+    }
+
+    /// Removes the value referenced by `index`, if its element is still stored in this `Bucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the handle to remove.
+    fn remove_index(&mut self, index: &Index) -> Option<T> {
+        let i = self.slot(index)?;
+        let value = self._remove(i);
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Resolves the slot referenced by `index`, if its element is still stored in this `Bucket`.
+    ///
+    /// The cell of a removed element holds the dangling sentinel, which is out
+    /// of bounds. Cells are also compared themselves, so a handle never
+    /// resolves to another element even if its cell holds a slot in bounds,
+    /// and since the handle keeps its cell alive, no new cell can share its address.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the handle to resolve.
+    fn slot(&self, index: &Index) -> Option<usize> {
+        slot_in(&self.data, index)
+    }
+
+    fn _remove(&mut self, i: usize) -> T {
+        let value = self._remove_value(i);
+        self.recycle(value)
+    }
+
+    /// Returns the data of a removed value, keeping its shared cell for reuse if nothing refers to it.
+    ///
+    /// Reusing the cells saves an allocation per insertion for workloads that
+    /// drop their handles before removing. At most as many cells as the
+    /// original capacity are kept.
+    ///
+    /// # Arguments
+    /// * `value` - The removed value.
+    fn recycle(&mut self, value: Value<T>) -> T {
+        let mut cell = value.index;
+        let limit = self.capacity.original;
+
+        // A cell still referenced by a strong or weak handle must keep identifying its element
+        if self.free_cells.len() < limit && Index::get_mut(&mut cell).is_some() {
+            if self.free_cells.capacity() == 0 {
+                self.free_cells.reserve_exact(limit)
+            }
+            self.free_cells.push(cell)
+        }
+        value.data
+    }
+
+    fn _remove_value(&mut self, i: usize) -> Value<T> {
+        let value = self._swap_remove(i);
+
+        // Shrink the capacity if needed
+        self.shrink_unused();
+        value
+    }
+
+    /// Removes the element in slot `i` without adjusting the capacity.
+    ///
+    /// Every handle to the removed element is marked as dangling.
+    ///
+    /// # Arguments
+    /// * `i` - The slot of the element to remove.
+    fn _swap_remove(&mut self, i: usize) -> Value<T> {
+        let len = self.len();
+        if i >= len {
+            panic!("attempted to remove slot {i} from a bucket of length {len}")
+        }
+        // SAFETY: the slot was checked to be in bounds
+        unsafe { self._swap_remove_unchecked(i) }
+    }
+
+    /// Removes the element in slot `i` without adjusting the capacity or checking the bounds.
+    ///
+    /// # Arguments
+    /// * `i` - The slot of the element to remove.
+    ///
+    /// # Safety
+    /// The slot must be less than the length of the `Bucket`.
+    unsafe fn _swap_remove_unchecked(&mut self, i: usize) -> Value<T> {
+        let last = self.len() - 1;
+        let base = self.data.as_mut_ptr();
+
+        // SAFETY: both slots are in bounds, and the last one is forgotten by shortening the length.
+        // Moving the last element into the freed slot is a no-op when removing the last one.
+        let value = unsafe {
+            let value = std::ptr::read(base.add(i));
+            std::ptr::copy(base.add(last), base.add(i), 1);
+            self.data.set_len(last);
+            value
+        };
+        value.index.store(DANGLING, Ordering::Relaxed);
+        self.persisted.forget(&value.index);
+        self.interned.forget(&value.index);
+
+        // Update the index of the moved element, if any
+        if let Some(moved) = self.data.get(i) {
+            moved.index.store(i, Ordering::Relaxed)
+        }
+        value
+    }
+
+    /// Decreases the capacity of the `Bucket` by every entirely unused step of growth.
+    ///
+    /// A reservation may leave several steps unused at once, so a single
+    /// removal can step the capacity down more than once.
+    fn shrink_unused(&mut self) {
+        while self.capacity.can_shrink_to(self.len()) {
+            self.shrink()
+        }
+    }
+
+    /// Checks if the capacity of the `Bucket` can grow beyond its current size.
+    fn can_grow(&self) -> bool {
+        !self.overwrite && self.capacity.next() > self.capacity.current
+    }
+
+    /// Increases the capacity of the `Bucket`.
+    ///
+    /// This method is called internally when the `Bucket` is full.
+    fn grow(&mut self) {
+        let old = self.capacity.current;
+        self.capacity.grow();
+        self.data.reserve_exact(self.capacity.current - self.len());
+        self.capacity_changed(old, CapacityChangeReason::Grow);
+    }
+
+    /// Increases the capacity of the `Bucket` so that it holds at least `n` elements.
+    ///
+    /// # Arguments
+    /// * `n` - The number of elements to make room for.
+    fn grow_to(&mut self, n: usize) {
+        let old = self.capacity.current;
+        self.capacity.grow_to(n);
+        self.data
+            .reserve_exact(self.capacity.current.max(n) - self.len());
+        self.capacity_changed(old, CapacityChangeReason::Grow);
+    }
+
+    /// Replaces the capacity of the `Bucket`, adjusting the backing storage to match.
+    ///
+    /// # Arguments
+    /// * `capacity` - The capacity to restore.
+    fn set_capacity(&mut self, capacity: Capacity) {
+        let (old, current) = (self.capacity.current, capacity.current);
+        self.capacity = capacity;
+
+        if self.data.capacity() < current {
+            self.data.reserve_exact(current - self.data.len())
+        } else {
+            self.data.shrink_to(current)
+        }
+
+        let reason = match old < current {
+            true => CapacityChangeReason::Reserve,
+            false => CapacityChangeReason::ShrinkToFit,
+        };
+        self.capacity_changed(old, reason);
+    }
+
+    /// Decreases the capacity of the `Bucket`.
+    ///
+    /// This method is called internally when the `Bucket` has extra capacity
+    /// after removing elements.
+    fn shrink(&mut self) {
+        let old = self.capacity.current;
+        self.capacity.shrink();
+        self.data.shrink_to(self.capacity.current);
+        self.capacity_changed(old, CapacityChangeReason::Shrink);
+    }
+}
+
+impl<T: Debug> Debug for Bucket<T> {
+    /// Formats the bucket for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bucket")
+            .field("data", &self.data)
+            .field("capacity", &self.capacity)
+            .field("seq", &self.seq)
+            .finish()
+    }
+}
+
+impl<T: Clone> Clone for Bucket<T> {
+    /// Clones every value into a new `Bucket` with fresh, independent handles.
+    ///
+    /// Handles into the original do not resolve against the clone, and removals
+    /// from either one leave the other untouched. Interned elements stay interned
+    /// and persistent identifiers resolve to the same values in the clone, but
+    /// the capacity change callback is not carried over.
+    fn clone(&self) -> Self {
+        let mut data = Vec::with_capacity(self.data.capacity());
+        data.extend(
+            self.data
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v.clone_into_slot(i)),
+        );
+
+        Self {
+            interned: self.clone_interned(&data),
+            persisted: self.clone_persisted(&data),
+            origin: checkpoint::Origin::new(),
+            data,
+            capacity: self.capacity.clone(),
+            seq: self.seq,
+            hook: None,
+            overwrite: self.overwrite,
+            ring: None,
+            free_cells: Vec::new(),
+            #[cfg(feature = "ttl")]
+            clock: self.clock.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "get")]
+impl<T> std::ops::Index<&ValueIndex> for Bucket<T> {
+    type Output = T;
+
+    /// Retrieves a reference to the value at the given index, like [`Bucket::get`].
+    ///
+    /// # Panics
+    /// Panics if the handle is stale or dangling.
+    fn index(&self, index: &ValueIndex) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+#[cfg(feature = "get")]
+impl<T> std::ops::IndexMut<&ValueIndex> for Bucket<T> {
+    /// Retrieves a mutable reference to the value at the given index, like [`Bucket::get_mut`].
+    ///
+    /// # Panics
+    /// Panics if the handle is stale or dangling.
+    fn index_mut(&mut self, index: &ValueIndex) -> &mut Self::Output {
+        self.get_mut(index)
+    }
+}
+
+impl<T> Default for Bucket<T> {
+    /// Creates an empty `Bucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialization() {
+        let bucket = Bucket::<u8>::new(10);
+        assert_eq!(bucket.len(), 0);
+        assert!(bucket.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_insert() {
+        let mut bucket = Bucket::new(2);
+        let idx1 = bucket.insert(42);
+        let idx2 = bucket.insert(43);
+        assert_eq!(*bucket.get(&idx1), 42);
+        assert_eq!(*bucket.get(&idx2), 43);
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_index_operators() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert((1, 'a'));
+        let b = bucket.insert((2, 'b'));
+        let c = bucket.insert((3, 'c'));
+
+        // The last element is relocated into the slot of the removed one
+        bucket.remove_index(&a.0);
+        assert_eq!(bucket[&c], (3, 'c'));
+
+        bucket[&c].0 = 30;
+        bucket[&b] = (20, 'B');
+        assert_eq!(bucket[&c], (30, 'c'));
+        assert_eq!(bucket[&b], (20, 'B'));
+        assert_eq!(bucket.data[0].data, (30, 'c'));
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    #[should_panic(expected = "stale or dangling")]
+    fn test_index_stale() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove_index(&a.0);
+        bucket[&stale] = 2;
+    }
+
+    #[test]
+    fn test_try_get() {
+        let mut bucket = Bucket::new(2);
+        assert_eq!(bucket.try_get(&ValueIndex::dangling()), None);
+
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let c = bucket.insert('c');
+        let stale = ValueIndex(a.0.clone());
+
+        // The slot of the removed element is reused by the last one
+        bucket.remove_index(&a.0);
+        assert_eq!(bucket.try_get(&stale), None);
+        assert_eq!(bucket.try_get(&c), Some(&'c'));
+        assert_eq!(bucket.try_get(&b), Some(&'b'));
+
+        // Handles of an emptied bucket point past its end
+        bucket.remove_index(&b.0);
+        bucket.remove_index(&c.0);
+        assert_eq!(bucket.try_get(&b), None);
+        assert_eq!(bucket.try_get(&c), None);
+        assert!(bucket.is_empty());
+    }
+
+    #[test]
+    fn test_get_many_mut() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..4).map(|i| bucket.insert(i)).collect();
+
+        let [a] = bucket.get_many_mut([&handles[2]]).unwrap();
+        *a += 10;
+        let [a, b] = bucket.get_many_mut([&handles[0], &handles[3]]).unwrap();
+        std::mem::swap(a, b);
+        let [a, b, c] = bucket
+            .get_many_mut([&handles[3], &handles[1], &handles[0]])
+            .unwrap();
+        (*a, *b, *c) = (*c + 100, *b + 100, *a + 100);
+        assert_eq!(bucket, [100, 101, 12, 103]);
+        assert_eq!(bucket.get_many_mut([]), Some([]));
+    }
+
+    #[test]
+    fn test_get_many_mut_overlapping() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..3).map(|i| bucket.insert(i)).collect();
+        let shared = ValueIndex(handles[1].0.clone());
+
+        assert!(bucket.get_many_mut([&handles[1], &handles[1]]).is_none());
+        assert!(bucket
+            .get_many_mut([&handles[0], &handles[1], &shared])
+            .is_none());
+
+        // The stale handle holds the slot the last element was moved into
+        let stale = ValueIndex(handles[0].0.clone());
+        bucket.remove_index(&handles[0].0);
+        assert!(bucket.get_many_mut([&stale, &handles[2]]).is_none());
+        assert!(bucket.get_many_mut([&stale]).is_none());
+        assert!(bucket
+            .get_many_mut([&handles[1], &ValueIndex::dangling()])
+            .is_none());
+
+        let [a, b] = bucket.get_many_mut([&handles[2], &handles[1]]).unwrap();
+        (*a, *b) = (*b, *a);
+        assert_eq!(bucket, [1, 2]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        assert!(bucket.contains(&a));
+        assert!(bucket.contains(&b));
+        assert!(!bucket.contains(&ValueIndex::dangling()));
+
+        // The removed handle is marked as dangling
+        bucket.remove_index(&b.0);
+        assert!(b.is_dangling());
+        assert!(!bucket.contains(&b));
+        assert!(bucket.contains(&a));
+
+        // The slot of the removed element is recycled by a new one
+        let c = bucket.insert(3);
+        assert!(b.is_dangling());
+        assert!(!bucket.contains(&b));
+        assert!(bucket.contains(&c));
+    }
+
+    #[test]
+    fn test_stale_handle_aliasing() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let c = bucket.insert('c');
+        let stale = ValueIndex(a.0.clone());
+
+        // The last element is moved into the slot the stale handle held
+        bucket.remove_index(&a.0);
+        assert!(stale.is_dangling());
+        assert!(!bucket.contains(&stale));
+        assert_eq!(bucket.try_get(&stale), None);
+        assert_eq!(bucket.remove_index(&stale.0), None);
+        assert_eq!(bucket.try_get(&c), Some(&'c'));
+
+        // Neither does it alias elements inserted into that slot later on
+        bucket.remove_index(&b.0);
+        bucket.remove_index(&c.0);
+        let d = bucket.insert('d');
+        assert!(stale.is_dangling());
+        assert!(!bucket.contains(&stale));
+        assert_eq!(bucket.try_get(&stale), None);
+        assert_eq!(bucket.try_get(&d), Some(&'d'));
+    }
+
+    #[test]
+    fn test_foreign_handles() {
+        let mut a = Bucket::new(2);
+        let mut b = Bucket::new(2);
+        let from_a: Vec<_> = (0..3).map(|i| a.insert(i)).collect();
+        let from_b: Vec<_> = (10..13).map(|i| b.insert(i)).collect();
+
+        // Every handle holds a slot that exists in the other bucket too
+        for idx in &from_a {
+            assert!(!b.contains(idx));
+            assert_eq!(b.try_get(idx), None);
+            assert_eq!(b.remove_index(&idx.0), None);
+        }
+        assert_eq!(a.try_get(&from_b[1]), None);
+        assert_eq!(a.take(&from_b[2]), None);
+
+        // Neither bucket was modified
+        assert_eq!(a, [0, 1, 2]);
+        assert_eq!(b, [10, 11, 12]);
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(b.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    #[should_panic(expected = "stale or dangling")]
+    fn test_get_foreign() {
+        let mut a = Bucket::new(1);
+        let mut b = Bucket::new(1);
+        let idx = a.insert(1);
+        b.insert(2);
+        b.get(&idx);
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_get_mut_after_relocation() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(String::from("a"));
+        let b = bucket.insert(String::from("b"));
+        let c = bucket.insert(String::from("c"));
+
+        // Removing the first element moves the last one into its slot
+        bucket.remove_index(&a.0);
+        assert_eq!(c.0.load(Ordering::Relaxed), 0);
+
+        bucket.get_mut(&c).push('!');
+        *bucket.get_mut(&b) += "?";
+        assert_eq!(bucket.get(&c), "c!");
+        assert_eq!(bucket.get(&b), "b?");
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    #[should_panic(expected = "stale or dangling")]
+    fn test_get_mut_stale() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove_index(&a.0);
+        bucket.insert(2);
+        *bucket.get_mut(&stale) += 1;
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert(42);
+        assert_eq!(bucket.remove(idx), Some(42));
+
+        assert!(bucket.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_growth() {
+        let mut bucket = Bucket::new(2);
+        bucket.insert(1);
+        bucket.insert(2);
+        bucket.insert(3); // Triggers growth
+        assert_eq!(bucket.len(), 3);
+    }
+
+    #[test]
+    fn test_capacity_shrink() {
+        let mut bucket = Bucket::new(10);
+        for i in 0..10 {
+            bucket.insert(i);
+        }
+        bucket.capacity.shrink();
+        assert_eq!(bucket.capacity(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_edge_cases() {
+        let mut bucket = Bucket::new(1);
+        let idx = bucket.insert(10);
+        assert_eq!(*bucket.get(&idx), 10);
+        bucket.remove(idx);
+        assert!(bucket.is_empty());
+    }
+
+    #[test]
+    fn test_remove_borrowed() {
+        struct Entity {
+            handle: ValueIndex,
+        }
+
+        let mut bucket = Bucket::new(2);
+        let entity = Entity {
+            handle: bucket.insert('a'),
+        };
+        let other = bucket.insert('b');
+
+        assert_eq!(bucket.remove(&entity.handle), Some('a'));
+        assert!(entity.handle.is_dangling());
+        assert_eq!(bucket.try_get(&entity.handle), None);
+
+        // The slot is reused, but never through the stale handle
+        let c = bucket.insert('c');
+        assert_eq!(bucket.remove(&entity.handle), None);
+        assert_eq!(bucket.try_get(&other), Some(&'b'));
+        assert_eq!(bucket.try_get(&c), Some(&'c'));
+    }
+
+    #[test]
+    fn test_remove_empty() {
+        let mut bucket = Bucket::new(1);
+        let idx = bucket.insert(1);
+        let idx_clone = ValueIndex(idx.0.clone());
+        bucket.remove(idx);
+        assert_eq!(bucket.remove(idx_clone), None)
+    }
+
+    #[cfg(all(feature = "clone", feature = "get"))]
+    #[test]
+    fn test_cloned_handle_dangling() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert(1);
+        let idx_clone = idx.clone();
+        let other = bucket.insert(2);
+
+        bucket.remove(idx);
+        assert!(idx_clone.is_dangling());
+        assert_eq!(bucket.try_get(&idx_clone), None);
+        assert_eq!(*bucket.get(&other), 2);
+        assert_eq!(bucket.remove(idx_clone), None);
+    }
+
+    #[test]
+    fn test_remove_unchecked() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+
+        // SAFETY: every handle is removed once, while its element is stored
+        unsafe {
+            assert_eq!(bucket.remove_unchecked(&handles[0]), 0);
+            assert_eq!(bucket.remove_unchecked(&handles[4]), 4);
+            assert_eq!(bucket.remove_unchecked(&handles[2]), 2);
+        }
+        assert!(handles[0].is_dangling());
+        assert_eq!(bucket.try_get(&handles[3]), Some(&3));
+        assert_eq!(bucket.capacity(), 2);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_remove_stale_from_empty() {
+        let mut bucket = Bucket::new(1);
+        let idx = bucket.insert(1);
+        let stale = ValueIndex(idx.0.clone());
+        assert_eq!(bucket.remove_index(&idx.0), Some(1));
+
+        // The checked paths refuse the handle rather than reading out of bounds
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.remove_index(&stale.0), None);
+        assert_eq!(bucket.try_remove(&stale), Err(RemoveError::Dangling));
+        assert_eq!(bucket.pop(), None);
+    }
+
+    #[test]
+    fn test_capacity_management() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        let c = bucket.insert(3); // Should trigger growth
+
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.len(), 3);
+
+        bucket.remove(a);
+        bucket.remove(b);
+        bucket.remove(c); // Should trigger shrinking
+
+        assert_eq!(bucket.capacity(), 2);
+    }
+
+    #[test]
+    fn test_iter_after_removal() {
+        let mut bucket = Bucket::new(5);
+        let idx1 = bucket.insert(1);
+        _ = bucket.insert(2);
+        bucket.remove(idx1);
+
+        let values: Vec<_> = bucket.iter().map(|v| *v).collect();
+
+        assert_eq!(values, vec![&2]);
+    }
+
+    #[test]
+    fn test_value_ref_handles() {
+        let mut bucket = Bucket::new(2);
+        (0..5).for_each(|i| drop(bucket.insert(i)));
+
+        let three = bucket
+            .iter()
+            .find(|v| **v == &3)
+            .map(|v| v.index())
+            .unwrap();
+        let slots: Vec<_> = bucket.iter().map(|v| v.slot()).collect();
+        assert_eq!(slots, [0, 1, 2, 3, 4]);
+
+        let four = bucket
+            .iter_mut()
+            .find_map(|mut v| {
+                (*v == 4).then(|| {
+                    *v = 40;
+                    v.index()
+                })
+            })
+            .unwrap();
+
+        assert_eq!(bucket.remove(three), Some(3));
+        assert_eq!(bucket.try_get(&four), Some(&40));
+        assert_eq!(bucket.iter_mut().map(|v| v.slot()).max(), Some(3));
+        assert_eq!(bucket.remove(four), Some(40));
+    }
+
+    #[test]
+    fn test_iter_indexed() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i)).collect();
+
+        let even: Vec<_> = bucket
+            .iter_indexed()
+            .filter(|(_, v)| *v % 2 == 0)
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(even.len(), 5);
+
+        // Removing relocates the survivors, which keep resolving
+        for idx in &even {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.len(), 5);
+        for (i, idx) in handles.iter().enumerate() {
+            match i % 2 {
+                0 => assert!(!bucket.contains(idx)),
+                _ => assert_eq!(bucket.try_get(idx), Some(&i)),
+            }
+        }
+        assert!(bucket
+            .iter_indexed()
+            .all(|(idx, v)| bucket.try_get(&idx) == Some(v)));
+    }
+
+    #[test]
+    fn test_indices() {
+        let mut bucket = Bucket::new(4);
+        (0..12).for_each(|i| drop(bucket.insert(i)));
+
+        let indices: Vec<_> = bucket.indices().collect();
+        assert_eq!(indices.len(), 12);
+
+        // Every other removal moves the last element into the freed slot
+        let (removed, kept): (Vec<_>, Vec<_>) = indices
+            .iter()
+            .partition(|idx| idx.0.load(Ordering::Relaxed) % 2 == 0);
+        for idx in &removed {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.len(), 6);
+        assert!(removed.iter().all(|idx| idx.is_dangling()));
+        for idx in &kept {
+            assert!(bucket.contains(idx));
+        }
+        let mut values: Vec<_> = kept
+            .iter()
+            .map(|idx| *bucket.try_get(idx).unwrap())
+            .collect();
+        values.sort();
+        assert_eq!(values, [1, 3, 5, 7, 9, 11]);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_iter_mut() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..7).map(|i| bucket.insert(i * 10)).collect();
+        bucket.remove_index(&handles[2].0);
+
+        bucket.iter_mut().for_each(|mut v| *v += 1);
+
+        for (i, idx) in handles.iter().enumerate() {
+            match i {
+                2 => assert!(!bucket.contains(idx)),
+                _ => assert_eq!(*bucket.get(idx), i * 10 + 1),
+            }
+        }
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_take_put_back() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(String::from("a"));
+        let b = bucket.insert(String::from("b"));
+        let capacity = bucket.capacity();
+
+        assert_eq!(bucket.take(&a), Some(String::from("a")));
+        assert_eq!(bucket.get(&a), "");
+        assert_eq!(bucket.get(&b), "b");
+        assert_eq!(bucket.len(), 2);
+        assert_eq!(bucket.capacity(), capacity);
+
+        assert_eq!(bucket.put_back(&a, String::from("c")), Some(String::new()));
+        assert_eq!(bucket.get(&a), "c");
+        assert_eq!(a.0.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_replace_keeps_handle() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..3).map(|i| bucket.insert(i)).collect();
+
+        // Relocate the last element first, so the replaced one is not in its original slot
+        bucket.remove_index(&handles[0].0);
+        let capacity = bucket.capacity();
+        assert_eq!(bucket.replace(&handles[2], 20), Some(2));
+        assert_eq!(bucket.replace(&handles[2], 200), Some(20));
+
+        assert_eq!(bucket.try_get(&handles[2]), Some(&200));
+        assert_eq!(bucket.try_get(&handles[1]), Some(&1));
+        assert_eq!(handles[2].0.load(Ordering::Relaxed), 0);
+        assert_eq!(bucket.len(), 2);
+        assert_eq!(bucket.capacity(), capacity);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_take_keeps_slots() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (1..=5).map(|i| bucket.insert(vec![i])).collect();
+        let slots: Vec<_> = handles.iter().map(|h| bucket.slot(&h.0)).collect();
+        let capacity = bucket.capacity();
+
+        assert_eq!(bucket.take(&handles[1]), Some(vec![2]));
+        assert_eq!(bucket.take(&handles[1]), Some(vec![]));
+        assert_eq!(bucket.put_back(&handles[1], vec![20]), Some(vec![]));
+
+        // No element moved and nothing was freed
+        assert_eq!(
+            handles
+                .iter()
+                .map(|h| bucket.slot(&h.0))
+                .collect::<Vec<_>>(),
+            slots
+        );
+        assert_eq!(bucket, [vec![1], vec![20], vec![3], vec![4], vec![5]]);
+        assert_eq!(bucket.len(), 5);
+        assert_eq!(bucket.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_take_stale() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert(1);
+        let idx_clone = ValueIndex(idx.0.clone());
+        bucket.remove(idx);
+        assert_eq!(bucket.take(&idx_clone), None);
+        assert_eq!(bucket.replace(&idx_clone, 2), None);
+        assert!(bucket.is_empty());
     }
 
-    fn _remove(&mut self, i: usize) -> T {
-        let j = self.len() - 1;
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_replace_with() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(vec![1]);
+        let b = bucket.insert(vec![2]);
+        let c = bucket.insert(vec![3]);
+
+        assert!(bucket.replace_with(&a, |mut v| {
+            v.push(10);
+            v
+        }));
+        assert!(bucket.replace_with(&c, |_| vec![30]));
+
+        assert_eq!(bucket.get(&a), &[1, 10]);
+        assert_eq!(bucket.get(&b), &[2]);
+        assert_eq!(bucket.get(&c), &[30]);
+        assert_eq!(a.0.load(Ordering::Relaxed), 0);
+        assert_eq!(c.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_replace_with_panic() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        let c = bucket.insert(3);
+        let a_clone = ValueIndex(a.0.clone());
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bucket.replace_with(&a, |_| panic!("transition failed"))
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(bucket.len(), 2);
+        assert_eq!(bucket.slot(&a.0), None);
+        assert_eq!(*bucket.get(&b), 2);
+        assert_eq!(*bucket.get(&c), 3);
+        assert!(!bucket.replace_with(&a_clone, |v| v));
+    }
+
+    #[test]
+    fn test_reserve() {
+        use std::sync::{Arc, Mutex};
+
+        let mut bucket = Bucket::new(4);
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let log = changes.clone();
+        bucket.set_on_capacity_change(move |change| log.lock().unwrap().push(change.reason));
+
+        bucket.reserve(1000);
+        assert_eq!(bucket.capacity(), 1000);
+        let allocated = bucket.allocated_capacity();
+        assert!(allocated >= 1000);
+
+        // Inserting up to the reserved capacity neither grows nor reallocates
+        let handles: Vec<_> = (0..1000).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.allocated_capacity(), allocated);
+        assert_eq!(*changes.lock().unwrap(), [CapacityChangeReason::Reserve]);
+
+        // Shrinking steps back down as usual
+        for idx in &handles {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        bucket.reserve(2);
+        assert_eq!(bucket.capacity(), 4);
+        bucket.reserve_exact(10);
+        assert_eq!(bucket.capacity(), 12);
+        let allocated = bucket.allocated_capacity();
+        assert!(allocated >= 12);
+
+        // The whole stepped capacity is allocated, not only the requested room
+        for i in 0..12 {
+            bucket.insert(i);
+        }
+        assert_eq!(bucket.allocated_capacity(), allocated);
 
-        if self.len() > 1 && i < j {
-            // Swap with the last element
-            self.data.swap(i, j);
+        let mut bounded = Bucket::<u8>::bounded_overwriting(3);
+        bounded.reserve(10);
+        assert_eq!(bounded.capacity(), 3);
+    }
+
+    #[test]
+    fn test_reserve_then_remove_few() {
+        let mut bucket = Bucket::new(4);
+        bucket.reserve(100);
+        assert_eq!(bucket.capacity(), 100);
+
+        // Several unused steps are released by a single removal
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[0].0);
+        assert_eq!(bucket.capacity(), 12);
+        for idx in &handles[1..] {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
 
-            // Update the index of the swapped element
-            self.data[i].index.store(i, Ordering::Relaxed)
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut bucket = Bucket::new(8);
+        let handles: Vec<_> = (0..10_000).map(|i| bucket.insert(i)).collect();
+        for idx in &handles[10..] {
+            bucket.remove_index(&idx.0);
+        }
+        bucket.shrink_to_fit();
+        assert_eq!(bucket.capacity(), 16);
+        assert!(bucket.allocated_capacity() >= 10);
+
+        // Without removals, capacity reserved up front is only released on request
+        bucket.reserve(10_000);
+        bucket.shrink_to(100);
+        assert_eq!(bucket.capacity(), 104);
+        bucket.shrink_to(1000);
+        assert_eq!(bucket.capacity(), 104);
+        bucket.shrink_to_fit();
+        assert_eq!(bucket.capacity(), 16);
+        assert!(bucket.allocated_capacity() >= 10);
+
+        for (i, idx) in handles[..10].iter().enumerate() {
+            assert_eq!(bucket.try_get(idx), Some(&i));
         }
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // Never below the original capacity
+        let mut bucket = Bucket::with_policy(4, GrowthPolicy::Doubling);
+        bucket.reserve(100);
+        bucket.insert(1);
+        bucket.shrink_to_fit();
+        assert_eq!(bucket.capacity(), 4);
+    }
 
-        // Remove and return the element at the index
-        let value = {
-            #[cfg(test)]
-            {
-                self.data.pop().unwrap()
+    #[test]
+    fn test_degenerate_capacities() {
+        for original in [0, 1] {
+            let mut bucket = Bucket::new(original);
+            let mut live = Vec::new();
+            let mut state = 17u32;
+
+            // Mixed inserts and removals driven by a small linear congruential generator
+            for i in 0..500 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                if live.is_empty() || !(state >> 16).is_multiple_of(3) {
+                    live.push((bucket.insert(i), i));
+                } else {
+                    let (idx, value) = live.swap_remove((state >> 16) as usize % live.len());
+                    assert_eq!(bucket.remove_index(&idx.0), Some(value));
+                }
+                assert!(bucket.len() <= bucket.capacity());
+                assert_eq!(bucket.validate(), Ok(()));
+            }
+            for (idx, value) in &live {
+                assert_eq!(bucket.try_get(idx), Some(value));
             }
 
-            #[cfg(not(test))]
-            unsafe {
-                self.data.pop().unwrap_unchecked()
+            // Emptying the bucket steps its capacity back down to the original one
+            for (idx, _) in live {
+                bucket.remove_index(&idx.0);
             }
-        };
+            assert_eq!(bucket.capacity(), original);
+        }
+    }
 
-        // Shrink the capacity if needed
-        if j > 0 && j == self.capacity.current - self.capacity.original {
-            self.shrink()
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_insert_many() {
+        let mut bucket = Bucket::new(8);
+        let first = bucket.insert(usize::MAX);
+
+        let handles = bucket.insert_many(0..10_000);
+        assert_eq!(handles.len(), 10_000);
+        assert_eq!(bucket.len(), 10_001);
+        assert_eq!(bucket.capacity(), 10_008);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(*bucket.get(idx), i);
         }
-        value.data
+        assert_eq!(*bucket.get(&first), usize::MAX);
+        assert!(bucket.insert_many(std::iter::empty()).is_empty());
     }
 
-    /// Increases the capacity of the `Bucket`.
-    ///
-    /// This method is called internally when the `Bucket` is full.
-    fn grow(&mut self) {
-        self.capacity.grow();
-        self.data.reserve(self.capacity.original);
+    #[test]
+    fn test_extend_from_empty_bucket() {
+        let mut bucket = Bucket::new(4);
+        bucket.insert(1);
+
+        assert!(bucket.extend_from_bucket(&Bucket::new(4)).is_empty());
+        assert_eq!(bucket.len(), 1);
+        assert_eq!(bucket.capacity(), 4);
     }
 
-    /// Decreases the capacity of the `Bucket`.
-    ///
-    /// This method is called internally when the `Bucket` has extra capacity
-    /// after removing elements.
-    fn shrink(&mut self) {
-        self.capacity.shrink();
-        self.data.shrink_to(self.capacity.current);
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_extend_from_bucket() {
+        let mut src = Bucket::new(8);
+        for i in 0..100 {
+            src.insert(i);
+        }
+        let mut bucket = Bucket::new(3);
+        bucket.insert(-1);
+
+        let handles = bucket.extend_from_bucket(&src);
+
+        assert_eq!(handles.len(), 100);
+        assert_eq!(bucket.len(), 101);
+        assert_eq!(bucket.capacity(), 102);
+        assert_eq!(src.len(), 100);
+
+        for (slot, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.get(idx), &src.data[slot].data);
+        }
     }
-}
 
-impl<T> Default for Bucket<T> {
-    /// Creates an empty `Bucket` with a default initial capacity.
-    fn default() -> Self {
-        Self::new(32)
+    #[test]
+    fn test_swap() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..4).map(|i| bucket.insert(i)).collect();
+
+        bucket.swap(&handles[0], &handles[3]);
+        bucket.swap(&handles[1], &handles[1]);
+        assert_eq!(bucket, [3, 1, 2, 0]);
+        assert_eq!(bucket.try_get(&handles[0]), Some(&0));
+        assert_eq!(bucket.try_get(&handles[3]), Some(&3));
+
+        // The last slot is now held by the first value
+        bucket.remove_index(&handles[3].0);
+        assert_eq!(bucket, [0, 1, 2]);
+        assert_eq!(bucket.try_get(&handles[0]), Some(&0));
+        assert_eq!(bucket.validate(), Ok(()));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    #[should_panic(expected = "stale or dangling")]
+    fn test_swap_stale() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove_index(&a.0);
+        bucket.swap(&b, &stale);
+    }
 
     #[test]
-    fn test_initialization() {
-        let bucket = Bucket::<u8>::new(10);
-        assert_eq!(bucket.len(), 0);
+    fn test_pop() {
+        let mut bucket = Bucket::new(2);
+        assert_eq!(bucket.pop(), None);
+
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.capacity(), 6);
+
+        // Alternate insertions with pops until the bucket is empty
+        let mut popped = Vec::new();
+        for i in 5..8 {
+            let idx = bucket.insert(i);
+            popped.extend(bucket.pop());
+            popped.extend(bucket.pop());
+            assert_eq!(bucket.try_remove(&idx), Err(RemoveError::Dangling));
+        }
+        assert_eq!(popped, vec![5, 4, 6, 3, 7, 2]);
+        assert!(handles[..2].iter().all(|h| bucket.contains(h)));
+        assert_eq!(handles[0].0.load(Ordering::Relaxed), 0);
+        assert_eq!(handles[1].0.load(Ordering::Relaxed), 1);
+
+        assert_eq!(bucket.pop(), Some(1));
+        assert_eq!(bucket.pop(), Some(0));
+        assert_eq!(bucket.pop(), None);
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+        assert_eq!(bucket.capacity(), 2);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "get")]
+    fn test_pop_by_key() {
+        let mut bucket = Bucket::new(4);
+        let jobs = [("a", 2), ("b", 5), ("c", 1), ("d", 5), ("e", 1), ("f", 3)];
+        let handles: Vec<_> = jobs.iter().map(|&job| bucket.insert(job)).collect();
+
+        // Ties go to the lowest slot
+        assert_eq!(bucket.pop_max_by_key(|j| j.1), Some(("b", 5)));
+        assert_eq!(bucket.pop_min_by_key(|j| j.1), Some(("c", 1)));
+
+        // "f" was swapped into slot 1 and "e" into slot 2
+        assert_eq!(bucket.pop_max_by_key(|j| j.1), Some(("d", 5)));
+        assert_eq!(bucket.pop_min_by_key(|j| j.1), Some(("e", 1)));
+
+        assert_eq!(*bucket.get(&handles[0]), ("a", 2));
+        assert_eq!(*bucket.get(&handles[5]), ("f", 3));
+        assert_eq!(bucket.len(), 2);
+    }
+
+    #[test]
+    fn test_pop_by_key_small() {
+        let mut bucket = Bucket::<u8>::new(1);
+        assert_eq!(bucket.pop_min_by_key(|&v| v), None);
+        assert_eq!(bucket.pop_max_by_key(|&v| v), None);
+
+        bucket.insert(7);
+        assert_eq!(bucket.pop_max_by_key(|&v| v), Some(7));
+        assert!(bucket.is_empty());
+
+        bucket.insert(8);
+        assert_eq!(bucket.pop_min_by_key(|&v| v), Some(8));
         assert!(bucket.is_empty());
     }
 
+    #[test]
+    fn test_iter_since() {
+        let mut bucket = Bucket::new(2);
+        fn since(bucket: &Bucket<char>, seq: u64) -> Vec<(u64, char)> {
+            bucket.iter_since(seq).map(|(s, v)| (s, **v)).collect()
+        }
+        assert_eq!(bucket.current_seq(), 0);
+
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let mark = bucket.current_seq();
+        assert_eq!(since(&bucket, 0), vec![(1, 'a'), (2, 'b')]);
+        assert_eq!(since(&bucket, mark), vec![]);
+
+        let c = bucket.insert('c');
+        bucket.insert('d');
+        bucket.remove(c);
+        bucket.remove(a);
+        assert_eq!(since(&bucket, mark), vec![(4, 'd')]);
+
+        let mark = bucket.current_seq();
+        bucket.insert('e');
+        bucket.remove(b);
+        bucket.insert('f');
+        assert_eq!(since(&bucket, mark), vec![(5, 'e'), (6, 'f')]);
+        assert_eq!(since(&bucket, 0), vec![(4, 'd'), (5, 'e'), (6, 'f')]);
+    }
+
+    #[test]
+    fn test_value_as_ref() {
+        fn len<S: AsRef<str>>(s: S) -> usize {
+            s.as_ref().len()
+        }
+
+        let mut bucket = Bucket::new(2);
+        bucket.insert(String::from("abc"));
+
+        let value = &mut bucket.data[0];
+        assert_eq!(len(AsRef::<String>::as_ref(value)), 3);
+
+        value.as_mut().push('d');
+        BorrowMut::<String>::borrow_mut(value).push('e');
+        assert_eq!(Borrow::<String>::borrow(value), "abcde");
+
+        let value_ref: ValueRef<'_, String> = (&bucket.data[0]).into();
+        assert_eq!(len(AsRef::<String>::as_ref(&value_ref)), 5);
+    }
+
+    #[test]
+    fn test_borrow_laws() {
+        fn hash<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut bucket = Bucket::new(4);
+        for s in ["a", "b", "a", "c"] {
+            bucket.insert(String::from(s));
+        }
+        let values = &bucket.data;
+
+        for x in values {
+            let x_ref: ValueRef<'_, String> = x.into();
+            let borrowed: &String = x.borrow();
+            assert_eq!(hash(x), hash(borrowed));
+            assert_eq!(hash(&x_ref), hash(Borrow::<String>::borrow(&x_ref)));
+
+            for y in values {
+                let y_ref: ValueRef<'_, String> = y.into();
+                let eq = Borrow::<String>::borrow(x) == Borrow::<String>::borrow(y);
+                let cmp = Borrow::<String>::borrow(x).cmp(Borrow::<String>::borrow(y));
+
+                assert_eq!(x == y, eq);
+                assert_eq!(x_ref == y_ref, eq);
+                assert_eq!(x.cmp(y), cmp);
+                assert_eq!(x_ref.cmp(&y_ref), cmp);
+            }
+        }
+    }
+
+    #[test]
+    fn test_as_entries() {
+        let mut bucket = Bucket::new(3);
+        let mut handles: Vec<_> = (0..20).map(|i| bucket.insert(i)).collect();
+
+        for step in 0..30 {
+            if step % 3 == 0 {
+                handles.push(bucket.insert(step + 100));
+            } else {
+                let idx = handles.swap_remove(step * 7 % handles.len());
+                bucket.remove(idx);
+            }
+
+            let entries = bucket.as_entries();
+            assert_eq!(entries.len(), bucket.len());
+
+            for (i, entry) in entries.iter().enumerate() {
+                assert_eq!(entry.slot(), i);
+                assert!(Index::ptr_eq(&entry.index().0, &entry.index));
+            }
+        }
+
+        for idx in &handles {
+            let entry = &bucket.as_entries()[idx.0.load(Ordering::Relaxed)];
+            assert!(Index::ptr_eq(&entry.index().0, &idx.0));
+            assert_eq!(entry.as_ref(), &entry.data);
+        }
+    }
+
+    #[test]
+    fn test_dangling_handle() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert(1);
+        let dangling = ValueIndex::default();
+
+        assert!(dangling.is_dangling());
+        assert!(!idx.is_dangling());
+        assert!(!bucket.contains(&dangling));
+        assert_eq!(bucket.replace(&dangling, 2), None);
+        assert!(!bucket.replace_with(&dangling, |v| v + 1));
+        assert_eq!(bucket.len(), 1);
+        assert!(bucket.contains(&idx));
+    }
+
+    #[test]
+    fn test_removed_handle_is_dangling() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let copy = ValueIndex(a.0.clone());
+
+        // The slot of `a` is now held by `b`, but the copy does not resolve to it
+        bucket.remove_index(&a.0);
+        assert!(a.is_dangling());
+        assert!(copy.is_dangling());
+        assert!(!b.is_dangling());
+        assert_eq!(bucket.try_get(&copy), None);
+        assert_eq!(bucket.remove_index(&copy.0), None);
+        assert_eq!(bucket, ['b']);
+
+        let c = bucket.insert('c');
+        bucket.pop();
+        assert!(c.is_dangling());
+    }
+
     #[test]
     #[cfg(feature = "get")]
-    fn test_insert() {
+    #[should_panic(expected = "stale or dangling")]
+    fn test_get_dangling() {
         let mut bucket = Bucket::new(2);
-        let idx1 = bucket.insert(42);
-        let idx2 = bucket.insert(43);
-        assert_eq!(*bucket.get(&idx1), 42);
-        assert_eq!(*bucket.get(&idx2), 43);
+        bucket.insert(1);
+        bucket.get(&ValueIndex::dangling());
     }
 
     #[test]
-    fn test_remove() {
+    fn test_remove_dangling() {
         let mut bucket = Bucket::new(2);
-        let idx = bucket.insert(42);
-        let value = bucket.remove(idx);
+        bucket.insert(1);
+        assert_eq!(bucket.remove(ValueIndex::dangling()), None);
+        assert_eq!(bucket.len(), 1);
+    }
+
+    #[test]
+    fn test_dangling_late_initialization() {
+        #[derive(Default)]
+        struct Component {
+            handle: ValueIndex,
+        }
 
-        #[cfg(not(feature = "clone"))]
-        assert_eq!(value, 42);
+        let mut bucket = Bucket::new(2);
+        let mut component = Component::default();
+        assert!(component.handle.is_dangling());
+        assert!(Index::ptr_eq(
+            &component.handle.0,
+            &ValueIndex::dangling().0
+        ));
+
+        component.handle = bucket.insert('a');
+        assert!(!component.handle.is_dangling());
+        assert!(bucket.contains(&component.handle));
+
+        // The sentinel is untouched by the real handle
+        let dangling = ValueIndex::dangling();
+        assert!(!Index::ptr_eq(&component.handle.0, &dangling.0));
+        assert_eq!(dangling.0.load(Ordering::Relaxed), DANGLING);
+    }
 
-        #[cfg(feature = "clone")]
-        assert_eq!(value, Some(42));
+    #[test]
+    fn test_raw_handles() {
+        use std::rc::Rc;
 
+        let counter = Rc::new(());
+        let mut bucket = Bucket::new(2);
+        let raw: Vec<_> = (0..5)
+            .map(|_| bucket.insert(counter.clone()).into_raw())
+            .collect();
+        let strong = Index::strong_count(&bucket.data[0].index);
+
+        // SAFETY: each pointer was returned by `into_raw` and is still owned
+        let copies: Vec<_> = raw
+            .iter()
+            .map(|&p| unsafe { ValueIndex::clone_raw(p) })
+            .collect();
+        assert_eq!(Index::strong_count(&bucket.data[0].index), strong + 1);
+
+        for (copy, &p) in copies.iter().zip(&raw) {
+            assert!(bucket.contains(copy));
+            assert_eq!(Index::as_ptr(&copy.0).cast(), p);
+        }
+        for copy in copies {
+            bucket.remove_index(&copy.0);
+        }
         assert!(bucket.is_empty());
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        // SAFETY: each pointer is reconstituted exactly once
+        let handles: Vec<_> = raw
+            .into_iter()
+            .map(|p| unsafe { ValueIndex::from_raw(p) })
+            .collect();
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+        assert!(handles.iter().all(|h| Index::strong_count(&h.0) == 1));
     }
 
     #[test]
-    fn test_capacity_growth() {
+    fn test_value_clone_and_detach() {
         let mut bucket = Bucket::new(2);
-        bucket.insert(1);
-        bucket.insert(2);
-        bucket.insert(3); // Triggers growth
-        assert_eq!(bucket.len(), 3);
+        let a = bucket.insert(String::from("a"));
+        let b = bucket.insert(String::from("b"));
+
+        let i = bucket.slot(&a.0).unwrap();
+        let taken = bucket._remove_value(i);
+        let stashed = taken.clone();
+        assert!(Index::ptr_eq(&stashed.index, &a.0));
+
+        // The detached handle no longer resolves once its element is gone
+        let (data, idx) = taken.detach();
+        assert!(!bucket.contains(&idx));
+        let reinserted = bucket.insert(data);
+
+        assert_eq!(*stashed, "a");
+        assert_eq!(
+            bucket.replace(&reinserted, String::from("c")).as_deref(),
+            Some("a")
+        );
+        assert!(!bucket.contains(&stashed.index()));
+
+        let (copy, idx) = bucket.as_entries()[bucket.slot(&b.0).unwrap()].to_detached();
+        assert_eq!(copy, "b");
+        assert!(Index::ptr_eq(&idx.0, &b.0));
     }
 
     #[test]
-    fn test_capacity_shrink() {
-        let mut bucket = Bucket::new(10);
-        for i in 0..10 {
-            bucket.insert(i);
+    fn test_value_index_and_into_inner() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(String::from("a"));
+        bucket.insert(String::from("b"));
+
+        let value = bucket._remove_value(bucket.slot(&a.0).unwrap());
+        assert!(Index::ptr_eq(&value.index().0, &a.0));
+        assert_eq!(value.into_inner(), "a");
+
+        // A standalone value never resolves against a bucket
+        let value = Value::new(String::from("c"));
+        assert!(value.index().is_dangling());
+        assert!(!bucket.contains(&value.index()));
+        assert_eq!(value.into_inner(), "c");
+    }
+
+    #[test]
+    fn test_remove_patterns() {
+        let mut bucket = Bucket::new(4);
+        let mut handles: Vec<_> = (0..64).map(|i| bucket.insert(i)).collect();
+
+        // Remove from the back, which never relocates an element
+        for _ in 0..16 {
+            let idx = handles.pop().unwrap();
+            assert!(bucket.remove_index(&idx.0).is_some());
+            assert_eq!(bucket.validate(), Ok(()));
         }
-        bucket.capacity.shrink();
-        assert_eq!(bucket.capacity(), 0);
+
+        // Remove from pseudo-random slots, which relocates the last element
+        let mut r = 7;
+        while !handles.is_empty() {
+            r = (r * 31 + 11) % 97;
+            let idx = handles.swap_remove(r % handles.len());
+            let i = bucket.slot(&idx.0).unwrap();
+            assert!(bucket.remove_index(&idx.0).is_some());
+            assert_eq!(bucket.validate(), Ok(()));
+            assert!(handles.iter().all(|h| bucket.contains(h)));
+            assert!(bucket.len() <= i || bucket.slot(&bucket.data[i].index).is_some());
+        }
+        assert_eq!(bucket.capacity(), 4);
     }
 
     #[test]
-    #[cfg(feature = "get")]
-    fn test_edge_cases() {
-        let mut bucket = Bucket::new(1);
-        let idx = bucket.insert(10);
-        assert_eq!(*bucket.get(&idx), 10);
-        bucket.remove(idx);
+    fn test_retain() {
+        let mut r = 11;
+        for round in 0..20 {
+            let n = 1 + round * 7;
+            let mut bucket = Bucket::new(4);
+            let handles: Vec<_> = (0..n).map(|i| bucket.insert(i)).collect();
+
+            // Retain a pseudo-random subset
+            let keep: Vec<bool> = (0..n)
+                .map(|_| {
+                    r = (r * 1103 + 12345) % 65_536;
+                    r % 3 != 0
+                })
+                .collect();
+            let mut visited = 0;
+            bucket.retain(|&v| {
+                visited += 1;
+                keep[v]
+            });
+
+            assert_eq!(visited, n);
+            assert_eq!(bucket.len(), keep.iter().filter(|&&k| k).count());
+            assert_eq!(bucket.validate(), Ok(()));
+            assert_eq!(bucket.capacity(), bucket.len().div_ceil(4).max(1) * 4);
+
+            for (i, idx) in handles.iter().enumerate() {
+                match keep[i] {
+                    true => assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i),
+                    false => assert!(!bucket.contains(idx)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut bucket = Bucket::new(8);
+        let old: Vec<_> = (0..100).map(|i| bucket.insert(i)).collect();
+
+        bucket.clear();
         assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 8);
+        assert_eq!(bucket.allocated_capacity(), 8);
+
+        // The old handles are marked as dangling, so they never resolve to the new values
+        let new: Vec<_> = (100..105).map(|i| bucket.insert(i)).collect();
+        assert!(old.iter().all(|h| h.0.load(Ordering::Relaxed) == DANGLING));
+        assert!(old.iter().all(|h| !bucket.contains(h)));
+        assert_eq!(bucket.replace(&old[0], 0), None);
+        for (i, idx) in new.iter().enumerate() {
+            assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i + 100);
+        }
     }
 
-    #[cfg(not(feature = "clone"))]
     #[test]
-    #[should_panic]
-    fn test_remove_empty() {
-        let mut bucket = Bucket::new(1);
-        let idx = bucket.insert(1);
-        let idx_clone = ValueIndex(idx.0.clone());
-        bucket.remove(idx);
-        bucket.remove(idx_clone); // Should panic as index is invalid
+    fn test_clone_independent_handles() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[0].0);
+
+        let mut clone = bucket.clone();
+        assert_eq!(
+            clone,
+            bucket.data.iter().map(|v| v.data).collect::<Vec<_>>()
+        );
+        assert_eq!(clone.capacity(), bucket.capacity());
+        assert_eq!(clone.current_seq(), bucket.current_seq());
+        assert!(handles.iter().all(|h| !clone.contains(h)));
+
+        // Removing from the clone relocates only its own elements
+        let cloned: Vec<_> = clone.data.iter().map(|v| v.index()).collect();
+        for idx in cloned.iter().step_by(2) {
+            clone.remove_index(&idx.0);
+        }
+        assert_eq!(clone.len(), 4);
+        assert_eq!(clone.validate(), Ok(()));
+
+        assert_eq!(bucket.validate(), Ok(()));
+        for (i, idx) in handles.iter().enumerate().skip(1) {
+            assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i);
+        }
     }
 
-    #[cfg(feature = "clone")]
     #[test]
-    fn test_remove_empty() {
-        let mut bucket = Bucket::new(1);
-        let idx = bucket.insert(1);
-        let idx_clone = ValueIndex(idx.0.clone());
-        bucket.remove(idx);
-        assert_eq!(bucket.remove(idx_clone), None)
+    fn test_clone_interned() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern("a");
+        bucket.intern("a");
+
+        let mut clone = bucket.clone();
+        let a_clone = clone.intern("a");
+        assert_eq!(clone.len(), 1);
+        assert_eq!(clone.release(&a_clone), None);
+        assert_eq!(clone.release(&a_clone), None);
+        assert_eq!(clone.release(&a_clone), Some("a"));
+
+        assert_eq!(bucket.release(&a), None);
+        assert_eq!(bucket.release(&a), Some("a"));
     }
 
     #[test]
-    fn test_capacity_management() {
+    fn test_into_entries() {
         let mut bucket = Bucket::new(2);
-        let a = bucket.insert(1);
-        let b = bucket.insert(2);
-        let c = bucket.insert(3); // Should trigger growth
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[1].0);
 
-        assert_eq!(bucket.capacity(), 4);
-        assert_eq!(bucket.len(), 3);
+        let entries = bucket.into_entries();
+        assert_eq!(entries.len(), 4);
 
-        bucket.remove(a);
-        bucket.remove(b);
-        bucket.remove(c); // Should trigger shrinking
+        // The last element was moved into the slot of the removed one
+        let expected = [0, 4, 2, 3];
+        for ((idx, value), i) in entries.zip(expected) {
+            assert_eq!(value, i);
+            assert!(Index::ptr_eq(&idx.0, &handles[i].0));
+        }
+    }
 
-        assert_eq!(bucket.capacity(), 2);
+    #[test]
+    fn test_into_entries_abandoned() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut bucket = Bucket::new(4);
+        for _ in 0..6 {
+            bucket.insert(counter.clone());
+        }
+
+        let mut entries = bucket.into_entries();
+        let first = entries.next();
+        assert_eq!(entries.len(), 5);
+        drop(entries);
+
+        // Only the yielded value is still alive
+        assert_eq!(Rc::strong_count(&counter), 2);
+        drop(first);
+        assert_eq!(Rc::strong_count(&counter), 1);
     }
 
     #[test]
-    fn test_iter_after_removal() {
-        let mut bucket = Bucket::new(5);
-        let idx1 = bucket.insert(1);
-        _ = bucket.insert(2);
-        bucket.remove(idx1);
+    fn test_into_sorted_vec() {
+        let mut bucket = Bucket::new(2);
+        for (i, word) in ["pear", "fig", "apple", "kiwi", "plum", "date"]
+            .into_iter()
+            .enumerate()
+        {
+            bucket.insert((word, i));
+        }
+        bucket.remove_index(&bucket.data[0].index.clone());
+
+        // Equal lengths keep their slot order
+        let sorted = bucket.into_sorted_vec_by_key(|(word, _)| word.len());
+        assert_eq!(
+            sorted,
+            vec![
+                ("fig", 1),
+                ("date", 5),
+                ("kiwi", 3),
+                ("plum", 4),
+                ("apple", 2)
+            ]
+        );
+
+        let mut bucket = Bucket::new(4);
+        [3, 1, 2, 1]
+            .into_iter()
+            .for_each(|v| drop(bucket.insert(v)));
+        assert_eq!(bucket.into_sorted_vec(), vec![1, 1, 2, 3]);
+        assert!(Bucket::<u8>::new(1).into_sorted_vec().is_empty());
+        assert!(Bucket::<u8>::new(1)
+            .into_sorted_vec_by_key(|v| *v)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_allocation_tracks_capacity() {
+        for policy in [GrowthPolicy::Linear(3), GrowthPolicy::Doubling] {
+            let mut bucket = Bucket::with_policy(3, policy);
+            let mut live = Vec::new();
+
+            // Grow in bursts and shrink back in between, checking after every step
+            for round in 0..20 {
+                for i in 0..round * 3 {
+                    live.push(bucket.insert(i));
+                    assert_eq!(bucket.allocated_capacity(), bucket.capacity());
+                }
+                for idx in live.drain(..).skip(round).step_by(2) {
+                    bucket.remove_index(&idx.0);
+                    assert_eq!(bucket.allocated_capacity(), bucket.capacity());
+                }
+                live.extend(bucket.as_entries().iter().map(|v| v.index()));
+            }
+            bucket.reserve(100);
+            assert_eq!(bucket.allocated_capacity(), bucket.capacity());
+            bucket.shrink_to_fit();
+            assert_eq!(bucket.allocated_capacity(), bucket.capacity());
+            bucket.clear();
+            assert_eq!(bucket.allocated_capacity(), 3);
+        }
+    }
 
-        #[cfg(not(feature = "clone"))]
-        let values: Vec<_> = bucket.iter().collect();
+    #[test]
+    fn test_new_const_reserves_lazily() {
+        let mut bucket = Bucket::new_const(8);
+        assert_eq!(bucket.capacity(), 8);
+        assert_eq!(bucket.allocated_capacity(), 0);
 
-        #[cfg(feature = "clone")]
-        let values: Vec<_> = bucket.iter().map(|v| v.data).collect();
+        bucket.insert(1);
+        assert_eq!(bucket.allocated_capacity(), 8);
+        assert!(Bucket::<u8>::new(8).allocated_capacity() >= 8);
+    }
 
-        assert_eq!(values, vec![&2]);
+    #[test]
+    #[cfg(feature = "atomic")]
+    fn test_new_const_in_static() {
+        use std::{sync::Mutex, thread};
+
+        static REGISTRY: Mutex<Bucket<usize>> = Mutex::new(Bucket::new_const(64));
+
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                thread::spawn(move || {
+                    for i in 0..10 {
+                        REGISTRY.lock().unwrap().insert(t * 10 + i);
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        let registry = REGISTRY.lock().unwrap();
+        let mut values: Vec<_> = registry.data.iter().map(|v| v.data).collect();
+        values.sort();
+        assert_eq!(values, (0..40).collect::<Vec<_>>());
+        assert_eq!(registry.capacity(), 64);
+        assert_eq!(registry.allocated_capacity(), 64);
     }
 
     #[test]
@@ -0,0 +1,339 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc, sync::atomic::Ordering};
+
+use crate::{Bucket, Index, SyncBucket, ValueIndex, DANGLING};
+
+/// The shared cell of a `LocalIndex`, holding the slot of its element.
+type LocalCell = Rc<Cell<usize>>;
+
+/// A handle to an element of a `LocalBucket`.
+///
+/// Its shared cell is never atomic, so unlike `ValueIndex`, it cannot be
+/// sent to another thread.
+#[derive(Debug)]
+pub struct LocalIndex(LocalCell);
+
+#[cfg(feature = "clone")]
+impl Clone for LocalIndex {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// A `Bucket` for a single thread, whose handles are not atomically reference-counted.
+///
+/// With the `atomic` feature, every handle of a `Bucket` pays for atomic
+/// reference counting. A `LocalBucket` can be filled on a single thread with
+/// cheaper handles instead, then converted by [`LocalBucket::into_atomic`]
+/// into a `SyncBucket` to share with other threads. Like `Bucket`, removing
+/// an element moves the last one into its slot.
+pub struct LocalBucket<T> {
+    values: Vec<T>,
+    cells: Vec<LocalCell>,
+    capacity: usize,
+}
+
+/// Translates the handles of a `LocalBucket` into handles to the `SyncBucket` it was converted into.
+///
+/// `SyncRemap` is returned by [`LocalBucket::into_atomic`]. It keeps the
+/// shared cells of the converted elements alive, so that their handles are
+/// never mistaken for handles to other elements.
+#[derive(Debug)]
+pub struct SyncRemap {
+    old: Vec<LocalCell>,
+    new: Vec<Index>,
+}
+
+/// Translates the handles of a `SyncBucket` into handles to the `LocalBucket` it was converted into.
+///
+/// `LocalRemap` is returned by [`SyncBucket::into_local`]. It keeps the
+/// shared cells of the converted elements alive, so that their handles are
+/// never mistaken for handles to other elements.
+#[derive(Debug)]
+pub struct LocalRemap {
+    old: Vec<Index>,
+    new: Vec<LocalCell>,
+}
+
+impl<T> LocalBucket<T> {
+    /// Creates a new `LocalBucket` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots, also given to the `SyncBucket` it is converted into.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            cells: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `LocalBucket`.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if the `LocalBucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the values, in slot order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `LocalIndex` of the value to retrieve.
+    pub fn get(&self, index: &LocalIndex) -> Option<&T> {
+        self.slot(&index.0).map(|i| &self.values[i])
+    }
+
+    /// Retrieves a mutable reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `LocalIndex` of the value to retrieve.
+    pub fn get_mut(&mut self, index: &LocalIndex) -> Option<&mut T> {
+        self.slot(&index.0).map(|i| &mut self.values[i])
+    }
+
+    /// Checks if the value at the given index is still stored in the `LocalBucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `LocalIndex` of the value to look up.
+    pub fn contains(&self, index: &LocalIndex) -> bool {
+        self.slot(&index.0).is_some()
+    }
+
+    /// Inserts a new value into the next slot.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> LocalIndex {
+        let cell = Rc::new(Cell::new(self.len()));
+        self.values.push(data);
+        self.cells.push(cell.clone());
+        LocalIndex(cell)
+    }
+
+    /// Removes the value at the specified index, if it exists.
+    ///
+    /// The last element is moved into the freed slot.
+    ///
+    /// # Arguments
+    /// * `index` - The `LocalIndex` of the value to remove.
+    pub fn remove(&mut self, index: &LocalIndex) -> Option<T> {
+        let i = self.slot(&index.0)?;
+        self.cells.swap_remove(i).set(DANGLING);
+
+        // Update the index of the moved element, if any
+        if let Some(moved) = self.cells.get(i) {
+            moved.set(i)
+        }
+        Some(self.values.swap_remove(i))
+    }
+
+    /// Converts the `LocalBucket` into a `SyncBucket`, along with the translation of its handles.
+    ///
+    /// Every element is given a new, atomic shared cell, in O(n). The handles
+    /// of the `LocalBucket` do not resolve against the `SyncBucket`, and are
+    /// translated by the returned `SyncRemap` instead.
+    pub fn into_atomic(self) -> (SyncBucket<T>, SyncRemap) {
+        let mut bucket = Bucket::new(self.capacity);
+        bucket.reserve(self.len());
+
+        let new = self
+            .values
+            .into_iter()
+            .map(|data| bucket.insert(data).0)
+            .collect();
+        let remap = SyncRemap {
+            old: self.cells,
+            new,
+        };
+        (bucket.into(), remap)
+    }
+
+    /// Resolves the slot referenced by `index`, if its element is still stored.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the handle to resolve.
+    fn slot(&self, index: &LocalCell) -> Option<usize> {
+        let i = index.get();
+
+        match self.cells.get(i) {
+            Some(cell) if Rc::ptr_eq(cell, index) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl<T> SyncBucket<T> {
+    /// Converts the `SyncBucket` into a `LocalBucket`, along with the translation of its handles.
+    ///
+    /// Every element is given a new, non-atomic shared cell, in O(n). The
+    /// handles of the `SyncBucket` do not resolve against the `LocalBucket`,
+    /// and are translated by the returned `LocalRemap` instead. Of the
+    /// capacity configuration, only the initial capacity carries over, and
+    /// persistent identifiers and interned entries are dropped.
+    pub fn into_local(self) -> (LocalBucket<T>, LocalRemap) {
+        let parts = self.into_inner().into_raw_parts();
+        let new: Vec<_> = (0..parts.values.len())
+            .map(|i| Rc::new(Cell::new(i)))
+            .collect();
+
+        let bucket = LocalBucket {
+            values: parts.values,
+            cells: new.clone(),
+            capacity: parts.original_capacity,
+        };
+        let remap = LocalRemap {
+            old: parts.cells,
+            new,
+        };
+        (bucket, remap)
+    }
+}
+
+impl SyncRemap {
+    /// Returns a handle to the converted element that was in the given slot of the `LocalBucket`.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot of the element in the `LocalBucket`.
+    pub fn slot(&self, slot: usize) -> Option<ValueIndex> {
+        self.new.get(slot).map(|cell| ValueIndex(cell.clone()))
+    }
+
+    /// Translates a handle to the `LocalBucket` into a handle to the converted element.
+    ///
+    /// Returns `None` if the element of the handle was not stored when the
+    /// `LocalBucket` was converted.
+    ///
+    /// # Arguments
+    /// * `index` - The `LocalIndex` issued by the `LocalBucket`.
+    pub fn index(&self, index: &LocalIndex) -> Option<ValueIndex> {
+        let slot = index.0.get();
+        match self.old.get(slot) {
+            Some(cell) if Rc::ptr_eq(cell, &index.0) => self.slot(slot),
+            _ => None,
+        }
+    }
+}
+
+impl LocalRemap {
+    /// Returns a handle to the converted element that was in the given slot of the `SyncBucket`.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot of the element in the `SyncBucket`.
+    pub fn slot(&self, slot: usize) -> Option<LocalIndex> {
+        self.new.get(slot).map(|cell| LocalIndex(cell.clone()))
+    }
+
+    /// Translates a handle to the `SyncBucket` into a handle to the converted element.
+    ///
+    /// Returns `None` if the element of the handle was not stored when the
+    /// `SyncBucket` was converted.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` issued by the `SyncBucket`.
+    pub fn index(&self, index: &ValueIndex) -> Option<LocalIndex> {
+        let slot = index.0.load(Ordering::Relaxed);
+        match self.old.get(slot) {
+            Some(cell) if Index::ptr_eq(cell, &index.0) => self.slot(slot),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for LocalBucket<T> {
+    /// Creates an empty `LocalBucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<T: Debug> Debug for LocalBucket<T> {
+    /// Formats the bucket for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.values).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_across_threads() {
+        let mut local = LocalBucket::new(4);
+        let handles: Vec<_> = (0..20).map(|i| local.insert(i)).collect();
+        local.remove(&handles[3]);
+        *local.get_mut(&handles[5]).unwrap() += 100;
+
+        let (shared, remap) = local.into_atomic();
+        assert_eq!(shared.len(), 19);
+        assert!(remap.index(&handles[3]).is_none());
+        let translated: Vec<_> = handles.iter().map(|idx| remap.index(idx)).collect();
+
+        // The translated handles resolve from every thread while values move around
+        thread::scope(|s| {
+            for (t, idx) in translated.iter().enumerate().filter(|(t, _)| t % 2 == 0) {
+                let (shared, idx) = (&shared, idx.as_ref());
+                s.spawn(move || {
+                    let own = shared.insert(1000 + t);
+                    let expected = match t {
+                        3 => None,
+                        5 => Some(105),
+                        _ => Some(t),
+                    };
+                    assert_eq!(idx.and_then(|idx| shared.with(idx, |v| *v)), expected);
+                    assert_eq!(shared.remove(&own), Some(1000 + t));
+                });
+            }
+        });
+        assert!(shared.remove(translated[0].as_ref().unwrap()).is_some());
+
+        let (mut local, back) = shared.into_local();
+        assert_eq!(local.len(), 18);
+        assert_eq!(local.capacity, 4);
+        for (i, idx) in translated.iter().enumerate() {
+            let value = idx.as_ref().and_then(|idx| back.index(idx));
+            let expected = match i {
+                0 | 3 => None,
+                5 => Some(105),
+                _ => Some(i),
+            };
+            assert_eq!(value.and_then(|idx| local.get(&idx).copied()), expected);
+        }
+
+        // Handles of the original `LocalBucket` do not resolve against the new one
+        assert!(local.get(&handles[1]).is_none());
+        let idx = back.slot(0).unwrap();
+        assert!(local.remove(&idx).is_some());
+        assert!(!local.contains(&idx));
+    }
+
+    #[test]
+    fn test_local_remove() {
+        let mut bucket = LocalBucket::default();
+        let handles: Vec<_> = ['a', 'b', 'c'].map(|v| bucket.insert(v)).into();
+
+        // The last value moves into the freed slot
+        assert_eq!(bucket.remove(&handles[0]), Some('a'));
+        assert_eq!(bucket.remove(&handles[0]), None);
+        assert_eq!(bucket.iter().collect::<String>(), "cb");
+        assert_eq!(bucket.get(&handles[2]), Some(&'c'));
+        assert_eq!(handles[0].0.get(), DANGLING);
+
+        let (shared, remap) = bucket.into_atomic();
+        assert!(remap.index(&handles[0]).is_none());
+        assert_eq!(
+            remap.slot(0).and_then(|idx| shared.with(&idx, |v| *v)),
+            Some('c')
+        );
+        assert!(remap.slot(2).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::{
+    Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+};
+
+use crate::{Bucket, ValueIndex};
+
+/// Element-level locking for buckets of `RwLock`s.
+///
+/// Each element is locked on its own, so threads sharing the `Bucket` can
+/// access distinct elements at the same time. Inserting and removing still
+/// require `&mut self`, and therefore exclusive access to the whole `Bucket`.
+///
+/// A poisoned element is still handed out, since a panicking writer cannot
+/// leave the `Bucket` itself inconsistent.
+impl<T> Bucket<RwLock<T>> {
+    /// Inserts a new value wrapped in its own `RwLock`.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert_locked(&mut self, data: T) -> ValueIndex {
+        self.insert(RwLock::new(data))
+    }
+
+    /// Locks the value at the given index for reading, blocking until it is available.
+    ///
+    /// Returns `None` if the handle is stale or dangling.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to read.
+    pub fn read_element(&self, index: &ValueIndex) -> Option<RwLockReadGuard<'_, T>> {
+        let lock = &self.data[self.slot(&index.0)?].data;
+        Some(lock.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Locks the value at the given index for writing, blocking until it is available.
+    ///
+    /// Returns `None` if the handle is stale or dangling.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to write.
+    pub fn write_element(&self, index: &ValueIndex) -> Option<RwLockWriteGuard<'_, T>> {
+        let value = &self.data[self.slot(&index.0)?];
+        value.mark();
+        Some(value.data.write().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Locks the value at the given index for reading, unless it is being written.
+    ///
+    /// Returns `None` if the handle is stale or dangling, or if the lock is held.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to read.
+    pub fn try_read_element(&self, index: &ValueIndex) -> Option<RwLockReadGuard<'_, T>> {
+        let lock = &self.data[self.slot(&index.0)?].data;
+        lock.try_read().or_else(recover).ok()
+    }
+
+    /// Locks the value at the given index for writing, unless it is being read or written.
+    ///
+    /// Returns `None` if the handle is stale or dangling, or if the lock is held.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to write.
+    pub fn try_write_element(&self, index: &ValueIndex) -> Option<RwLockWriteGuard<'_, T>> {
+        let value = &self.data[self.slot(&index.0)?];
+        let guard = value.data.try_write().or_else(recover).ok()?;
+        value.mark();
+        Some(guard)
+    }
+}
+
+/// Element-level locking for buckets of `Mutex`es.
+///
+/// This mirrors the helpers of `Bucket<RwLock<T>>` for values that are only
+/// ever locked exclusively.
+impl<T> Bucket<Mutex<T>> {
+    /// Inserts a new value wrapped in its own `Mutex`.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert_locked(&mut self, data: T) -> ValueIndex {
+        self.insert(Mutex::new(data))
+    }
+
+    /// Locks the value at the given index, blocking until it is available.
+    ///
+    /// Returns `None` if the handle is stale or dangling.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to lock.
+    pub fn lock_element(&self, index: &ValueIndex) -> Option<MutexGuard<'_, T>> {
+        let value = &self.data[self.slot(&index.0)?];
+        value.mark();
+        Some(value.data.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Locks the value at the given index, unless it is already locked.
+    ///
+    /// Returns `None` if the handle is stale or dangling, or if the lock is held.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to lock.
+    pub fn try_lock_element(&self, index: &ValueIndex) -> Option<MutexGuard<'_, T>> {
+        let value = &self.data[self.slot(&index.0)?];
+        let guard = value.data.try_lock().or_else(recover).ok()?;
+        value.mark();
+        Some(guard)
+    }
+}
+
+/// Recovers the guard of a poisoned lock, keeping the error of a lock that is held.
+fn recover<G>(e: TryLockError<G>) -> Result<G, TryLockError<G>> {
+    match e {
+        TryLockError::Poisoned(p) => Ok(p.into_inner()),
+        e => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_elements() {
+        let mut bucket: Bucket<RwLock<_>> = Bucket::new(2);
+        let a = bucket.insert_locked(1);
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove_index(&a.0);
+
+        assert!(bucket.read_element(&stale).is_none());
+        assert!(bucket.write_element(&stale).is_none());
+        assert!(bucket.try_read_element(&stale).is_none());
+        assert!(bucket.try_write_element(&ValueIndex::dangling()).is_none());
+
+        let mut bucket: Bucket<Mutex<_>> = Bucket::new(2);
+        let a = bucket.insert_locked(1);
+        let stale = ValueIndex(a.0.clone());
+        bucket.remove_index(&a.0);
+        assert!(bucket.lock_element(&stale).is_none());
+        assert!(bucket.try_lock_element(&stale).is_none());
+    }
+
+    #[test]
+    fn test_element_conflicts() {
+        let mut bucket: Bucket<RwLock<_>> = Bucket::new(2);
+        let a = bucket.insert_locked(1);
+        let b = bucket.insert_locked(2);
+
+        let read = bucket.read_element(&a).unwrap();
+        assert!(bucket.try_read_element(&a).is_some());
+        assert!(bucket.try_write_element(&a).is_none());
+
+        // Other elements are unaffected
+        *bucket.try_write_element(&b).unwrap() += 10;
+        drop(read);
+        *bucket.try_write_element(&a).unwrap() += 10;
+        assert_eq!(*bucket.read_element(&a).unwrap(), 11);
+        assert_eq!(*bucket.read_element(&b).unwrap(), 12);
+
+        let mut bucket: Bucket<Mutex<_>> = Bucket::new(2);
+        let a = bucket.insert_locked(String::from("a"));
+        let guard = bucket.lock_element(&a).unwrap();
+        assert!(bucket.try_lock_element(&a).is_none());
+        drop(guard);
+        bucket.try_lock_element(&a).unwrap().push('b');
+        assert_eq!(*bucket.lock_element(&a).unwrap(), "ab");
+    }
+
+    #[test]
+    #[cfg(feature = "atomic")]
+    fn test_concurrent_writers() {
+        use std::thread;
+
+        let mut bucket: Bucket<RwLock<_>> = Bucket::new(4);
+        let handles: Vec<_> = (0..8).map(|_| bucket.insert_locked(0)).collect();
+
+        thread::scope(|s| {
+            for idx in &handles {
+                let bucket = &bucket;
+                s.spawn(move || {
+                    for _ in 0..1000 {
+                        *bucket.write_element(idx).unwrap() += 1;
+                    }
+                });
+            }
+        });
+        assert!(handles
+            .iter()
+            .all(|h| *bucket.read_element(h).unwrap() == 1000));
+    }
+
+    #[test]
+    #[cfg(feature = "atomic")]
+    fn test_concurrent_reader_writer() {
+        use std::{sync::Barrier, thread};
+
+        let mut bucket: Bucket<RwLock<_>> = Bucket::new(2);
+        let a = bucket.insert_locked(vec![0]);
+        let barrier = Barrier::new(2);
+
+        thread::scope(|s| {
+            let guard = bucket.read_element(&a).unwrap();
+            s.spawn(|| {
+                assert!(bucket.try_write_element(&a).is_none());
+                barrier.wait();
+
+                // Blocks until the reader is done
+                bucket.write_element(&a).unwrap().push(1);
+            });
+            barrier.wait();
+            assert_eq!(*guard, vec![0]);
+            drop(guard);
+        });
+        assert_eq!(*bucket.read_element(&a).unwrap(), vec![0, 1]);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{Bucket, ValueIndex};
+
+impl<T> Bucket<T> {
+    /// Marks the value at the specified index as the most recently used, returning whether it exists.
+    ///
+    /// Values that were never touched rank by their insertion time.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to touch.
+    pub fn touch(&mut self, index: &ValueIndex) -> bool {
+        let Some(i) = self.slot(&index.0) else {
+            return false;
+        };
+        self.data[i].used = self.next_seq();
+        true
+    }
+
+    /// Removes the least recently used value, returning it together with its handle.
+    ///
+    /// The returned handle no longer resolves against the `Bucket`, but still
+    /// identifies the element it was issued for.
+    pub fn evict_lru(&mut self) -> Option<(ValueIndex, T)> {
+        let i = self.lru_slot()?;
+        let value = self._remove_value(i);
+        self.paranoid();
+        Some((ValueIndex(value.index), value.data))
+    }
+
+    /// Removes the least recently used values for as long as `f` accepts them.
+    ///
+    /// Values are passed to `f` from the least to the most recently used, and
+    /// eviction stops at the first value `f` rejects, which is kept.
+    ///
+    /// # Arguments
+    /// * `f` - The closure deciding whether the next value is evicted.
+    pub fn evict_lru_while(&mut self, mut f: impl FnMut(&T) -> bool) -> Vec<(ValueIndex, T)> {
+        // Rank the slots once, then follow the elements the removals move around
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_unstable_by_key(|&i| self.data[i].used);
+        let mut rank = vec![0; order.len()];
+        for (r, &i) in order.iter().enumerate() {
+            rank[i] = r;
+        }
+        let mut evicted = Vec::new();
+
+        for r in 0..order.len() {
+            let i = order[r];
+            if !f(&self.data[i].data) {
+                break;
+            }
+            // The last element is moved into the freed slot
+            let last = self.len() - 1;
+            order[rank[last]] = i;
+            rank[i] = rank[last];
+
+            let value = self._remove_value(i);
+            evicted.push((ValueIndex(value.index), value.data));
+        }
+
+        self.paranoid();
+        evicted
+    }
+
+    /// Returns the slot of the least recently used value, if any.
+    fn lru_slot(&self) -> Option<usize> {
+        self.data
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, v)| v.used)
+            .map(|(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Index;
+
+    use super::*;
+
+    #[test]
+    fn test_evict_lru_order() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(|v| bucket.insert(v))
+            .collect();
+
+        assert!(bucket.touch(&handles[0]));
+        assert!(bucket.touch(&handles[2]));
+        assert!(bucket.touch(&handles[1]));
+        assert!(bucket.touch(&handles[0]));
+
+        // Untouched values go first, by insertion time, then by last touch
+        let expected = ["d", "e", "c", "b", "a"];
+
+        for (i, value) in expected.into_iter().enumerate() {
+            let (idx, evicted) = bucket.evict_lru().unwrap();
+            assert_eq!(evicted, value);
+            assert_eq!(bucket.len(), 4 - i);
+            assert_eq!(bucket.slot(&idx.0), None);
+        }
+        assert!(bucket.evict_lru().is_none());
+        assert!(!bucket.touch(&handles[0]));
+    }
+
+    #[test]
+    fn test_evict_lru_while() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (1..=6).map(|size| bucket.insert(size)).collect();
+        bucket.touch(&handles[0]);
+        bucket.touch(&handles[3]);
+
+        // Evict until the total size fits a budget of 10
+        let mut total: i32 = bucket.data.iter().map(|v| v.data).sum();
+        let evicted = bucket.evict_lru_while(|&size| {
+            let over = total > 10;
+            total -= size;
+            over
+        });
+
+        let sizes: Vec<_> = evicted.iter().map(|(_, size)| *size).collect();
+        assert_eq!(sizes, vec![2, 3, 5, 6]);
+        assert!(evicted
+            .iter()
+            .zip([1, 2, 4, 5])
+            .all(|((idx, _), i)| Index::ptr_eq(&idx.0, &handles[i].0)));
+        assert_eq!(bucket.len(), 2);
+        assert_eq!(bucket.evict_lru_while(|_| false).len(), 0);
+    }
+
+    #[test]
+    fn test_evict_lru_while_matches_evict_lru() {
+        let mut a = Bucket::new(3);
+        let mut b = Bucket::new(3);
+        let handles: Vec<_> = (0..20).map(|i| (a.insert(i), b.insert(i))).collect();
+        for i in [7, 3, 18, 0, 11, 3, 19, 5] {
+            a.touch(&handles[i].0);
+            b.touch(&handles[i].1);
+        }
+
+        // A single pass evicts in the same order as repeated single evictions
+        let evicted = a.evict_lru_while(|_| true);
+        let expected: Vec<_> = std::iter::from_fn(|| b.evict_lru()).collect();
+        assert_eq!(evicted.len(), 20);
+        assert!(evicted.iter().zip(&expected).all(|((_, x), (_, y))| x == y));
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+use std::mem;
+
+use crate::{checkpoint::Origin, intern::InternTable, persist::PersistTable, Bucket, Value};
+
+impl<T> Value<T> {
+    /// Transforms the data of this value, keeping its shared cell and metadata.
+    ///
+    /// # Arguments
+    /// * `f` - The function applied to the data.
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Value<U> {
+        Value {
+            data: f(self.data),
+            index: self.index,
+            seq: self.seq,
+            #[cfg(feature = "lru")]
+            used: self.used,
+            #[cfg(feature = "dirty")]
+            dirty: self.dirty,
+            #[cfg(feature = "ttl")]
+            deadline: self.deadline,
+        }
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Transforms every value into a new `Bucket`, in which the handles issued by this one keep resolving.
+    ///
+    /// Every element keeps its shared cell and slot, and the capacity
+    /// configuration, persistent identifiers and capacity change callback
+    /// carry over. Interned values are not interned in the new `Bucket`, as
+    /// the transformed values may compare differently.
+    ///
+    /// # Arguments
+    /// * `f` - The function applied to every value, in slot order.
+    pub fn map<U>(mut self, mut f: impl FnMut(T) -> U) -> Bucket<U> {
+        let mut data = Vec::with_capacity(self.data.capacity());
+        data.extend(mem::take(&mut self.data).into_iter().map(|v| v.map(&mut f)));
+
+        let bucket = Bucket {
+            data,
+            capacity: self.capacity.clone(),
+            seq: self.seq,
+            hook: self.hook.take(),
+            overwrite: self.overwrite,
+            ring: None,
+            interned: InternTable::new(),
+            persisted: mem::replace(&mut self.persisted, PersistTable::new()),
+            origin: Origin::new(),
+            free_cells: mem::take(&mut self.free_cells),
+            #[cfg(feature = "ttl")]
+            clock: self.clock.take(),
+        };
+        bucket.paranoid();
+        bucket
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_keeps_handles() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i.to_string())).collect();
+        bucket.remove_index(&handles[0].0);
+        let id = bucket.persist(&handles[5]).unwrap();
+
+        let mut mapped = bucket.map(|s| s.parse::<u32>().unwrap() * 10);
+        assert_eq!(mapped.len(), 9);
+        assert_eq!(mapped.capacity(), 12);
+        assert_eq!(mapped.validate(), Ok(()));
+        assert!(!mapped.contains(&handles[0]));
+        for (i, idx) in handles.iter().enumerate().skip(1) {
+            assert_eq!(mapped.try_get(idx), Some(&(i as u32 * 10)));
+        }
+        assert_eq!(mapped.resolve(id).as_ref(), Some(&handles[5]));
+
+        // Removing through the old handles still relocates and shrinks
+        for idx in &handles[1..6] {
+            assert!(mapped.remove_index(&idx.0).is_some());
+        }
+        assert_eq!(mapped.capacity(), 4);
+        assert_eq!(mapped.try_get(&handles[9]), Some(&90));
+        assert!(mapped.resolve(id).is_none());
+        assert_eq!(mapped.validate(), Ok(()));
+
+        let idx = mapped.insert(100);
+        assert_eq!(mapped.try_get(&idx), Some(&100));
+        assert_eq!(mapped.into_sorted_vec(), [60, 70, 80, 90, 100]);
+    }
+
+    #[test]
+    fn test_map_empty() {
+        let mut bucket = Bucket::with_policy(2, crate::GrowthPolicy::Doubling);
+        bucket.extend(0..5);
+        bucket.retain(|_| false);
+        let mapped = bucket.map(|i: i32| i as u8);
+        assert!(mapped.is_empty());
+        assert_eq!(mapped.capacity(), 2);
+        assert_eq!(mapped.policy(), crate::GrowthPolicy::Doubling);
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
+
+use crate::{Bucket, Index, ValueIndex};
+
+/// A `Bucket` that keeps its values ordered by a sort key at all times.
+///
+/// Every value is stored together with its key, and an index of the keys is
+/// maintained on every insertion, removal, and [`OrderedBucket::rekey`].
+/// Duplicate keys are allowed; values sharing a key are ordered by the time
+/// they were given that key, oldest first.
+pub struct OrderedBucket<K, T> {
+    // Dropped before the `Bucket`, so that its handles are not reported as leaked
+    keys: BTreeMap<(K, u64), ValueIndex>,
+    bucket: Bucket<(K, T)>,
+    seq: u64,
+}
+
+impl<K: Ord + Clone, T> OrderedBucket<K, T> {
+    /// Creates a new `OrderedBucket` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `OrderedBucket`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            bucket: Bucket::new(capacity),
+            seq: 0,
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `OrderedBucket`.
+    pub fn len(&self) -> usize {
+        self.bucket.len()
+    }
+
+    /// Checks if the `OrderedBucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bucket.is_empty()
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get(&self, index: &ValueIndex) -> Option<&T> {
+        self.entry(index).map(|(_, v)| v)
+    }
+
+    /// Retrieves a reference to the key of the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value whose key to retrieve.
+    pub fn key(&self, index: &ValueIndex) -> Option<&K> {
+        self.entry(index).map(|(k, _)| k)
+    }
+
+    /// Returns the smallest key in the `OrderedBucket`, if any.
+    pub fn first_key(&self) -> Option<&K> {
+        self.keys.keys().next().map(|(k, _)| k)
+    }
+
+    /// Returns the largest key in the `OrderedBucket`, if any.
+    pub fn last_key(&self) -> Option<&K> {
+        self.keys.keys().next_back().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the keys and values, in key order.
+    pub fn iter_ordered(&self) -> impl DoubleEndedIterator<Item = (&K, &T)> {
+        self.keys.values().map(|idx| self.resolve(idx))
+    }
+
+    /// Returns an iterator over the keys and values whose key lies in `range`, in key order.
+    ///
+    /// # Arguments
+    /// * `range` - The range of keys to yield.
+    pub fn range(&self, range: impl RangeBounds<K>) -> impl DoubleEndedIterator<Item = (&K, &T)> {
+        // Sequence numbers start at one, so these bounds enclose every entry of a key
+        let start = match range.start_bound() {
+            Bound::Included(k) => Bound::Included((k.clone(), 0)),
+            Bound::Excluded(k) => Bound::Excluded((k.clone(), u64::MAX)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(k) => Bound::Included((k.clone(), u64::MAX)),
+            Bound::Excluded(k) => Bound::Excluded((k.clone(), 0)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        self.keys
+            .range((start, end))
+            .map(|(_, idx)| self.resolve(idx))
+    }
+
+    /// Inserts a new value with the given key into the `OrderedBucket`.
+    ///
+    /// # Arguments
+    /// * `key` - The sort key of the value.
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, key: K, data: T) -> ValueIndex {
+        let seq = self.next_seq();
+        let index = self.bucket.insert((key.clone(), data));
+        self.keys.insert((key, seq), ValueIndex(index.0.clone()));
+        index
+    }
+
+    /// Removes the value at the specified index, returning it together with its key.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> Option<(K, T)> {
+        let seq = self.seq_of(index)?;
+        let (key, data) = self.bucket.remove_index(&index.0)?;
+        self.keys.remove(&(key.clone(), seq));
+        Some((key, data))
+    }
+
+    /// Changes the key of the value at the specified index, returning the previous key.
+    ///
+    /// The value is ordered after every other value already holding the new key.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to rekey.
+    /// * `key` - The new sort key of the value.
+    pub fn rekey(&mut self, index: &ValueIndex, key: K) -> Option<K> {
+        let seq = self.seq_of(index)?;
+        let i = self.bucket.slot(&index.0)?;
+        let old = std::mem::replace(&mut self.bucket.data[i].data.0, key.clone());
+
+        let handle = self.keys.remove(&(old.clone(), seq))?;
+        let seq = self.next_seq();
+        self.keys.insert((key, seq), handle);
+        Some(old)
+    }
+
+    /// Advances the key sequence counter, returning the new sequence number.
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+
+    /// Finds the sequence number under which the key of `index` is recorded.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to look up.
+    fn seq_of(&self, index: &ValueIndex) -> Option<u64> {
+        let (key, _) = self.entry(index)?;
+        self.keys
+            .range((key.clone(), 0)..=(key.clone(), u64::MAX))
+            .find(|(_, idx)| Index::ptr_eq(&idx.0, &index.0))
+            .map(|((_, seq), _)| *seq)
+    }
+
+    /// Resolves the key and value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the entry to resolve.
+    fn entry(&self, index: &ValueIndex) -> Option<&(K, T)> {
+        let i = self.bucket.slot(&index.0)?;
+        Some(&self.bucket.data[i].data)
+    }
+
+    /// Resolves a handle recorded in the key index, which is always valid.
+    ///
+    /// # Arguments
+    /// * `index` - The recorded `ValueIndex` to resolve.
+    fn resolve(&self, index: &ValueIndex) -> (&K, &T) {
+        match self.entry(index) {
+            Some((k, v)) => (k, v),
+            None => unreachable!("the key index refers to a removed value"),
+        }
+    }
+}
+
+impl<K: Ord + Clone, T> Default for OrderedBucket<K, T> {
+    /// Creates an empty `OrderedBucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<K: Ord + Clone + Debug, T: Debug> Debug for OrderedBucket<K, T> {
+    /// Formats the ordered bucket for debugging purposes, in key order.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter_ordered()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordered_randomized() {
+        let mut bucket = OrderedBucket::new(4);
+        let mut live: Vec<(ValueIndex, u32)> = Vec::new();
+        let mut state = 0x2545_f491_u64;
+
+        for step in 0..2000u32 {
+            // A small linear congruential generator keeps the workload reproducible
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let r = (state >> 33) as u32;
+
+            match r % 4 {
+                0 | 1 => live.push((bucket.insert(r % 50, step), step)),
+                2 if !live.is_empty() => {
+                    let (idx, value) = live.swap_remove(r as usize % live.len());
+                    assert_eq!(bucket.remove(&idx).map(|(_, v)| v), Some(value));
+                    assert_eq!(bucket.remove(&idx), None);
+                }
+                3 if !live.is_empty() => {
+                    let (idx, _) = &live[r as usize % live.len()];
+                    let old = *bucket.key(idx).unwrap();
+                    assert_eq!(bucket.rekey(idx, (r / 7) % 50), Some(old));
+                }
+                _ => {}
+            }
+
+            let ordered: Vec<_> = bucket.iter_ordered().collect();
+            assert_eq!(ordered.len(), live.len());
+            assert!(ordered.windows(2).all(|w| w[0].0 <= w[1].0));
+        }
+
+        assert!(live
+            .iter()
+            .all(|(idx, value)| bucket.get(idx) == Some(value)));
+        assert_eq!(
+            bucket.first_key(),
+            bucket.iter_ordered().next().map(|(k, _)| k)
+        );
+        assert_eq!(
+            bucket.last_key(),
+            bucket.iter_ordered().next_back().map(|(k, _)| k)
+        );
+    }
+
+    #[test]
+    fn test_ordered_range_and_duplicates() {
+        let mut bucket = OrderedBucket::default();
+        let a = bucket.insert(3, 'a');
+        bucket.insert(1, 'b');
+        bucket.insert(3, 'c');
+        bucket.insert(5, 'd');
+        bucket.insert(7, 'e');
+
+        let values =
+            |it: &mut dyn Iterator<Item = (&i32, &char)>| it.map(|(_, v)| *v).collect::<String>();
+        assert_eq!(values(&mut bucket.range(3..7)), "acd");
+        assert_eq!(values(&mut bucket.range(..=3)), "bac");
+        assert_eq!(
+            values(&mut bucket.range((Bound::Excluded(3), Bound::Unbounded))),
+            "de"
+        );
+
+        // A rekeyed value goes after every value already holding the key
+        assert_eq!(bucket.rekey(&a, 3), Some(3));
+        assert_eq!(values(&mut bucket.iter_ordered()), "bcade");
+        assert_eq!(bucket.remove(&a), Some((3, 'a')));
+        assert_eq!(bucket.rekey(&a, 0), None);
+        assert_eq!(
+            (bucket.first_key(), bucket.last_key()),
+            (Some(&1), Some(&7))
+        );
+        assert_eq!(format!("{:?}", bucket), "{1: 'b', 3: 'c', 5: 'd', 7: 'e'}");
+    }
+}
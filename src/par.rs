@@ -0,0 +1,272 @@
+use std::sync::atomic::Ordering;
+
+use rayon::{
+    iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer},
+    prelude::*,
+    slice, vec,
+};
+
+use crate::{Bucket, Value, DANGLING};
+
+/// A parallel iterator over the values of a `Bucket`, created by [`Bucket::par_iter`].
+pub struct ParIter<'a, T>(slice::Iter<'a, Value<T>>);
+
+/// A mutable parallel iterator over the values of a `Bucket`, created by [`Bucket::par_iter_mut`].
+pub struct ParIterMut<'a, T>(slice::IterMut<'a, Value<T>>);
+
+/// A parallel iterator over the values removed from a `Bucket`, created by [`Bucket::par_drain`].
+///
+/// The values that are not consumed are dropped along with the iterator.
+pub struct ParDrain<T>(vec::IntoIter<Value<T>>);
+
+impl<T: Send + Sync> Bucket<T> {
+    /// Returns a parallel iterator over the values in the `Bucket`.
+    ///
+    /// Values are split across the threads of the current `rayon` pool, and
+    /// are yielded as plain references regardless of the enabled features.
+    pub fn par_iter(&self) -> ParIter<'_, T> {
+        ParIter(self.data.par_iter())
+    }
+
+    /// Returns a mutable parallel iterator over the values in the `Bucket`.
+    ///
+    /// Only the data can be modified, so every handle remains valid. Each value
+    /// is flagged as modified once it is yielded.
+    pub fn par_iter_mut(&mut self) -> ParIterMut<'_, T> {
+        ParIterMut(self.data.par_iter_mut())
+    }
+}
+
+impl<T: Send> Bucket<T> {
+    /// Removes every value, returning them as a parallel iterator.
+    ///
+    /// The `Bucket` takes no part in the iteration: it is empty, with its
+    /// capacity back to the original one, as soon as this returns, and no
+    /// handle to a drained value resolves again. The values are yielded in
+    /// slot order by indexed consumers, and in any order otherwise.
+    pub fn par_drain(&mut self) -> ParDrain<T> {
+        let values = std::mem::take(&mut self.data);
+        for value in &values {
+            value.index.store(DANGLING, Ordering::Relaxed);
+            self.persisted.forget(&value.index)
+        }
+        self.clear();
+        ParDrain(values.into_par_iter())
+    }
+}
+
+/// Returns the data of a value.
+fn data<T>(value: &Value<T>) -> &T {
+    &value.data
+}
+
+/// Returns the data of a value, flagging it as modified.
+fn data_mut<T>(value: &mut Value<T>) -> &mut T {
+    value.mark();
+    &mut value.data
+}
+
+impl<'a, T: Send + Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    /// Drives the values into the consumer, in any order.
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(data).drive_unindexed(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl<T: Send + Sync> IndexedParallelIterator for ParIter<'_, T> {
+    /// Drives the values into the consumer, in slot order.
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(data).drive(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Splits the values into producers for the callback.
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.0.map(data).with_producer(callback)
+    }
+}
+
+impl<'a, T: Send + Sync> ParallelIterator for ParIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    /// Drives the values into the consumer, in any order.
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(data_mut).drive_unindexed(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl<T: Send + Sync> IndexedParallelIterator for ParIterMut<'_, T> {
+    /// Drives the values into the consumer, in slot order.
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(data_mut).drive(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Splits the values into producers for the callback.
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.0.map(data_mut).with_producer(callback)
+    }
+}
+
+/// Returns the data of a removed value.
+fn into_data<T>(value: Value<T>) -> T {
+    value.data
+}
+
+impl<T: Send> ParallelIterator for ParDrain<T> {
+    type Item = T;
+
+    /// Drives the values into the consumer, in any order.
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(into_data).drive_unindexed(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl<T: Send> IndexedParallelIterator for ParDrain<T> {
+    /// Drives the values into the consumer, in slot order.
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.0.map(into_data).drive(consumer)
+    }
+
+    /// Returns the exact number of values.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Splits the values into producers for the callback.
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.0.map(into_data).with_producer(callback)
+    }
+}
+
+impl<'a, T: Send + Sync> IntoParallelIterator for &'a Bucket<T> {
+    type Item = &'a T;
+    type Iter = ParIter<'a, T>;
+
+    /// Returns a parallel iterator over the values in the `Bucket`.
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter()
+    }
+}
+
+impl<'a, T: Send + Sync> IntoParallelIterator for &'a mut Bucket<T> {
+    type Item = &'a mut T;
+    type Iter = ParIterMut<'a, T>;
+
+    /// Returns a mutable parallel iterator over the values in the `Bucket`.
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    /// Counts how many times it was dropped.
+    struct Tracked(u64, Arc<AtomicUsize>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_par_sum() {
+        let mut bucket: Bucket<u64> = (0..20_000).collect();
+        bucket.retain(|v| v % 3 != 0);
+
+        let sequential: u64 = bucket.data.iter().map(|v| v.data).sum();
+        assert_eq!(bucket.par_iter().sum::<u64>(), sequential);
+        assert_eq!((&bucket).into_par_iter().count(), bucket.len());
+    }
+
+    #[test]
+    fn test_par_mutate() {
+        let mut bucket = Bucket::new(64);
+        let handles: Vec<_> = (0..5_000u64).map(|i| bucket.insert(i)).collect();
+
+        bucket.par_iter_mut().for_each(|v| *v *= 2);
+        (&mut bucket).into_par_iter().for_each(|v| *v += 1);
+
+        // Every handle still resolves to its own value
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.try_get(idx), Some(&(i as u64 * 2 + 1)));
+        }
+        let collected: Vec<_> = bucket.par_iter().copied().collect();
+        assert_eq!(bucket, collected);
+    }
+
+    #[test]
+    fn test_par_drain_sum() {
+        let mut bucket = Bucket::new(16);
+        let handles: Vec<_> = (0..10_000u64).map(|i| bucket.insert(i)).collect();
+        bucket.retain(|v| v % 7 != 0);
+        let sequential: u64 = bucket.data.iter().map(|v| v.data).sum();
+
+        assert_eq!(bucket.par_drain().sum::<u64>(), sequential);
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 16);
+        assert!(handles.iter().all(|h| !bucket.contains(h)));
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // The bucket is reusable right away
+        let idx = bucket.insert(1);
+        assert_eq!(bucket.try_get(&idx), Some(&1));
+        assert_eq!(bucket.par_drain().collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn test_par_drain_drops() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let mut bucket = Bucket::new(8);
+        bucket.extend((0..1_000).map(|i| Tracked(i, drops.clone())));
+
+        // Dropping the iterator unconsumed drops every value once
+        drop(bucket.par_drain());
+        assert_eq!(drops.load(Ordering::Relaxed), 1_000);
+        assert!(bucket.is_empty());
+
+        bucket.extend((0..1_000).map(|i| Tracked(i, drops.clone())));
+        let sum: u64 = bucket.par_drain().map(|t| t.0).sum();
+        assert_eq!(sum, 499_500);
+        assert_eq!(drops.load(Ordering::Relaxed), 2_000);
+
+        // Values are dropped exactly once even if consumption stops early
+        bucket.extend((0..1_000).map(|i| Tracked(i, drops.clone())));
+        assert!(bucket.par_drain().any(|t| t.0 == 500));
+        drop(bucket);
+        assert_eq!(drops.load(Ordering::Relaxed), 3_000);
+    }
+}
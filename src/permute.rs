@@ -0,0 +1,164 @@
+use std::{fmt, sync::atomic::Ordering};
+
+use crate::Bucket;
+
+/// Describes why a slice is not a permutation of the slots of a `Bucket`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PermutationError {
+    /// The permutation has a different length than the `Bucket`.
+    LengthMismatch { len: usize, expected: usize },
+
+    /// The permutation refers to a slot past the last one.
+    OutOfRange { slot: usize, len: usize },
+
+    /// The permutation refers to the same slot more than once.
+    Duplicate { slot: usize },
+}
+
+impl fmt::Display for PermutationError {
+    /// Formats the error as a human-readable report.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { len, expected } => write!(
+                f,
+                "length mismatch: the permutation has {len} slots instead of {expected}"
+            ),
+            Self::OutOfRange { slot, len } => write!(
+                f,
+                "out of range: slot {slot} does not exist in a bucket of length {len}"
+            ),
+            Self::Duplicate { slot } => {
+                write!(f, "duplicate slot: slot {slot} appears more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PermutationError {}
+
+impl<T> Bucket<T> {
+    /// Reorders the values so that the value in slot `perm[i]` moves into slot `i`.
+    ///
+    /// Every handle keeps resolving to its value. The permutation is validated
+    /// before anything is moved, so the `Bucket` is left untouched on error.
+    ///
+    /// # Arguments
+    /// * `perm` - The old slot of the value to put into each new slot.
+    pub fn apply_permutation(&mut self, perm: &[usize]) -> Result<(), PermutationError> {
+        let len = self.len();
+        if perm.len() != len {
+            return Err(PermutationError::LengthMismatch {
+                len: perm.len(),
+                expected: len,
+            });
+        }
+
+        let mut seen = vec![false; len];
+        for &slot in perm {
+            match seen.get_mut(slot) {
+                None => return Err(PermutationError::OutOfRange { slot, len }),
+                Some(true) => return Err(PermutationError::Duplicate { slot }),
+                Some(seen) => *seen = true,
+            }
+        }
+
+        let mut old: Vec<_> = self.data.drain(..).map(Some).collect();
+        self.data
+            .extend(perm.iter().filter_map(|&slot| old[slot].take()));
+
+        for (i, value) in self.data.iter().enumerate() {
+            value.index.store(i, Ordering::Relaxed)
+        }
+        self.ring = None;
+        self.paranoid();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValueIndex;
+
+    use super::*;
+
+    fn filled(n: usize) -> (Bucket<usize>, Vec<ValueIndex>) {
+        let mut bucket = Bucket::new(4);
+        let handles = (0..n).map(|i| bucket.insert(i)).collect();
+        (bucket, handles)
+    }
+
+    fn assert_resolves(bucket: &Bucket<usize>, handles: &[ValueIndex]) {
+        assert_eq!(bucket.validate(), Ok(()));
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i);
+        }
+    }
+
+    fn values(bucket: &Bucket<usize>) -> Vec<usize> {
+        bucket.data.iter().map(|v| v.data).collect()
+    }
+
+    #[test]
+    fn test_identity_and_reversal() {
+        let (mut bucket, handles) = filled(6);
+
+        assert_eq!(bucket.apply_permutation(&[0, 1, 2, 3, 4, 5]), Ok(()));
+        assert_eq!(values(&bucket), vec![0, 1, 2, 3, 4, 5]);
+        assert_resolves(&bucket, &handles);
+
+        assert_eq!(bucket.apply_permutation(&[5, 4, 3, 2, 1, 0]), Ok(()));
+        assert_eq!(values(&bucket), vec![5, 4, 3, 2, 1, 0]);
+        assert_resolves(&bucket, &handles);
+
+        assert_eq!(Bucket::<u8>::new(1).apply_permutation(&[]), Ok(()));
+    }
+
+    #[test]
+    fn test_permutation_round_trip() {
+        let (mut bucket, handles) = filled(50);
+
+        // A pseudo-random permutation and its inverse
+        let mut perm: Vec<usize> = (0..50).collect();
+        let mut r = 17;
+        for i in (1..50).rev() {
+            r = (r * 31 + 7) % 101;
+            perm.swap(i, r % (i + 1));
+        }
+        let mut inverse = vec![0; 50];
+        for (new, &old) in perm.iter().enumerate() {
+            inverse[old] = new;
+        }
+
+        assert_eq!(bucket.apply_permutation(&perm), Ok(()));
+        assert_eq!(values(&bucket), perm);
+        assert_resolves(&bucket, &handles);
+
+        assert_eq!(bucket.apply_permutation(&inverse), Ok(()));
+        assert_eq!(values(&bucket), (0..50).collect::<Vec<_>>());
+        assert_resolves(&bucket, &handles);
+    }
+
+    #[test]
+    fn test_invalid_permutations() {
+        let (mut bucket, handles) = filled(4);
+
+        assert_eq!(
+            bucket.apply_permutation(&[0, 1, 2]),
+            Err(PermutationError::LengthMismatch {
+                len: 3,
+                expected: 4
+            })
+        );
+        assert_eq!(
+            bucket.apply_permutation(&[3, 2, 4, 0]),
+            Err(PermutationError::OutOfRange { slot: 4, len: 4 })
+        );
+        assert_eq!(
+            bucket.apply_permutation(&[1, 0, 1, 2]),
+            Err(PermutationError::Duplicate { slot: 1 })
+        );
+
+        assert_eq!(values(&bucket), vec![0, 1, 2, 3]);
+        assert_resolves(&bucket, &handles);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, DefaultHasher},
+};
+
+use crate::{Bucket, Index, Value, ValueIndex};
+
+/// A plain identifier of an element of a `Bucket`, which can leave the process.
+///
+/// Unlike a `ValueIndex`, a `PersistentId` is just a number, so it can be
+/// stored in a database or sent in a message, then turned back into a handle
+/// by [`Bucket::resolve`]. Identifiers are issued by [`Bucket::persist`] in
+/// increasing order and never reused by the same `Bucket`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PersistentId(u64);
+
+impl PersistentId {
+    /// Creates a `PersistentId` from its raw value, as returned by [`PersistentId::get`].
+    ///
+    /// # Arguments
+    /// * `id` - The raw value of the identifier.
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw value of the identifier.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+type Map<K, V> = HashMap<K, V, BuildHasherDefault<DefaultHasher>>;
+
+/// The persisted elements of a `Bucket`, keyed both ways.
+#[derive(Debug, Default)]
+struct Tables {
+    next: u64,
+    cells: Map<u64, Index>,
+    ids: Map<usize, u64>,
+}
+
+/// The persistent identifiers issued by a `Bucket`, allocated on first use.
+#[derive(Debug)]
+pub(crate) struct PersistTable(Option<Box<Tables>>);
+
+/// Returns the address of a shared cell, which identifies its element.
+fn address(index: &Index) -> usize {
+    Index::as_ptr(index) as usize
+}
+
+impl PersistTable {
+    /// Creates an empty, unallocated table.
+    pub(crate) const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Forgets the identifier of a removed element, if it has one.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the removed element.
+    pub(crate) fn forget(&mut self, index: &Index) {
+        if let Some(tables) = &mut self.0 {
+            if let Some(id) = tables.ids.remove(&address(index)) {
+                tables.cells.remove(&id);
+            }
+        }
+    }
+
+    /// Returns the shared cells of the persisted elements.
+    pub(crate) fn cells(&self) -> impl Iterator<Item = &Index> {
+        self.0.iter().flat_map(|tables| tables.cells.values())
+    }
+
+    /// Returns the identifier of an element, if it was persisted.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the element.
+    pub(crate) fn id(&self, index: &Index) -> Option<u64> {
+        self.0.as_ref()?.ids.get(&address(index)).copied()
+    }
+
+    /// Gives an element back the identifier it was persisted with before being removed.
+    ///
+    /// # Arguments
+    /// * `id` - The identifier previously issued to the element.
+    /// * `index` - The shared cell of the element.
+    pub(crate) fn register(&mut self, id: u64, index: &Index) {
+        let tables = self.0.get_or_insert_with(Box::default);
+        tables.ids.insert(address(index), id);
+        tables.cells.insert(id, index.clone());
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Copies the persisted identifiers over to a clone of the elements of this `Bucket`.
+    ///
+    /// # Arguments
+    /// * `data` - The elements of the clone, in the slot order of this `Bucket`.
+    pub(crate) fn clone_persisted(&self, data: &[Value<T>]) -> PersistTable {
+        let Some(tables) = &self.persisted.0 else {
+            return PersistTable::new();
+        };
+        let mut clone = Tables {
+            next: tables.next,
+            ..Tables::default()
+        };
+
+        for (&id, index) in &tables.cells {
+            if let Some(i) = self.slot(index) {
+                let index = data[i].index.clone();
+                clone.ids.insert(address(&index), id);
+                clone.cells.insert(id, index);
+            }
+        }
+        PersistTable(Some(Box::new(clone)))
+    }
+
+    /// Returns the slot and identifier of every persisted element.
+    pub(crate) fn persisted_slots(&self) -> Vec<(usize, u64)> {
+        let Some(tables) = &self.persisted.0 else {
+            return Vec::new();
+        };
+        tables
+            .cells
+            .iter()
+            .filter_map(|(&id, index)| Some((self.slot(index)?, id)))
+            .collect()
+    }
+
+    /// Returns the persistent identifier of an element, issuing one on first use.
+    ///
+    /// The identifier resolves to the element until it is removed, even if it
+    /// is relocated in the meantime. Returns `None` if the handle does not
+    /// resolve against this `Bucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the element.
+    pub fn persist(&mut self, index: &ValueIndex) -> Option<PersistentId> {
+        let i = self.slot(&index.0)?;
+        let tables = self.persisted.0.get_or_insert_with(Box::default);
+        let cell = &self.data[i].index;
+
+        let id = *tables.ids.entry(address(cell)).or_insert_with(|| {
+            let id = tables.next;
+            tables.next += 1;
+            tables.cells.insert(id, cell.clone());
+            id
+        });
+        Some(PersistentId(id))
+    }
+
+    /// Returns a handle to the element identified by a persistent identifier, if it is still stored.
+    ///
+    /// # Arguments
+    /// * `id` - The identifier returned by [`Bucket::persist`].
+    pub fn resolve(&self, id: PersistentId) -> Option<ValueIndex> {
+        let index = self.persisted.0.as_ref()?.cells.get(&id.0)?;
+        self.slot(index).map(|_| ValueIndex(index.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persist_and_resolve() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..6).map(|i| bucket.insert(i)).collect();
+        let ids: Vec<_> = handles.iter().map(|h| bucket.persist(h).unwrap()).collect();
+        assert_eq!(ids[3].get(), 3);
+        assert_eq!(bucket.persist(&handles[3]), Some(ids[3]));
+
+        // The identifier leaves the process as a plain number
+        let raw: Vec<u64> = ids.iter().map(|id| id.get()).collect();
+        let ids: Vec<_> = raw.into_iter().map(PersistentId::new).collect();
+
+        // Removing relocates the other elements, which keep resolving
+        bucket.remove_index(&handles[0].0);
+        bucket.remove_index(&handles[2].0);
+        for (i, id) in ids.iter().enumerate() {
+            match bucket.resolve(*id) {
+                Some(idx) => assert_eq!(bucket.try_get(&idx), Some(&i)),
+                None => assert!(i == 0 || i == 2),
+            }
+        }
+        assert_eq!(bucket.persisted.0.as_ref().unwrap().cells.len(), 4);
+
+        // Identifiers are never reused
+        let new = bucket.insert(6);
+        assert_eq!(bucket.persist(&new), Some(PersistentId::new(6)));
+        assert!(bucket.resolve(ids[0]).is_none());
+        assert!(bucket.persist(&handles[0]).is_none());
+
+        bucket.clear();
+        assert!(bucket.resolve(ids[1]).is_none());
+        assert!(bucket.persisted.0.as_ref().unwrap().ids.is_empty());
+    }
+
+    #[test]
+    fn test_persist_clone() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let id = bucket.persist(&b).unwrap();
+        let clone = bucket.clone();
+
+        // The clone resolves the identifier to its own element
+        let idx = clone.resolve(id).unwrap();
+        assert_eq!(clone.try_get(&idx), Some(&'b'));
+        assert!(!bucket.contains(&idx));
+
+        bucket.remove_index(&b.0);
+        assert!(bucket.resolve(id).is_none());
+        assert!(clone.resolve(id).is_some());
+        assert!(bucket.resolve(PersistentId::new(7)).is_none());
+        assert!(bucket.contains(&a));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_id() {
+        let mut bucket = Bucket::new(2);
+        bucket.insert("a");
+        let b = bucket.insert("b");
+        let c = bucket.insert("c");
+        bucket.persist(&c);
+        let id = bucket.persist(&b).unwrap();
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, "1");
+        let id: PersistentId = serde_json::from_str(&json).unwrap();
+        assert_eq!(bucket.try_get(&bucket.resolve(id).unwrap()), Some(&"b"));
+
+        bucket.remove_index(&b.0);
+        assert!(bucket.resolve(id).is_none());
+    }
+}
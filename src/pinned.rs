@@ -0,0 +1,286 @@
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Index, ValueIndex, DANGLING};
+
+/// An occupied slot of a `PinnedBucket`.
+struct Pinned<T> {
+    data: Pin<Box<T>>,
+    index: Index,
+}
+
+/// A data structure whose values never move once inserted.
+///
+/// `PinnedBucket` boxes every value and never relocates occupied slots, so
+/// values can safely be accessed through `Pin`. Removal drops the value in
+/// place and frees its slot for reuse, leaving every other slot untouched.
+pub struct PinnedBucket<T> {
+    slots: Vec<Option<Pinned<T>>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> PinnedBucket<T> {
+    /// Creates a new `PinnedBucket` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `PinnedBucket`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `PinnedBucket`.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the `PinnedBucket` is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the pinned elements in the `PinnedBucket`.
+    pub fn iter(&self) -> impl Iterator<Item = Pin<&T>> {
+        self.slots.iter().flatten().map(|v| v.data.as_ref())
+    }
+
+    /// Retrieves a pinned reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get_pin(&self, index: &ValueIndex) -> Option<Pin<&T>> {
+        let i = self.slot(&index.0)?;
+        self.slots[i].as_ref().map(|v| v.data.as_ref())
+    }
+
+    /// Retrieves a pinned mutable reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get_pin_mut(&mut self, index: &ValueIndex) -> Option<Pin<&mut T>> {
+        let i = self.slot(&index.0)?;
+        self.slots[i].as_mut().map(|v| v.data.as_mut())
+    }
+
+    /// Inserts a new value into the `PinnedBucket`, reusing a freed slot if available.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> ValueIndex {
+        let i = self.free.pop().unwrap_or(self.slots.len());
+        let index = Index::new(AtomicUsize::new(i));
+
+        let value = Some(Pinned {
+            data: Box::pin(data),
+            index: index.clone(),
+        });
+
+        if i == self.slots.len() {
+            self.slots.push(value)
+        } else {
+            self.slots[i] = value
+        }
+        self.len += 1;
+
+        ValueIndex(index)
+    }
+
+    /// Removes the value at the specified index, returning whether it existed.
+    ///
+    /// The value is dropped in place and no other element is moved.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> bool {
+        let Some(i) = self.slot(&index.0) else {
+            return false;
+        };
+
+        if let Some(value) = self.slots[i].take() {
+            value.index.store(DANGLING, Ordering::Relaxed)
+        }
+        self.free.push(i);
+        self.len -= 1;
+        true
+    }
+
+    /// Moves occupied slots down to fill the holes left by removals.
+    ///
+    /// Only the boxes are moved between slots, so the values themselves stay
+    /// pinned at their addresses and every handle keeps resolving. Returns the
+    /// `(old, new)` slot of every relocated element, in ascending order.
+    pub fn compact(&mut self) -> Vec<(usize, usize)> {
+        let mut moved = Vec::new();
+
+        let slots: Vec<_> = self
+            .slots
+            .drain(..)
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|v| (i, v)))
+            .enumerate()
+            .map(|(j, (i, v))| {
+                if i != j {
+                    v.index.store(j, Ordering::Relaxed);
+                    moved.push((i, j));
+                }
+                Some(v)
+            })
+            .collect();
+
+        self.slots = slots;
+        self.slots.shrink_to_fit();
+        self.free.clear();
+        moved
+    }
+
+    /// Resolves the slot referenced by `index`, if its element is still stored.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the handle to resolve.
+    fn slot(&self, index: &Index) -> Option<usize> {
+        let i = index.load(Ordering::Relaxed);
+
+        match self.slots.get(i) {
+            Some(Some(v)) if Index::ptr_eq(&v.index, index) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for PinnedBucket<T> {
+    /// Creates an empty `PinnedBucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<T: Debug> Debug for PinnedBucket<T> {
+    /// Formats the pinned bucket for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomPinned;
+
+    use super::*;
+
+    /// A value that must not move once pinned.
+    struct SelfReferential {
+        value: u32,
+        _pin: PhantomPinned,
+    }
+
+    impl SelfReferential {
+        fn bump(self: Pin<&mut Self>) {
+            // SAFETY: `value` is not structurally pinned
+            unsafe { self.get_unchecked_mut().value += 1 }
+        }
+    }
+
+    #[test]
+    fn test_addresses_are_stable() {
+        let mut bucket = PinnedBucket::new(1);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i)).collect();
+        let addresses: Vec<*const i32> = handles
+            .iter()
+            .map(|idx| &*bucket.get_pin(idx).unwrap() as *const _)
+            .collect();
+
+        // Churn every other element and grow the slot storage
+        for idx in handles.iter().step_by(2) {
+            assert!(bucket.remove(idx));
+        }
+        for i in 0..100 {
+            bucket.insert(i);
+        }
+
+        for (i, idx) in handles.iter().enumerate().skip(1).step_by(2) {
+            let pin = bucket.get_pin(idx).unwrap();
+            assert_eq!(*pin, i as i32);
+            assert_eq!(&*pin as *const i32, addresses[i]);
+        }
+        assert_eq!(bucket.len(), 105);
+    }
+
+    #[test]
+    fn test_stale_handles() {
+        let mut bucket = PinnedBucket::default();
+        let a = bucket.insert(1);
+        assert!(bucket.remove(&a));
+        assert!(!bucket.remove(&a));
+
+        // The freed slot is reused by a new element
+        let b = bucket.insert(2);
+        assert_eq!(b.0.load(Ordering::Relaxed), 0);
+        assert!(bucket.get_pin(&a).is_none());
+        assert_eq!(bucket.get_pin(&b).as_deref(), Some(&2));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut bucket = PinnedBucket::new(4);
+        let mut handles: Vec<_> = (0..100).map(|i| (i, bucket.insert(i))).collect();
+
+        // Fragment the slots, refilling some holes along the way
+        handles.retain(|(i, idx)| i % 3 == 0 || !bucket.remove(idx));
+        for i in 100..110 {
+            handles.push((i, bucket.insert(i)));
+        }
+        handles.retain(|(i, idx)| i % 5 != 0 || !bucket.remove(idx));
+
+        let before: Vec<_> = handles
+            .iter()
+            .map(|(_, idx)| {
+                let slot = idx.0.load(Ordering::Relaxed);
+                let addr = &*bucket.get_pin(idx).unwrap() as *const i32;
+                (slot, addr)
+            })
+            .collect();
+        let moved = bucket.compact();
+
+        // Every slot is occupied, and nothing moved in memory
+        assert_eq!(bucket.slots.len(), bucket.len());
+        assert!(bucket.slots.iter().all(Option::is_some));
+        assert!(moved.windows(2).all(|w| w[0].0 < w[1].0));
+
+        for ((i, idx), (slot, addr)) in handles.iter().zip(before) {
+            let pin = bucket.get_pin(idx).unwrap();
+            assert_eq!((*pin, &*pin as *const i32), (*i, addr));
+
+            let now = idx.0.load(Ordering::Relaxed);
+            match moved.iter().find(|(old, _)| *old == slot) {
+                Some(&(_, new)) => assert_eq!(now, new),
+                None => assert_eq!(now, slot),
+            }
+        }
+        assert!(bucket.compact().is_empty());
+    }
+
+    #[test]
+    fn test_not_unpin() {
+        let mut bucket = PinnedBucket::new(4);
+        let idx = bucket.insert(SelfReferential {
+            value: 1,
+            _pin: PhantomPinned,
+        });
+        bucket.insert(SelfReferential {
+            value: 5,
+            _pin: PhantomPinned,
+        });
+
+        bucket.get_pin_mut(&idx).unwrap().bump();
+
+        assert_eq!(bucket.get_pin(&idx).unwrap().value, 2);
+        assert_eq!(bucket.iter().map(|v| v.value).sum::<u32>(), 7);
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    fmt::Debug,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bytemuck::{Pod, PodCastError};
+
+use crate::{Bucket, Index, ValueIndex, DANGLING};
+
+/// A data structure storing plain old data contiguously, so that it can be viewed as bytes.
+///
+/// A `Bucket` stores every value next to its shared cell, so its values do
+/// not form a contiguous slice of `T`. `PodBucket` keeps the values and the
+/// cells in separate arrays instead, and removes an element by moving the
+/// last one into its slot, like `Bucket`.
+pub struct PodBucket<T> {
+    data: Vec<T>,
+    cells: Vec<Index>,
+}
+
+impl<T: Pod> PodBucket<T> {
+    /// Creates a new `PodBucket` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `PodBucket`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            cells: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `PodBucket`.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if the `PodBucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the values in slot order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Returns the values in slot order, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Returns the bytes of the values, in slot order, without copying them.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.data)
+    }
+
+    /// Returns the bytes of the values, in slot order, mutably and without copying them.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        bytemuck::cast_slice_mut(&mut self.data)
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get(&self, index: &ValueIndex) -> Option<&T> {
+        self.slot(&index.0).map(|i| &self.data[i])
+    }
+
+    /// Retrieves a mutable reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get_mut(&mut self, index: &ValueIndex) -> Option<&mut T> {
+        self.slot(&index.0).map(|i| &mut self.data[i])
+    }
+
+    /// Inserts a new value into the next slot.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> ValueIndex {
+        let index = Index::new(AtomicUsize::new(self.len()));
+        self.data.push(data);
+        self.cells.push(index.clone());
+        ValueIndex(index)
+    }
+
+    /// Inserts every value encoded in `bytes`, returning their handles in order.
+    ///
+    /// Nothing is inserted if `bytes` is not aligned for `T`, or if its length
+    /// is not a multiple of the size of `T`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the values to insert.
+    pub fn extend_from_bytes(&mut self, bytes: &[u8]) -> Result<Vec<ValueIndex>, PodCastError> {
+        let values: &[T] = bytemuck::try_cast_slice(bytes)?;
+        self.data.reserve(values.len());
+        self.cells.reserve(values.len());
+
+        Ok(values.iter().map(|&data| self.insert(data)).collect())
+    }
+
+    /// Removes the value at the specified index, if it exists.
+    ///
+    /// The last element is moved into the freed slot.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> Option<T> {
+        let i = self.slot(&index.0)?;
+        let cell = self.cells.swap_remove(i);
+        cell.store(DANGLING, Ordering::Relaxed);
+
+        // Update the index of the moved element, if any
+        if let Some(moved) = self.cells.get(i) {
+            moved.store(i, Ordering::Relaxed)
+        }
+        Some(self.data.swap_remove(i))
+    }
+
+    /// Resolves the slot referenced by `index`, if its element is still stored.
+    ///
+    /// # Arguments
+    /// * `index` - The shared cell of the handle to resolve.
+    fn slot(&self, index: &Index) -> Option<usize> {
+        let i = index.load(Ordering::Relaxed);
+
+        match self.cells.get(i) {
+            Some(cell) if Index::ptr_eq(cell, index) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Pod> From<Bucket<T>> for PodBucket<T> {
+    /// Moves the values of a `Bucket` into a `PodBucket`, in which its handles keep resolving.
+    ///
+    /// Persistent identifiers and interned entries are not carried over.
+    fn from(bucket: Bucket<T>) -> Self {
+        let parts = bucket.into_raw_parts();
+        Self {
+            data: parts.values,
+            cells: parts.cells,
+        }
+    }
+}
+
+impl<T: Pod> Default for PodBucket<T> {
+    /// Creates an empty `PodBucket` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<T: Debug> Debug for PodBucket<T> {
+    /// Formats the bucket for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.data).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Particle {
+        position: [f32; 3],
+        velocity: [f32; 3],
+        age: u32,
+    }
+
+    fn particle(i: u32) -> Particle {
+        let x = i as f32;
+        Particle {
+            position: [x, x + 1.0, x + 2.0],
+            velocity: [-x, 0.5, 0.0],
+            age: i,
+        }
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut bucket = PodBucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(particle(i))).collect();
+        bucket.remove(&handles[3]);
+
+        // The bytes are those of the values, in slot order
+        assert_eq!(bucket.as_bytes().len(), 9 * size_of::<Particle>());
+        let uploaded: Vec<Particle> = bucket
+            .as_bytes()
+            .chunks_exact(size_of::<Particle>())
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        assert_eq!(uploaded, bucket.as_slice());
+
+        let mut copy = PodBucket::default();
+        let ingested = copy
+            .extend_from_bytes(bytemuck::cast_slice(&uploaded))
+            .unwrap();
+        assert_eq!(copy.as_slice(), bucket.as_slice());
+
+        // Every ingested element resolves through its own handle
+        for (idx, value) in ingested.iter().zip(bucket.as_slice()) {
+            assert_eq!(copy.get(idx), Some(value));
+        }
+        assert_eq!(copy.remove(&ingested[0]), Some(particle(0)));
+        assert!(copy.get(&ingested[0]).is_none());
+        assert_eq!(copy.get(&ingested[8]), Some(&bucket.as_slice()[8]));
+    }
+
+    #[test]
+    fn test_bytes_mut() {
+        let mut bucket = PodBucket::new(2);
+        let a = bucket.insert(particle(1));
+        let b = bucket.insert(particle(2));
+
+        // Overwrite the age of the second particle through its bytes
+        let offset = size_of::<Particle>() + 24;
+        bucket.as_bytes_mut()[offset..offset + 4].copy_from_slice(&7u32.to_ne_bytes());
+        assert_eq!(bucket.get(&a), Some(&particle(1)));
+        assert_eq!(bucket.get(&b).map(|p| p.age), Some(7));
+    }
+
+    #[test]
+    fn test_extend_from_bytes_errors() {
+        let mut bucket = PodBucket::<u32>::new(4);
+        let words = [1u32, 2, 3];
+        let bytes: &[u8] = bytemuck::cast_slice(&words);
+
+        // A trailing partial value is rejected
+        assert_eq!(
+            bucket.extend_from_bytes(&bytes[..6]),
+            Err(PodCastError::OutputSliceWouldHaveSlop)
+        );
+
+        // So is a misaligned start
+        assert_eq!(
+            bucket.extend_from_bytes(&bytes[1..5]),
+            Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned)
+        );
+        assert!(bucket.is_empty());
+
+        let handles = bucket.extend_from_bytes(bytes).unwrap();
+        assert_eq!(bucket.as_slice(), &words);
+        assert_eq!(bucket.get(&handles[2]), Some(&3));
+    }
+
+    #[test]
+    fn test_from_bucket() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..6u64).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[1].0);
+
+        // The handles of the `Bucket` keep resolving
+        let pod = PodBucket::from(bucket);
+        assert_eq!(pod.len(), 5);
+        assert!(pod.get(&handles[1]).is_none());
+        for (i, idx) in handles.iter().enumerate().filter(|&(i, _)| i != 1) {
+            assert_eq!(pod.get(idx), Some(&(i as u64)));
+        }
+        assert_eq!(pod.as_bytes().len(), 5 * 8);
+    }
+}
@@ -0,0 +1,298 @@
+/// Describes how the capacity of a `Bucket` grows once it is full, and shrinks back.
+///
+/// Shrinking mirrors growing: the capacity steps back down as soon as the
+/// last step taken up is entirely unused, and never below its original size.
+/// [`Bucket::set_shrink_slack`](crate::Bucket::set_shrink_slack) delays
+/// shrinking by further unused steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GrowthPolicy {
+    /// Grows and shrinks by chunks of the given number of slots.
+    ///
+    /// This is the policy of [`Bucket::new`](crate::Bucket::new), with chunks
+    /// of the original capacity.
+    Linear(usize),
+
+    /// Doubles the capacity when it is full, and halves it back once half of it is unused.
+    ///
+    /// An empty capacity grows to a single slot first.
+    Doubling,
+}
+
+impl GrowthPolicy {
+    /// Returns the capacity following `current`.
+    ///
+    /// # Arguments
+    /// * `current` - The capacity to grow from.
+    pub(crate) const fn grown(self, current: usize) -> usize {
+        match self {
+            Self::Linear(chunk) => current + chunk,
+            Self::Doubling => doubled(current),
+        }
+    }
+
+    /// Returns the smallest capacity following `current` that holds `n` elements.
+    ///
+    /// The capacity is returned unchanged if it cannot grow at all.
+    ///
+    /// # Arguments
+    /// * `current` - The capacity to grow from.
+    /// * `n` - The number of elements to make room for.
+    pub(crate) const fn grown_to(self, mut current: usize, n: usize) -> usize {
+        match self {
+            Self::Linear(chunk) if current < n && chunk > 0 => {
+                current + (n - current).div_ceil(chunk) * chunk
+            }
+            Self::Doubling => {
+                while current < n {
+                    current = doubled(current)
+                }
+                current
+            }
+            _ => current,
+        }
+    }
+
+    /// Returns the capacity preceding `current`.
+    ///
+    /// # Arguments
+    /// * `current` - The capacity to shrink from.
+    pub(crate) const fn shrunk(self, current: usize) -> usize {
+        match self {
+            Self::Linear(chunk) => current.saturating_sub(chunk),
+            Self::Doubling => current / 2,
+        }
+    }
+
+    /// Checks if growing from `original` can lead to `current`.
+    ///
+    /// # Arguments
+    /// * `original` - The initial capacity.
+    /// * `current` - The capacity to check.
+    pub(crate) const fn reaches(self, original: usize, current: usize) -> bool {
+        if current < original {
+            return false;
+        }
+        match self {
+            Self::Linear(0) => current == original,
+            Self::Linear(chunk) => (current - original).is_multiple_of(chunk),
+            Self::Doubling if original == 0 => current == 0 || current.is_power_of_two(),
+            Self::Doubling => {
+                current.is_multiple_of(original) && (current / original).is_power_of_two()
+            }
+        }
+    }
+}
+
+/// Returns twice the given capacity, or a single slot if it is empty.
+///
+/// # Arguments
+/// * `current` - The capacity to double.
+const fn doubled(current: usize) -> usize {
+    match current {
+        0 => 1,
+        _ => current * 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{Bucket, CapacityChangeReason};
+
+    use super::*;
+
+    /// Inserts `n` values, returning the bucket and how many times it grew.
+    fn grow_counted(mut bucket: Bucket<usize>, n: usize) -> (Bucket<usize>, usize) {
+        let grows = Arc::new(AtomicUsize::new(0));
+        let counter = grows.clone();
+        bucket.set_on_capacity_change(move |change| {
+            if change.reason == CapacityChangeReason::Grow {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        for i in 0..n {
+            bucket.insert(i);
+        }
+        let grows = grows.load(Ordering::Relaxed);
+        (bucket, grows)
+    }
+
+    #[test]
+    fn test_doubling_grows_less() {
+        let (linear, linear_grows) = grow_counted(Bucket::new(8), 5000);
+        let (doubling, doubling_grows) =
+            grow_counted(Bucket::with_policy(8, GrowthPolicy::Doubling), 5000);
+
+        assert_eq!(linear_grows, 624);
+        assert_eq!(doubling_grows, 10);
+        assert_eq!(linear.capacity(), 5000);
+        assert_eq!(doubling.capacity(), 8192);
+        assert_eq!(doubling.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_doubling_shrinks_symmetrically() {
+        let mut bucket = Bucket::with_policy(4, GrowthPolicy::Doubling);
+        let handles: Vec<_> = (0..17).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.capacity(), 32);
+
+        // The capacity halves once half of it is unused
+        let mut capacities = Vec::new();
+        for idx in handles.iter().rev() {
+            bucket.remove_index(&idx.0);
+            capacities.push(bucket.capacity());
+        }
+        assert_eq!(capacities[..2], [16, 16]);
+        assert_eq!(capacities[8..10], [8, 8]);
+        assert_eq!(capacities[12..], [4, 4, 4, 4, 4]);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // Bulk removals shrink by every unused step
+        bucket.extend(0..33);
+        assert_eq!(bucket.capacity(), 64);
+        bucket.retain(|&i| i < 3);
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.policy(), GrowthPolicy::Doubling);
+    }
+
+    #[test]
+    fn test_linear_chunks() {
+        let mut bucket = Bucket::with_policy(5, GrowthPolicy::Linear(3));
+        let handles: Vec<_> = (0..9).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.capacity(), 11);
+
+        // Never shrinks below the original capacity
+        for idx in &handles {
+            bucket.remove_index(&idx.0);
+            assert!(bucket.capacity() >= 5);
+        }
+        assert_eq!(bucket.capacity(), 5);
+        assert_eq!(Bucket::<u8>::new(7).policy(), GrowthPolicy::Linear(7));
+    }
+
+    #[test]
+    fn test_reaches() {
+        assert!(GrowthPolicy::Linear(3).reaches(5, 11));
+        assert!(!GrowthPolicy::Linear(3).reaches(5, 10));
+        assert!(!GrowthPolicy::Linear(3).reaches(5, 2));
+        assert!(GrowthPolicy::Doubling.reaches(3, 24));
+        assert!(!GrowthPolicy::Doubling.reaches(3, 18));
+        assert!(GrowthPolicy::Doubling.reaches(0, 0));
+        assert!(GrowthPolicy::Doubling.reaches(0, 8));
+        assert!(!GrowthPolicy::Doubling.reaches(0, 6));
+        assert!(!GrowthPolicy::Linear(0).reaches(2, 4));
+    }
+
+    #[test]
+    fn test_doubling_from_zero() {
+        let mut bucket = Bucket::with_policy(0, GrowthPolicy::Doubling);
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.capacity(), 8);
+
+        for idx in &handles {
+            bucket.remove_index(&idx.0);
+        }
+        assert_eq!(bucket.capacity(), 0);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    /// Counts the capacity changes while the length oscillates around one more than `base`.
+    fn oscillate(mut bucket: Bucket<usize>, base: usize) -> (Bucket<usize>, usize) {
+        let changes = Arc::new(AtomicUsize::new(0));
+        let counter = changes.clone();
+        bucket.extend(0..=base);
+        bucket.set_on_capacity_change(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        for i in 0..100 {
+            let last = bucket.indices().last().unwrap();
+            bucket.remove_index(&last.0);
+            bucket.insert(i);
+        }
+        let changes = changes.load(Ordering::Relaxed);
+        (bucket, changes)
+    }
+
+    #[test]
+    fn test_shrink_slack() {
+        let (_, thrash) = oscillate(Bucket::new(4), 4);
+        assert_eq!(thrash, 200);
+
+        let mut bucket = Bucket::new(4);
+        bucket.set_shrink_slack(1);
+        let (mut bucket, changes) = oscillate(bucket, 4);
+        assert_eq!(changes, 0);
+        assert_eq!(bucket.capacity(), 8);
+
+        // The capacity still steps down once enough of it is unused
+        let handles: Vec<_> = bucket.indices().collect();
+        let mut capacities = Vec::new();
+        for idx in &handles {
+            bucket.remove_index(&idx.0);
+            capacities.push(bucket.capacity());
+        }
+        assert_eq!(capacities, [8, 8, 8, 8, 4]);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_shrink_slack_doubling() {
+        let mut bucket = Bucket::with_policy(4, GrowthPolicy::Doubling);
+        bucket.set_shrink_slack(1);
+        assert_eq!(bucket.shrink_slack(), 1);
+        let handles: Vec<_> = (0..9).map(|i| bucket.insert(i)).collect();
+        assert_eq!(bucket.capacity(), 16);
+
+        let mut capacities = Vec::new();
+        for idx in handles.iter().rev() {
+            bucket.remove_index(&idx.0);
+            capacities.push(bucket.capacity());
+        }
+        assert_eq!(capacities, [16, 16, 16, 16, 8, 8, 4, 4, 4]);
+
+        // Bulk removals follow the same threshold
+        bucket.extend(0..17);
+        assert_eq!(bucket.capacity(), 32);
+        bucket.retain(|&i| i < 5);
+        assert_eq!(bucket.capacity(), 16);
+    }
+
+    #[test]
+    fn test_auto_shrink_disabled() {
+        let mut bucket = Bucket::new(4);
+        bucket.set_auto_shrink(false);
+        assert!(!bucket.auto_shrink());
+        let handles: Vec<_> = (0..20).map(|i| bucket.insert(i)).collect();
+        let allocated = bucket.allocated_capacity();
+
+        // Removals never give the capacity back
+        let mut last = bucket.capacity();
+        for idx in handles.iter().skip(3) {
+            bucket.remove_index(&idx.0);
+            assert!(bucket.capacity() >= last);
+            last = bucket.capacity();
+        }
+        bucket.retain(|&i| i > 0);
+        assert_eq!(bucket.capacity(), 20);
+        assert_eq!(bucket.allocated_capacity(), allocated);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        // Shrinking on request still reclaims it
+        bucket.shrink_to_fit();
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.len(), 2);
+
+        // Growing is unaffected, and shrinking resumes once enabled again
+        bucket.extend(0..4);
+        assert_eq!(bucket.capacity(), 8);
+        bucket.set_auto_shrink(true);
+        bucket.remove_index(&handles[1].0);
+        bucket.remove_index(&handles[2].0);
+        assert_eq!(bucket.capacity(), 4);
+    }
+}
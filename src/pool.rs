@@ -0,0 +1,274 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    rc::{Rc, Weak},
+    sync::atomic::Ordering,
+};
+
+use crate::{Bucket, DANGLING};
+
+/// Counters describing how a `BucketPool` has been used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of buckets handed out by `acquire`.
+    pub acquired: usize,
+
+    /// The number of acquired buckets that were recycled rather than allocated.
+    pub reused: usize,
+
+    /// The number of buckets taken back into the pool.
+    pub returned: usize,
+
+    /// The number of buckets dropped because the pool was already at its limit.
+    pub discarded: usize,
+}
+
+/// The state shared between a `BucketPool` and the buckets it hands out.
+struct Pool<T> {
+    buckets: Vec<Bucket<T>>,
+    capacity: usize,
+    limit: usize,
+    stats: PoolStats,
+}
+
+/// A pool of recycled `Bucket` instances.
+///
+/// `BucketPool` hands out buckets through [`BucketPool::acquire`]. Once the
+/// returned guard is dropped, its `Bucket` is cleared and kept for the next
+/// acquisition, avoiding a fresh allocation of the backing storage.
+pub struct BucketPool<T> {
+    inner: Rc<RefCell<Pool<T>>>,
+}
+
+impl<T> BucketPool<T> {
+    /// Creates a new `BucketPool` retaining at most `limit` buckets.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial capacity of every `Bucket` created by the pool.
+    /// * `limit` - The maximum number of idle buckets kept for reuse.
+    pub fn new(capacity: usize, limit: usize) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Pool {
+                buckets: Vec::with_capacity(limit),
+                capacity,
+                limit,
+                stats: PoolStats::default(),
+            })),
+        }
+    }
+
+    /// Hands out an empty `Bucket`, recycling an idle one if available.
+    pub fn acquire(&self) -> PooledBucket<T> {
+        let mut pool = self.inner.borrow_mut();
+        pool.stats.acquired += 1;
+
+        let bucket = match pool.buckets.pop() {
+            Some(bucket) => {
+                pool.stats.reused += 1;
+                bucket
+            }
+            None => Bucket::new(pool.capacity),
+        };
+
+        PooledBucket {
+            bucket: ManuallyDrop::new(bucket),
+            pool: Rc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns the number of idle buckets currently kept for reuse.
+    pub fn idle(&self) -> usize {
+        self.inner.borrow().buckets.len()
+    }
+
+    /// Returns the usage counters of the pool.
+    pub fn stats(&self) -> PoolStats {
+        self.inner.borrow().stats
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Empties the `Bucket` and resets every setting to those of a new one, keeping its allocations.
+    ///
+    /// The backing storage and the pooled shared cells are kept, along with
+    /// the cells of the removed values that no handle refers to anymore.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial capacity to reset the `Bucket` to.
+    fn reset(&mut self, capacity: usize) {
+        let mut data = mem::take(&mut self.data);
+        for value in data.drain(..) {
+            value.index.store(DANGLING, Ordering::Relaxed);
+            self.persisted.forget(&value.index);
+            self.interned.forget(&value.index);
+            self.recycle(value);
+        }
+        let free_cells = mem::take(&mut self.free_cells);
+
+        *self = Bucket::new_const(capacity);
+        self.data = data;
+        self.free_cells = free_cells;
+        self.set_capacity(self.capacity.clone());
+        self.paranoid()
+    }
+}
+
+/// A `Bucket` borrowed from a `BucketPool`.
+///
+/// The `Bucket` is accessible through `Deref` and `DerefMut`, and is returned
+/// to its pool when dropped. If the pool no longer exists, it is simply dropped.
+pub struct PooledBucket<T> {
+    bucket: ManuallyDrop<Bucket<T>>,
+    pool: Weak<RefCell<Pool<T>>>,
+}
+
+impl<T> Deref for PooledBucket<T> {
+    type Target = Bucket<T>;
+
+    /// Provides immutable access to the pooled `Bucket`.
+    fn deref(&self) -> &Self::Target {
+        &self.bucket
+    }
+}
+
+impl<T> DerefMut for PooledBucket<T> {
+    /// Provides mutable access to the pooled `Bucket`.
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.bucket
+    }
+}
+
+impl<T: Debug> Debug for PooledBucket<T> {
+    /// Formats the pooled `Bucket` for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.bucket.fmt(f)
+    }
+}
+
+impl<T> Drop for PooledBucket<T> {
+    /// Resets the `Bucket` to the settings of the pool and hands it back.
+    fn drop(&mut self) {
+        // SAFETY: the bucket is never accessed again after being taken here
+        let mut bucket = unsafe { ManuallyDrop::take(&mut self.bucket) };
+
+        if let Some(pool) = self.pool.upgrade() {
+            let mut pool = pool.borrow_mut();
+
+            if pool.buckets.len() < pool.limit {
+                bucket.reset(pool.capacity);
+                pool.buckets.push(bucket);
+                pool.stats.returned += 1;
+            } else {
+                pool.stats.discarded += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::counting_alloc::allocations;
+
+    use super::*;
+
+    #[test]
+    fn test_pool_reuses_allocations() {
+        let pool = BucketPool::new(16, 4);
+
+        let first = allocations(|| {
+            pool.acquire().insert(1);
+        });
+        let second = allocations(|| {
+            pool.acquire().insert(1);
+        });
+
+        // The first cycle also allocates the pool of shared cells, from which
+        // the second one reuses the cell of the first inserted value
+        assert_eq!(first, 3);
+        assert_eq!(second, 0);
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                acquired: 2,
+                reused: 1,
+                returned: 2,
+                discarded: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_pool_contents_do_not_leak() {
+        let pool = BucketPool::new(2, 1);
+
+        let idx = {
+            let mut bucket = pool.acquire();
+            (0..5).map(|i| bucket.insert(i)).last().unwrap()
+        };
+
+        let mut bucket = pool.acquire();
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.capacity(), 2);
+        assert_eq!(bucket.slot(&idx.0), None);
+
+        bucket.insert(10);
+        assert_eq!(bucket.slot(&idx.0), None);
+    }
+
+    #[test]
+    fn test_pool_limit() {
+        let pool = BucketPool::<u8>::new(4, 2);
+        let buckets: Vec<_> = (0..5).map(|_| pool.acquire()).collect();
+        drop(buckets);
+
+        assert_eq!(pool.idle(), 2);
+        assert_eq!(pool.stats().returned, 2);
+        assert_eq!(pool.stats().discarded, 3);
+    }
+
+    #[test]
+    fn test_pool_outlived() {
+        let pool = BucketPool::new(4, 2);
+        let mut bucket = pool.acquire();
+        drop(pool);
+
+        bucket.insert(1);
+        assert_eq!(bucket.len(), 1);
+    }
+
+    #[test]
+    fn test_pool_resets_settings() {
+        let pool = BucketPool::new(4, 1);
+        {
+            let mut bucket = pool.acquire();
+            bucket.set_shrink_slack(2);
+            bucket.set_auto_shrink(false);
+            bucket.set_on_capacity_change(|_| {});
+            #[cfg(feature = "ttl")]
+            bucket.set_clock(crate::SystemClock);
+            for i in 0..10 {
+                let idx = bucket.insert(i);
+                bucket.persist(&idx);
+            }
+        }
+
+        let mut bucket = pool.acquire();
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(
+            (bucket.capacity.slack, bucket.capacity.auto_shrink),
+            (0, true)
+        );
+        assert!(bucket.hook.is_none());
+        #[cfg(feature = "ttl")]
+        assert!(bucket.clock.is_none());
+        assert_eq!(bucket.seq, 0);
+
+        // The shared cells of the removed values are kept for reuse
+        assert_eq!(bucket.free_cells.len(), 4);
+        let idx = bucket.insert(1);
+        assert_eq!(bucket.persist(&idx).map(|id| id.get()), Some(0));
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+}
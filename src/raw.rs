@@ -0,0 +1,176 @@
+use crate::{Bucket, Capacity, GrowthPolicy, Index, InvariantViolation, Value};
+
+/// The constituents of a `Bucket`, as returned by [`Bucket::into_raw_parts`].
+///
+/// The value and the cell of every element share their position, which is
+/// the slot the element occupies.
+#[derive(Debug)]
+pub struct RawParts<T> {
+    /// The value of every element, in slot order.
+    pub values: Vec<T>,
+
+    /// The shared cell of every element, in slot order.
+    pub cells: Vec<Index>,
+
+    /// The current capacity of the `Bucket`.
+    pub capacity: usize,
+
+    /// The initial capacity the `Bucket` was created with.
+    pub original_capacity: usize,
+
+    /// How the capacity of the `Bucket` grows and shrinks.
+    pub policy: GrowthPolicy,
+
+    /// The capacity the `Bucket` never grows past, if any.
+    pub max_capacity: Option<usize>,
+
+    /// The number of extra steps the length falls below the previous capacity before shrinking.
+    pub shrink_slack: usize,
+
+    /// Whether removals shrink the capacity of the `Bucket` on their own.
+    pub auto_shrink: bool,
+}
+
+impl<T> Bucket<T> {
+    /// Decomposes the `Bucket` into its values, cells, and capacity configuration.
+    ///
+    /// Every handle keeps its cell, so it resolves again once the parts are
+    /// reassembled with [`Bucket::from_raw_parts`] or [`Bucket::try_from_raw_parts`].
+    pub fn into_raw_parts(mut self) -> RawParts<T> {
+        let (cells, values) = std::mem::take(&mut self.data)
+            .into_iter()
+            .map(|v| (v.index, v.data))
+            .unzip();
+
+        RawParts {
+            values,
+            cells,
+            capacity: self.capacity.current,
+            original_capacity: self.capacity.original,
+            policy: self.capacity.policy,
+            max_capacity: self.capacity.max,
+            shrink_slack: self.capacity.slack,
+            auto_shrink: self.capacity.auto_shrink,
+        }
+    }
+
+    /// Reassembles a `Bucket` from its raw parts without checking them.
+    ///
+    /// Insertion sequence numbers and recency are reassigned in slot order.
+    ///
+    /// # Arguments
+    /// * `parts` - The parts to reassemble.
+    ///
+    /// # Safety
+    /// The parts must hold as many values as cells, every cell must store the
+    /// slot it is given and belong to no other element, and the capacity must
+    /// satisfy the invariants checked by [`Bucket::validate`]. Otherwise,
+    /// handles may resolve to the wrong elements.
+    pub unsafe fn from_raw_parts(parts: RawParts<T>) -> Self {
+        let mut bucket = Self::new_const(parts.original_capacity);
+        bucket.data.reserve_exact(parts.capacity);
+        bucket.capacity = Capacity {
+            original: parts.original_capacity,
+            current: parts.capacity,
+            policy: parts.policy,
+            max: parts.max_capacity,
+            slack: parts.shrink_slack,
+            auto_shrink: parts.auto_shrink,
+        };
+
+        for (data, index) in parts.values.into_iter().zip(parts.cells) {
+            let seq = bucket.next_seq();
+
+            bucket.data.push(Value::with_cell(data, index, seq));
+        }
+        bucket
+    }
+
+    /// Reassembles a `Bucket` from its raw parts, checking every invariant.
+    ///
+    /// # Arguments
+    /// * `parts` - The parts to reassemble.
+    pub fn try_from_raw_parts(parts: RawParts<T>) -> Result<Self, InvariantViolation> {
+        let (values, cells) = (parts.values.len(), parts.cells.len());
+
+        if values != cells {
+            return Err(InvariantViolation::MismatchedParts { values, cells });
+        }
+
+        // SAFETY: the reassembled bucket is validated before being handed out
+        let mut bucket = unsafe { Self::from_raw_parts(parts) };
+
+        match bucket.validate() {
+            Ok(()) => Ok(bucket),
+            Err(e) => {
+                bucket.data.clear();
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[test]
+    fn test_raw_parts_round_trip() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i)).collect();
+        bucket.remove_index(&handles[0].0);
+
+        let parts = bucket.into_raw_parts();
+        assert_eq!(parts.values, vec![4, 1, 2, 3]);
+        assert_eq!((parts.capacity, parts.original_capacity), (4, 2));
+
+        let mut bucket = Bucket::try_from_raw_parts(parts).unwrap();
+        assert!(!bucket.contains(&handles[0]));
+        for (i, idx) in handles.iter().enumerate().skip(1) {
+            assert_eq!(bucket.replace(idx, i * 10), Some(i));
+        }
+
+        // The reassembled bucket keeps managing its capacity
+        bucket.insert(50);
+        assert_eq!(bucket.capacity(), 6);
+        assert_eq!(bucket.remove_index(&handles[2].0), Some(20));
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_try_from_raw_parts_rejects_corruption() {
+        let mut bucket = Bucket::new(4);
+        (0..3).for_each(|i| drop(bucket.insert(i)));
+
+        let parts = bucket.into_raw_parts();
+        parts.cells[1].store(2, Ordering::Relaxed);
+        assert_eq!(
+            Bucket::try_from_raw_parts(parts).unwrap_err(),
+            InvariantViolation::SlotMismatch { slot: 1, stored: 2 }
+        );
+
+        let mut parts = Bucket::<u8>::new(1).into_raw_parts();
+        parts.values.push(1);
+        assert_eq!(
+            Bucket::try_from_raw_parts(parts).unwrap_err(),
+            InvariantViolation::MismatchedParts {
+                values: 1,
+                cells: 0
+            }
+        );
+
+        let mut bucket = Bucket::new(4);
+        bucket.insert(1);
+        let mut parts = bucket.into_raw_parts();
+        parts.capacity = 6;
+        assert_eq!(
+            Bucket::try_from_raw_parts(parts).unwrap_err(),
+            InvariantViolation::InvalidCapacity {
+                current: 6,
+                original: 4
+            }
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::borrow::Borrow;
+
+use crate::{Bucket, ValueIndex};
+
+impl<T> Bucket<T> {
+    /// Checks if the `Bucket` contains a value equal to `value`.
+    ///
+    /// Like `HashMap::get`, the value may be any borrowed form of the element
+    /// type, so a `Bucket<String>` can be searched with a `&str`.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn contains_value<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position(value).is_some()
+    }
+
+    /// Returns a handle to the first value equal to `value`, in slot order.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn index_of<Q>(&self, value: &Q) -> Option<ValueIndex>
+    where
+        T: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position(value).map(|i| self.data[i].index())
+    }
+
+    /// Removes the first value equal to `value`, in slot order, returning it if found.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn remove_value<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let i = self.position(value)?;
+        let value = self._remove(i);
+        self.paranoid();
+        Some(value)
+    }
+
+    /// Returns the slot of the first value equal to `value`, if any.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    fn position<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.data.iter().position(|v| v.data.borrow() == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Index;
+
+    use super::*;
+
+    #[test]
+    fn test_lookup_string_by_str() {
+        let mut bucket = Bucket::new(4);
+        let a = bucket.insert(String::from("foo"));
+        bucket.insert(String::from("bar"));
+        bucket.insert(String::from("foo"));
+
+        assert!(bucket.contains_value("bar"));
+        assert!(!bucket.contains_value("baz"));
+        assert!(Index::ptr_eq(&bucket.index_of("foo").unwrap().0, &a.0));
+
+        assert_eq!(bucket.remove_value("foo").as_deref(), Some("foo"));
+        assert_eq!(bucket.remove_value("foo").as_deref(), Some("foo"));
+        assert_eq!(bucket.remove_value("foo"), None);
+        assert_eq!(bucket.len(), 1);
+    }
+
+    #[test]
+    fn test_lookup_bytes_by_slice() {
+        let mut bucket = Bucket::new(2);
+        bucket.insert(b"abc".to_vec());
+        let b = bucket.insert(Vec::new());
+
+        assert!(bucket.contains_value(b"abc".as_slice()));
+        assert!(!bucket.contains_value(b"ab".as_slice()));
+        assert!(Index::ptr_eq(&bucket.index_of(&[][..]).unwrap().0, &b.0));
+    }
+
+    #[test]
+    fn test_lookup_box_by_value() {
+        let mut bucket = Bucket::new(2);
+        bucket.insert(Box::new(1));
+        bucket.insert(Box::new(2));
+
+        assert!(bucket.contains_value(&2));
+        assert_eq!(bucket.remove_value(&1), Some(Box::new(1)));
+        assert!(bucket.index_of(&1).is_none());
+    }
+}
@@ -0,0 +1,406 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{Bucket, Capacity, GrowthPolicy, Value};
+
+/// The serialized form of a `Bucket`.
+///
+/// Only the values and the capacity configuration are kept. The shared cells
+/// of the handles cannot outlive the process, so they are rebuilt on load.
+#[derive(Serialize)]
+#[serde(rename = "Bucket")]
+struct Repr<V> {
+    capacity: usize,
+    policy: GrowthPolicy,
+    max_capacity: Option<usize>,
+    shrink_slack: usize,
+    auto_shrink: bool,
+    overwrite: bool,
+    values: V,
+}
+
+/// The fields of the serialized form of a `Bucket`, in serialization order.
+const FIELDS: &[&str] = &[
+    "capacity",
+    "policy",
+    "max_capacity",
+    "shrink_slack",
+    "auto_shrink",
+    "overwrite",
+    "values",
+];
+
+/// A field of the serialized form of a `Bucket`.
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    Capacity,
+    Policy,
+    MaxCapacity,
+    ShrinkSlack,
+    AutoShrink,
+    Overwrite,
+    Values,
+    #[serde(other)]
+    Ignored,
+}
+
+/// The values of a `Bucket`, serialized in insertion order.
+struct Values<'a, T>(&'a [Value<T>]);
+
+impl<T: Serialize> Serialize for Values<'_, T> {
+    /// Serializes the values as a sequence, oldest first.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values: Vec<_> = self.0.iter().collect();
+        values.sort_unstable_by_key(|v| v.seq);
+        serializer.collect_seq(values.into_iter().map(|v| &v.data))
+    }
+}
+
+impl<T: Serialize> Serialize for Bucket<T> {
+    /// Serializes the capacity configuration, the overwriting mode and the values, oldest first.
+    ///
+    /// Handles, persistent identifiers, sequence numbers and per-element metadata
+    /// are not serialized.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Repr {
+            capacity: self.capacity.original,
+            policy: self.capacity.policy,
+            max_capacity: self.capacity.max,
+            shrink_slack: self.capacity.slack,
+            auto_shrink: self.capacity.auto_shrink,
+            overwrite: self.overwrite,
+            values: Values(&self.data),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a sequence of values straight into a `Bucket`.
+struct Pushing<'a, T>(&'a mut Bucket<T>);
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for Pushing<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Pushing<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of values")
+    }
+
+    /// Inserts every value in the order it is read.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(value) = seq.next_element()? {
+            self.0.push(value);
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a `Bucket` from its serialized form.
+struct BucketVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for BucketVisitor<T> {
+    type Value = Bucket<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("struct Bucket")
+    }
+
+    /// Reads the fields in any order, as done by self-describing formats.
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Bucket<T>, A::Error> {
+        let mut bucket = Bucket::new_const(0);
+        let (mut capacity, mut policy, mut values) = (None, None, false);
+        let mut max_capacity = None;
+        let mut shrink_slack = 0;
+        let mut auto_shrink = true;
+        let mut overwrite = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Capacity => capacity = Some(map.next_value()?),
+                Field::Policy => policy = Some(map.next_value()?),
+                Field::MaxCapacity => max_capacity = map.next_value()?,
+                Field::ShrinkSlack => shrink_slack = map.next_value()?,
+                Field::AutoShrink => auto_shrink = map.next_value()?,
+                Field::Overwrite => overwrite = Some(map.next_value()?),
+                Field::Values if values => return Err(A::Error::duplicate_field("values")),
+                Field::Values => {
+                    map.next_value_seed(Pushing(&mut bucket))?;
+                    values = true
+                }
+                Field::Ignored => _ = map.next_value::<IgnoredAny>()?,
+            }
+        }
+
+        let repr = Repr {
+            capacity: capacity.ok_or_else(|| A::Error::missing_field("capacity"))?,
+            policy: policy.ok_or_else(|| A::Error::missing_field("policy"))?,
+            max_capacity,
+            shrink_slack,
+            auto_shrink,
+            overwrite: overwrite.ok_or_else(|| A::Error::missing_field("overwrite"))?,
+            values,
+        };
+        if !repr.values {
+            return Err(A::Error::missing_field("values"));
+        }
+        configure(bucket, repr)
+    }
+
+    /// Reads the fields in serialization order, as done by compact formats.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bucket<T>, A::Error> {
+        let mut bucket = Bucket::new_const(0);
+        let next = |i: usize| A::Error::invalid_length(i, &self);
+
+        let repr = Repr {
+            capacity: seq.next_element()?.ok_or_else(|| next(0))?,
+            policy: seq.next_element()?.ok_or_else(|| next(1))?,
+            max_capacity: seq.next_element()?.ok_or_else(|| next(2))?,
+            shrink_slack: seq.next_element()?.ok_or_else(|| next(3))?,
+            auto_shrink: seq.next_element()?.ok_or_else(|| next(4))?,
+            overwrite: seq.next_element()?.ok_or_else(|| next(5))?,
+            values: seq
+                .next_element_seed(Pushing(&mut bucket))?
+                .ok_or_else(|| next(6))?,
+        };
+        configure(bucket, repr)
+    }
+}
+
+/// Applies the capacity configuration to a `Bucket` holding the deserialized values.
+///
+/// # Arguments
+/// * `bucket` - The `Bucket` the values were pushed into.
+/// * `repr` - The deserialized capacity configuration.
+fn configure<T, V, E: Error>(mut bucket: Bucket<T>, repr: Repr<V>) -> Result<Bucket<T>, E> {
+    let Repr {
+        capacity,
+        policy,
+        max_capacity,
+        shrink_slack,
+        auto_shrink,
+        overwrite,
+        ..
+    } = repr;
+
+    if max_capacity.is_some_and(|max| max < capacity) {
+        return Err(E::custom(
+            "the maximum capacity is less than the initial capacity",
+        ));
+    }
+    bucket.capacity = Capacity {
+        max: max_capacity,
+        slack: shrink_slack,
+        auto_shrink,
+        ..Capacity::with_policy(capacity, policy)
+    };
+    bucket.overwrite = overwrite;
+
+    let len = bucket.len();
+    if !overwrite {
+        bucket.capacity.grow_to(len);
+    }
+    if len > bucket.capacity() {
+        return Err(E::custom(format_args!(
+            "{len} values do not fit in a bucket of capacity {capacity}"
+        )));
+    }
+    // Reserve the rest of the capacity fallibly, so that an absurd one read
+    // from untrusted input is an error rather than an abort
+    bucket
+        .data
+        .try_reserve_exact(bucket.capacity() - len)
+        .map_err(E::custom)?;
+    bucket.paranoid();
+    Ok(bucket)
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Bucket<T> {
+    /// Rebuilds a `Bucket` by inserting the values in the order they were serialized.
+    ///
+    /// The values are inserted as they are read, without buffering them first,
+    /// so values borrowing from the input are not copied. The capacity grows
+    /// by whole steps of the growth policy to hold every value, just as if
+    /// they had been inserted one by one. Every value gets a fresh shared
+    /// cell, so handles issued before serializing do not resolve against the
+    /// new `Bucket`.
+    ///
+    /// Returns an error if the capacity cannot be allocated, rather than aborting.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Bucket", FIELDS, BucketVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(bucket: &Bucket<String>) -> Bucket<String> {
+        let json = serde_json::to_string(bucket).unwrap();
+        let restored: Bucket<String> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.validate(), Ok(()));
+        assert_eq!(&restored, bucket);
+        assert_eq!(restored.capacity(), bucket.capacity());
+        restored
+    }
+
+    #[test]
+    fn test_round_trip_empty() {
+        let bucket = Bucket::new(4);
+        assert_eq!(
+            serde_json::to_string(&bucket).unwrap(),
+            r#"{"capacity":4,"policy":{"Linear":4},"max_capacity":null,"shrink_slack":0,"auto_shrink":true,"overwrite":false,"values":[]}"#
+        );
+
+        let mut restored = round_trip(&bucket);
+        let idx = restored.insert(String::from("a"));
+        assert!(restored.contains(&idx));
+        assert_eq!(restored.len(), 1);
+    }
+
+    #[test]
+    fn test_round_trip_full() {
+        let mut bucket = Bucket::new(4);
+        for c in ["a", "b", "c", "d"] {
+            bucket.insert(String::from(c));
+        }
+        let mut restored = round_trip(&bucket);
+        assert_eq!(restored, ["a", "b", "c", "d"]);
+
+        // The restored bucket keeps growing and shrinking in chunks
+        let e = restored.insert(String::from("e"));
+        assert_eq!(restored.capacity(), 8);
+        restored.remove_index(&e.0);
+        assert_eq!(restored.capacity(), 4);
+        assert_eq!(restored.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_round_trip_after_removals() {
+        let mut bucket = Bucket::new(2);
+        let handles: Vec<_> = (0..9).map(|i| bucket.insert(i.to_string())).collect();
+        for i in [0, 4, 5, 8] {
+            bucket.remove_index(&handles[i].0);
+        }
+
+        let mut restored = round_trip(&bucket);
+
+        // Values are restored in insertion order
+        assert_eq!(restored, ["1", "2", "3", "6", "7"]);
+
+        // Old handles do not carry over, but new ones work as usual
+        assert!(!restored.contains(&handles[1]));
+        let new: Vec<_> = restored.as_entries().iter().map(|v| v.index()).collect();
+        restored.remove_index(&new[0].0);
+        restored.remove_index(&new[3].0);
+        let idx = restored.insert(String::from("x"));
+        assert!(restored.contains(&idx));
+        assert!(restored.contains(&new[4]));
+        assert_eq!(restored.validate(), Ok(()));
+
+        assert_eq!(restored.into_sorted_vec(), ["2", "3", "7", "x"]);
+    }
+
+    #[test]
+    fn test_round_trip_policy() {
+        let mut bucket = Bucket::with_policy(2, GrowthPolicy::Doubling);
+        bucket.extend((0..5).map(|i| i.to_string()));
+
+        let mut restored = round_trip(&bucket);
+        assert_eq!(restored.policy(), GrowthPolicy::Doubling);
+        restored.extend((5..9).map(|i| i.to_string()));
+        assert_eq!(restored.capacity(), 16);
+    }
+
+    #[test]
+    fn test_round_trip_overwriting() {
+        let mut bucket = Bucket::bounded_overwriting(3);
+        bucket.extend((0..5).map(|i| i.to_string()));
+
+        // The oldest value is still evicted first after a round trip
+        let mut restored = round_trip(&bucket);
+        assert_eq!(
+            restored
+                .insert_overwriting(String::from("5"))
+                .unwrap()
+                .1
+                .as_deref(),
+            Some("2")
+        );
+
+        let invalid = r#"{"capacity":1,"policy":"Doubling","overwrite":true,"values":["a","b"]}"#;
+        assert!(serde_json::from_str::<Bucket<String>>(invalid).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record<'a> {
+        id: u32,
+        name: &'a str,
+        tags: Vec<&'a str>,
+    }
+
+    #[test]
+    fn test_borrowed_round_trip() {
+        let mut bucket = Bucket::new(2);
+        for (id, name) in ["alpha", "beta", "gamma"].into_iter().enumerate() {
+            bucket.insert(Record {
+                id: id as u32,
+                name,
+                tags: vec!["x", name],
+            });
+        }
+        let json = serde_json::to_string(&bucket).unwrap();
+        let restored: Bucket<Record<'_>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, bucket);
+        assert_eq!(restored.capacity(), 4);
+
+        // Every string points into the serialized buffer
+        let range = json.as_bytes().as_ptr_range();
+        let borrowed = |s: &str| range.contains(&s.as_ptr());
+        for v in restored.iter() {
+            assert!(borrowed(v.name));
+            assert!(v.tags.iter().all(|t| borrowed(t)));
+        }
+    }
+
+    #[test]
+    fn test_deserialize_fields() {
+        // Fields may come in any order, and unknown ones are ignored
+        let json = r#"{"values":[1,2,3],"extra":{"a":[]},"overwrite":false,"policy":"Doubling","capacity":2}"#;
+        let bucket: Bucket<u8> = serde_json::from_str(json).unwrap();
+        assert_eq!(bucket, [1, 2, 3]);
+        assert_eq!(bucket.capacity(), 4);
+        assert_eq!(bucket.validate(), Ok(()));
+
+        let missing = r#"{"capacity":2,"policy":"Doubling","overwrite":false}"#;
+        assert!(serde_json::from_str::<Bucket<u8>>(missing).is_err());
+        let twice =
+            r#"{"capacity":2,"policy":"Doubling","overwrite":false,"values":[],"values":[]}"#;
+        assert!(serde_json::from_str::<Bucket<u8>>(twice).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_huge_capacity() {
+        let json =
+            r#"{"capacity":1000000000000000,"policy":{"Linear":1},"overwrite":false,"values":[]}"#;
+        assert!(serde_json::from_str::<Bucket<[u64; 4]>>(json).is_err());
+        let json = r#"{"capacity":18446744073709551615,"policy":"Doubling","overwrite":true,"values":[1]}"#;
+        assert!(serde_json::from_str::<Bucket<u8>>(json).is_err());
+
+        let json =
+            r#"{"capacity":4,"policy":"Doubling","max_capacity":2,"overwrite":false,"values":[]}"#;
+        assert!(serde_json::from_str::<Bucket<u8>>(json).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap},
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{Bucket, Index, ValueIndex};
+
+/// A `Bucket` that never stores two equal values.
+///
+/// Every value is indexed by its hash, so duplicates are detected on
+/// insertion and values can be looked up without scanning the `Bucket`.
+/// Values whose hashes collide share an index entry and are told apart by
+/// equality.
+pub struct BucketSet<T> {
+    // Dropped before the `Bucket`, so that its handles are not reported as leaked
+    hashes: HashMap<u64, Vec<ValueIndex>>,
+    bucket: Bucket<T>,
+    state: RandomState,
+}
+
+impl<T: Hash + Eq> BucketSet<T> {
+    /// Creates a new `BucketSet` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `BucketSet`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            hashes: HashMap::new(),
+            bucket: Bucket::new(capacity),
+            state: RandomState::new(),
+        }
+    }
+
+    /// Returns the number of elements currently stored in the `BucketSet`.
+    pub fn len(&self) -> usize {
+        self.bucket.len()
+    }
+
+    /// Checks if the `BucketSet` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bucket.is_empty()
+    }
+
+    /// Returns an iterator over the elements in the `BucketSet`, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.bucket.data.iter().map(|v| &v.data)
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn get(&self, index: &ValueIndex) -> Option<&T> {
+        let i = self.bucket.slot(&index.0)?;
+        Some(&self.bucket.data[i].data)
+    }
+
+    /// Checks if the `BucketSet` contains a value equal to `value`.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Returns a handle to the value equal to `value`, if any.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn get_handle<Q>(&self, value: &Q) -> Option<ValueIndex>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(value).map(|(_, idx)| ValueIndex(idx.0.clone()))
+    }
+
+    /// Inserts a new value into the `BucketSet`.
+    ///
+    /// If an equal value is already stored, the new one is handed back
+    /// together with a handle to the existing one.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> Result<ValueIndex, (ValueIndex, T)> {
+        if let Some(existing) = self.get_handle(&data) {
+            return Err((existing, data));
+        }
+
+        let hash = self.state.hash_one(&data);
+        let index = self.bucket.insert(data);
+        self.hashes
+            .entry(hash)
+            .or_default()
+            .push(ValueIndex(index.0.clone()));
+        Ok(index)
+    }
+
+    /// Removes the value at the specified index, returning it if it existed.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> Option<T> {
+        let value = self.get(index)?;
+        let hash = self.state.hash_one(value);
+        self.unlink(hash, index);
+        self.bucket.remove_index(&index.0)
+    }
+
+    /// Removes the value equal to `value`, returning it if found.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    pub fn remove_value<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (hash, index) = self.find(value)?;
+        let index = ValueIndex(index.0.clone());
+        self.unlink(hash, &index);
+        self.bucket.remove_index(&index.0)
+    }
+
+    /// Finds the hash and the index entry of the value equal to `value`.
+    ///
+    /// # Arguments
+    /// * `value` - The value to search for.
+    fn find<Q>(&self, value: &Q) -> Option<(u64, &ValueIndex)>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.state.hash_one(value);
+        self.hashes
+            .get(&hash)?
+            .iter()
+            .find(|idx| self.get(idx).is_some_and(|v| v.borrow() == value))
+            .map(|idx| (hash, idx))
+    }
+
+    /// Drops the index entry of `index` under the given hash.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the value at `index`.
+    /// * `index` - The `ValueIndex` whose entry to drop.
+    fn unlink(&mut self, hash: u64, index: &ValueIndex) {
+        let Some(handles) = self.hashes.get_mut(&hash) else {
+            return;
+        };
+        handles.retain(|idx| !Index::ptr_eq(&idx.0, &index.0));
+
+        if handles.is_empty() {
+            self.hashes.remove(&hash);
+        }
+    }
+}
+
+impl<T: Hash + Eq> Default for BucketSet<T> {
+    /// Creates an empty `BucketSet` with a default initial capacity.
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl<T: Hash + Eq + Debug> Debug for BucketSet<T> {
+    /// Formats the set for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+
+    use super::*;
+
+    /// A value whose hashes always collide.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Colliding(u32);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u8(0)
+        }
+    }
+
+    #[test]
+    fn test_set_under_churn() {
+        let mut set = BucketSet::new(4);
+        let mut live = Vec::new();
+
+        for round in 0..10u32 {
+            for i in 0..20 {
+                let value = format!("{}", (round * 7 + i) % 30);
+                match set.insert(value.clone()) {
+                    Ok(idx) => live.push((value, idx)),
+                    Err((existing, rejected)) => {
+                        assert_eq!(set.get(&existing), Some(&rejected));
+                    }
+                }
+            }
+            // Remove two of every three values, by handle and by value
+            let mut n = 0;
+            live.retain(|(value, idx)| {
+                n += 1;
+                match n % 3 {
+                    0 => set.remove(idx).as_ref() != Some(value),
+                    1 => set.remove_value(value.as_str()).as_ref() != Some(value),
+                    _ => true,
+                }
+            });
+
+            assert_eq!(set.len(), live.len());
+            for (value, idx) in &live {
+                assert!(set.contains(value.as_str()));
+                assert!(Index::ptr_eq(
+                    &set.get_handle(value.as_str()).unwrap().0,
+                    &idx.0
+                ));
+            }
+        }
+        assert!(set.remove_value("unknown").is_none());
+    }
+
+    #[test]
+    fn test_set_hash_collisions() {
+        let mut set = BucketSet::default();
+        let a = set.insert(Colliding(1)).unwrap();
+        let b = set.insert(Colliding(2)).unwrap();
+        let (existing, rejected) = set.insert(Colliding(1)).unwrap_err();
+
+        assert_eq!(rejected, Colliding(1));
+        assert!(Index::ptr_eq(&existing.0, &a.0));
+        assert_eq!(set.hashes.len(), 1);
+
+        assert_eq!(set.remove(&a), Some(Colliding(1)));
+        assert!(!set.contains(&Colliding(1)));
+        assert!(Index::ptr_eq(
+            &set.get_handle(&Colliding(2)).unwrap().0,
+            &b.0
+        ));
+        assert!(set.insert(Colliding(1)).is_ok());
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    iter::Flatten,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread, vec,
+};
+
+use crate::{Bucket, SyncBucket, ValueIndex};
+
+/// A handle to a value stored in a `ShardedBucket`.
+///
+/// Besides the handle of the value within its shard, it records which shard
+/// holds the value, so accessing it only locks that shard.
+#[derive(Debug)]
+pub struct ShardIndex {
+    shard: usize,
+    index: ValueIndex,
+}
+
+impl ShardIndex {
+    /// Returns the position of the shard holding the value.
+    pub const fn shard(&self) -> usize {
+        self.shard
+    }
+
+    /// Returns the handle of the value within its shard.
+    pub const fn index(&self) -> &ValueIndex {
+        &self.index
+    }
+}
+
+/// A `Bucket` split into independently locked shards, for shared use under heavy contention.
+///
+/// Unlike a [`SyncBucket`], whose single lock serializes every insertion, a
+/// `ShardedBucket` spreads insertions over its shards in a round-robin
+/// fashion, so threads inserting at the same time mostly lock distinct
+/// shards. Every other operation only locks the shard of its handle.
+pub struct ShardedBucket<T> {
+    shards: Box<[SyncBucket<T>]>,
+    next: AtomicUsize,
+}
+
+impl<T> ShardedBucket<T> {
+    /// Creates a new `ShardedBucket` with one shard per available thread.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in each shard.
+    pub fn new(capacity: usize) -> Self {
+        let shards = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_shards(capacity, shards)
+    }
+
+    /// Creates a new `ShardedBucket` with the specified number of shards.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in each shard.
+    /// * `shards` - The number of shards.
+    ///
+    /// # Panics
+    /// Panics if `shards` is zero.
+    pub fn with_shards(capacity: usize, shards: usize) -> Self {
+        if shards == 0 {
+            panic!("attempted to create a `ShardedBucket` without any shard")
+        }
+        Self {
+            shards: (0..shards).map(|_| SyncBucket::new(capacity)).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Inserts a new value into the next shard in turn, returning its handle.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&self, data: T) -> ShardIndex {
+        let shard = self.next.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        let index = self.shards[shard].insert(data);
+        ShardIndex { shard, index }
+    }
+
+    /// Removes the value referenced by `index`, if it is still stored in its shard.
+    ///
+    /// # Arguments
+    /// * `index` - The `ShardIndex` of the value to remove.
+    pub fn remove(&self, index: &ShardIndex) -> Option<T> {
+        self.shards.get(index.shard)?.remove(&index.index)
+    }
+
+    /// Calls `f` on the value referenced by `index`, if any, while holding a read lock on its shard.
+    ///
+    /// # Arguments
+    /// * `index` - The `ShardIndex` of the value to read.
+    /// * `f` - The closure called on the value.
+    pub fn with<R>(&self, index: &ShardIndex, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.shards.get(index.shard)?.with(&index.index, f)
+    }
+
+    /// Calls `f` on the value referenced by `index`, if any, while holding a write lock on its shard.
+    ///
+    /// # Arguments
+    /// * `index` - The `ShardIndex` of the value to modify.
+    /// * `f` - The closure called on the value.
+    pub fn with_mut<R>(&self, index: &ShardIndex, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.shards.get(index.shard)?.with_mut(&index.index, f)
+    }
+
+    /// Calls `f` on every value, one shard after the other.
+    ///
+    /// Each shard is read-locked while its values are visited, so values may be
+    /// inserted into or removed from the other shards in the meantime.
+    ///
+    /// # Arguments
+    /// * `f` - The closure called on each value.
+    pub fn for_each(&self, mut f: impl FnMut(&T)) {
+        for shard in &self.shards {
+            shard.read().data.iter().for_each(|v| f(&v.data))
+        }
+    }
+
+    /// Returns the number of elements stored across every shard.
+    ///
+    /// Each shard is locked in turn, so the sum may be outdated by the time it
+    /// is returned if other threads are inserting or removing.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(SyncBucket::len).sum()
+    }
+
+    /// Checks if every shard is empty.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(SyncBucket::is_empty)
+    }
+
+    /// Consumes the `ShardedBucket`, returning the underlying buckets in shard order.
+    pub fn into_shards(self) -> Vec<Bucket<T>> {
+        self.shards
+            .into_vec()
+            .into_iter()
+            .map(SyncBucket::into_inner)
+            .collect()
+    }
+}
+
+impl<T> IntoIterator for ShardedBucket<T> {
+    type Item = T;
+    type IntoIter = Flatten<vec::IntoIter<Bucket<T>>>;
+
+    /// Returns an iterator over the values of every shard, one shard after the other.
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_shards().into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_shards() {
+        let bucket = ShardedBucket::with_shards(8, 4);
+        let (inserted, removed) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        thread::scope(|s| {
+            for t in 0..8 {
+                let (bucket, inserted, removed) = (&bucket, &inserted, &removed);
+                s.spawn(move || {
+                    let mut own = Vec::new();
+                    for i in 0..500 {
+                        own.push((bucket.insert(t * 1000 + i), t * 1000 + i));
+                        inserted.fetch_add(1, Ordering::Relaxed);
+
+                        if i % 3 == 0 {
+                            let (idx, value) = own.swap_remove(i % own.len());
+                            assert_eq!(bucket.remove(&idx), Some(value));
+                            assert_eq!(bucket.remove(&idx), None);
+                            removed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    for (idx, value) in &own {
+                        assert_eq!(bucket.with(idx, |v| *v), Some(*value));
+                    }
+                });
+            }
+        });
+
+        let expected = inserted.into_inner() - removed.into_inner();
+        assert_eq!(bucket.len(), expected);
+
+        let mut count = 0;
+        bucket.for_each(|_| count += 1);
+        assert_eq!(count, expected);
+
+        // Insertions were spread over every shard
+        let shards = bucket.into_shards();
+        assert_eq!(shards.len(), 4);
+        for shard in &shards {
+            assert!(!shard.is_empty());
+            assert_eq!(shard.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_shard_routing() {
+        let bucket = ShardedBucket::with_shards(2, 3);
+        let handles: Vec<_> = (0..6).map(|i| bucket.insert(i)).collect();
+        let shards: Vec<_> = handles.iter().map(ShardIndex::shard).collect();
+        assert_eq!(shards, [0, 1, 2, 0, 1, 2]);
+
+        assert_eq!(bucket.with_mut(&handles[4], |v| *v *= 10), Some(()));
+        assert_eq!(bucket.remove(&handles[4]), Some(40));
+
+        // A handle routed to a shard that does not exist resolves to nothing
+        let other = ShardedBucket::with_shards(2, 8);
+        let foreign: Vec<_> = (0..8).map(|i| other.insert(i)).collect();
+        assert_eq!(bucket.with(&foreign[7], |v| *v), None);
+        assert_eq!(bucket.remove(&foreign[0]), None);
+
+        let mut values: Vec<_> = bucket.into_iter().collect();
+        values.sort();
+        assert_eq!(values, [0, 1, 2, 3, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "without any shard")]
+    fn test_no_shards() {
+        ShardedBucket::<u8>::with_shards(4, 0);
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Bucket, ValueIndex};
+
+/// A `Bucket` that can be shared between threads and modified through `&self`.
+///
+/// Every insertion and removal takes a write lock on the whole `Bucket`, held
+/// across the relocation of the last element and the update of its shared
+/// cell, so readers never resolve a handle to a slot that is being moved.
+/// Reading values through [`SyncBucket::with`] only takes a read lock, so
+/// readers proceed in parallel with each other.
+///
+/// A poisoned lock is still acquired, so a panic on one thread does not make
+/// the `SyncBucket` unusable for the others. A value a panicking closure was
+/// modifying through [`SyncBucket::with_mut`] may be left partially updated.
+#[derive(Debug, Default)]
+pub struct SyncBucket<T> {
+    inner: RwLock<Bucket<T>>,
+}
+
+impl<T> SyncBucket<T> {
+    /// Creates a new `SyncBucket` with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the `Bucket`.
+    pub fn new(capacity: usize) -> Self {
+        Bucket::new(capacity).into()
+    }
+
+    /// Inserts a new value, returning its handle.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&self, data: T) -> ValueIndex {
+        self.write().insert(data)
+    }
+
+    /// Removes the value referenced by `index`, if it is still stored in the `Bucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&self, index: &ValueIndex) -> Option<T> {
+        self.write().remove_index(&index.0)
+    }
+
+    /// Calls `f` on the value referenced by `index`, if any, while holding a read lock.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to read.
+    /// * `f` - The closure called on the value.
+    pub fn with<R>(&self, index: &ValueIndex, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.read().try_get(index).map(f)
+    }
+
+    /// Calls `f` on the value referenced by `index`, if any, while holding a write lock.
+    ///
+    /// The value is flagged as modified.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to modify.
+    /// * `f` - The closure called on the value.
+    pub fn with_mut<R>(&self, index: &ValueIndex, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut bucket = self.write();
+        let i = bucket.slot(&index.0)?;
+        let value = &mut bucket.data[i];
+        value.mark();
+        Some(f(&mut value.data))
+    }
+
+    /// Returns the number of elements currently stored in the `Bucket`.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Checks if the `Bucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Consumes the `SyncBucket`, returning the underlying `Bucket`.
+    pub fn into_inner(self) -> Bucket<T> {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the `Bucket` for reading, blocking until it is available.
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Bucket<T>> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the `Bucket` for writing, blocking until it is available.
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, Bucket<T>> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> From<Bucket<T>> for SyncBucket<T> {
+    /// Wraps a `Bucket` for shared use, keeping every handle it issued valid.
+    fn from(bucket: Bucket<T>) -> Self {
+        Self {
+            inner: RwLock::new(bucket),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_concurrent_mixed_access() {
+        let bucket = SyncBucket::new(4);
+        let shared: Vec<_> = (0..100).map(|i| bucket.insert(i)).collect();
+
+        thread::scope(|s| {
+            for t in 0..8 {
+                let (bucket, shared) = (&bucket, &shared);
+                s.spawn(move || {
+                    let base = 1000 * (t + 1);
+                    let own: Vec<_> = (0..200).map(|i| bucket.insert(base + i)).collect();
+
+                    for (i, idx) in own.iter().enumerate() {
+                        // Values shared by every thread keep resolving while others relocate them
+                        let j = (i + t) % shared.len();
+                        assert_eq!(bucket.with(&shared[j], |v| *v), Some(j));
+
+                        assert_eq!(bucket.with(idx, |v| *v), Some(base + i));
+                        if i % 2 == 0 {
+                            assert_eq!(bucket.remove(idx), Some(base + i));
+                            assert_eq!(bucket.with(idx, |v| *v), None);
+                        }
+                    }
+                    for (i, idx) in own.iter().enumerate().skip(1).step_by(2) {
+                        assert_eq!(bucket.with_mut(idx, |v| *v += 1), Some(()));
+                        assert_eq!(bucket.remove(idx), Some(base + i + 1));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(bucket.len(), 100);
+        let bucket = bucket.into_inner();
+        assert_eq!(bucket.validate(), Ok(()));
+        for (i, idx) in shared.iter().enumerate() {
+            assert_eq!(bucket.try_get(idx), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_from_bucket() {
+        let mut bucket = Bucket::new(2);
+        let idx = bucket.insert('a');
+
+        let bucket = SyncBucket::from(bucket);
+        assert_eq!(bucket.with(&idx, |v| *v), Some('a'));
+        assert_eq!(bucket.remove(&idx), Some('a'));
+        assert!(bucket.is_empty());
+        assert_eq!(bucket.remove(&ValueIndex::dangling()), None);
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    fmt::Debug,
+    mem::MaybeUninit,
+    ptr, slice,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{slot_in, Bucket, Index, Iter, IterMut, Value, ValueIndex, DANGLING};
+
+/// Up to `N` values stored inline, in slot order.
+struct InlineValues<T, const N: usize> {
+    values: [MaybeUninit<Value<T>>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> InlineValues<T, N> {
+    /// Creates an empty set of inline values.
+    const fn new() -> Self {
+        Self {
+            values: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the initialized values.
+    fn as_slice(&self) -> &[Value<T>] {
+        // SAFETY: the first `len` values are initialized
+        unsafe { slice::from_raw_parts(self.values.as_ptr().cast(), self.len) }
+    }
+
+    /// Returns the initialized values mutably.
+    fn as_mut_slice(&mut self) -> &mut [Value<T>] {
+        // SAFETY: the first `len` values are initialized
+        unsafe { slice::from_raw_parts_mut(self.values.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// Appends a value, handing it back if every inline slot is taken.
+    ///
+    /// # Arguments
+    /// * `value` - The value to append.
+    fn push(&mut self, value: Value<T>) -> Result<(), Value<T>> {
+        let Some(slot) = self.values.get_mut(self.len) else {
+            return Err(value);
+        };
+        slot.write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the value in slot `i`, moving the last value into its slot.
+    ///
+    /// # Arguments
+    /// * `i` - The slot of the value to remove.
+    fn swap_remove(&mut self, i: usize) -> Value<T> {
+        if i >= self.len {
+            panic!(
+                "attempted to remove slot {i} from {} inline values",
+                self.len
+            )
+        }
+        let last = self.len - 1;
+        let base = self.values.as_mut_ptr().cast::<Value<T>>();
+        self.len = last;
+
+        // SAFETY: both slots were initialized, and the last one is forgotten by shortening the length
+        unsafe {
+            let value = ptr::read(base.add(i));
+            ptr::copy(base.add(last), base.add(i), 1);
+            value
+        }
+    }
+
+    /// Moves every value out, leaving no value stored inline.
+    fn take_all(&mut self) -> Vec<Value<T>> {
+        let len = std::mem::take(&mut self.len);
+
+        // SAFETY: the first `len` values are initialized, and forgotten by resetting the length
+        (0..len)
+            .map(|i| unsafe { self.values[i].assume_init_read() })
+            .collect()
+    }
+}
+
+impl<T, const N: usize> Drop for InlineValues<T, N> {
+    /// Drops the initialized values.
+    fn drop(&mut self) {
+        // SAFETY: the first `len` values are initialized and never used again
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+/// Where the values of a `SmallBucket` are stored.
+enum Storage<T, const N: usize> {
+    Inline(InlineValues<T, N>),
+    Spilled(Bucket<T>),
+}
+
+/// A `Bucket` storing up to `N` values inline, without allocating their storage.
+///
+/// Once an insertion would exceed `N` values, every value is moved into a
+/// heap-allocated `Bucket` growing by chunks of `N`. The values move back
+/// inline once removals leave at most half of `N` of them, which avoids
+/// moving them back and forth around `N`. Handles keep resolving across both
+/// moves, since every value keeps its slot and shared cell.
+///
+/// The shared cell of each handle is still allocated on its own.
+pub struct SmallBucket<T, const N: usize> {
+    storage: Storage<T, N>,
+    seq: u64,
+}
+
+impl<T, const N: usize> SmallBucket<T, N> {
+    /// Creates a new, empty `SmallBucket` storing its values inline.
+    pub const fn new() -> Self {
+        Self {
+            storage: Storage::Inline(InlineValues::new()),
+            seq: 0,
+        }
+    }
+
+    /// Checks if the values were moved into a heap-allocated `Bucket`.
+    pub const fn is_spilled(&self) -> bool {
+        matches!(self.storage, Storage::Spilled(_))
+    }
+
+    /// Returns the number of elements currently stored in the `SmallBucket`.
+    pub fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    /// Checks if the `SmallBucket` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values().is_empty()
+    }
+
+    /// Returns the current capacity, which is `N` while the values are stored inline.
+    pub fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline(_) => N,
+            Storage::Spilled(bucket) => bucket.capacity(),
+        }
+    }
+
+    /// Inserts a new value, moving every value to the heap if the inline slots are taken.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> ValueIndex {
+        let inline = match &mut self.storage {
+            Storage::Inline(inline) => inline,
+            Storage::Spilled(bucket) => return bucket.insert(data),
+        };
+        self.seq += 1;
+        let index = Index::new(AtomicUsize::new(inline.len));
+
+        match inline.push(Value::with_cell(data, index.clone(), self.seq)) {
+            Ok(()) => ValueIndex(index),
+            Err(value) => {
+                self.spill(value);
+                ValueIndex(index)
+            }
+        }
+    }
+
+    /// Removes the value referenced by `index`, if it is still stored in the `SmallBucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> Option<T> {
+        let inline = match &mut self.storage {
+            Storage::Inline(inline) => inline,
+            Storage::Spilled(bucket) => {
+                let value = bucket.remove_index(&index.0)?;
+                if bucket.len() <= N / 2 {
+                    self.unspill()
+                }
+                return Some(value);
+            }
+        };
+        let i = slot_in(inline.as_slice(), &index.0)?;
+        let value = inline.swap_remove(i);
+        value.index.store(DANGLING, Ordering::Relaxed);
+
+        // Update the index of the moved value, if any
+        if let Some(moved) = inline.as_slice().get(i) {
+            moved.index.store(i, Ordering::Relaxed)
+        }
+        Some(value.data)
+    }
+
+    /// Checks if the handle resolves to an element of the `SmallBucket`.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` to check.
+    pub fn contains(&self, index: &ValueIndex) -> bool {
+        slot_in(self.values(), &index.0).is_some()
+    }
+
+    /// Retrieves a reference to the value at the given index, if it exists.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn try_get(&self, index: &ValueIndex) -> Option<&T> {
+        let values = self.values();
+        slot_in(values, &index.0).map(|i| &values[i].data)
+    }
+
+    /// Retrieves a mutable reference to the value at the given index, if it exists.
+    ///
+    /// The value is flagged as modified.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to retrieve.
+    pub fn try_get_mut(&mut self, index: &ValueIndex) -> Option<&mut T> {
+        let values = self.values_mut();
+        let value = &mut values[slot_in(values, &index.0)?];
+        value.mark();
+        Some(&mut value.data)
+    }
+
+    /// Returns an iterator over the values, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.values().iter())
+    }
+
+    /// Returns a mutable iterator over the values, in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.values_mut().iter_mut())
+    }
+
+    /// Returns the stored values, in slot order.
+    fn values(&self) -> &[Value<T>] {
+        match &self.storage {
+            Storage::Inline(inline) => inline.as_slice(),
+            Storage::Spilled(bucket) => &bucket.data,
+        }
+    }
+
+    /// Returns the stored values mutably, in slot order.
+    fn values_mut(&mut self) -> &mut [Value<T>] {
+        match &mut self.storage {
+            Storage::Inline(inline) => inline.as_mut_slice(),
+            Storage::Spilled(bucket) => &mut bucket.data,
+        }
+    }
+
+    /// Moves every inline value into a heap-allocated `Bucket`, followed by `value`.
+    ///
+    /// Every value keeps its slot and shared cell, so handles keep resolving.
+    ///
+    /// # Arguments
+    /// * `value` - The value that did not fit inline.
+    fn spill(&mut self, value: Value<T>) {
+        let Storage::Inline(inline) = &mut self.storage else {
+            return;
+        };
+        let mut bucket = Bucket::new(N.max(1));
+        bucket.grow_to(N + 1);
+        bucket.data.extend(inline.take_all());
+        bucket.data.push(value);
+        bucket.seq = self.seq;
+        bucket.paranoid();
+
+        self.storage = Storage::Spilled(bucket);
+    }
+
+    /// Moves every value of the heap-allocated `Bucket` back inline.
+    fn unspill(&mut self) {
+        let Storage::Spilled(bucket) = &mut self.storage else {
+            return;
+        };
+        let mut inline = InlineValues::new();
+        for value in bucket.data.drain(..) {
+            if inline.push(value).is_err() {
+                unreachable!()
+            }
+        }
+        self.seq = bucket.seq;
+        self.storage = Storage::Inline(inline);
+    }
+}
+
+impl<T, const N: usize> Default for SmallBucket<T, N> {
+    /// Creates a new, empty `SmallBucket` storing its values inline.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for SmallBucket<T, N> {
+    /// Formats the values for debugging purposes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.values().iter().map(|v| &v.data))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn test_spill_and_back() {
+        let mut bucket: SmallBucket<usize, 4> = SmallBucket::new();
+        let handles: Vec<_> = (0..4).map(|i| bucket.insert(i)).collect();
+        assert!(!bucket.is_spilled());
+        assert_eq!(bucket.capacity(), 4);
+
+        // The fifth value moves every value to the heap, keeping their handles
+        let e = bucket.insert(4);
+        assert!(bucket.is_spilled());
+        assert_eq!(bucket.capacity(), 8);
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.try_get(idx), Some(&i));
+        }
+        assert_eq!(bucket.try_get(&e), Some(&4));
+
+        // Removals relocate values on the heap, then move them back inline
+        assert_eq!(bucket.remove(&handles[0]), Some(0));
+        assert_eq!(bucket.remove(&handles[2]), Some(2));
+        assert!(bucket.is_spilled());
+        assert_eq!(bucket.remove(&e), Some(4));
+        assert!(!bucket.is_spilled());
+        assert_eq!(bucket.try_get(&handles[1]), Some(&1));
+        assert_eq!(bucket.try_get(&handles[3]), Some(&3));
+        assert!(!bucket.contains(&e));
+        assert!(handles[0].is_dangling());
+
+        // The values stored inline can spill again
+        let more: Vec<_> = (10..13).map(|i| bucket.insert(i)).collect();
+        assert!(bucket.is_spilled());
+        assert_eq!(bucket.len(), 5);
+        assert_eq!(bucket.try_get(&more[2]), Some(&12));
+    }
+
+    #[test]
+    fn test_inline_removals() {
+        let mut bucket: SmallBucket<char, 3> = SmallBucket::default();
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let c = bucket.insert('c');
+
+        assert_eq!(bucket.remove(&a), Some('a'));
+        assert_eq!(bucket.remove(&a), None);
+        *bucket.try_get_mut(&c).unwrap() = 'C';
+        assert_eq!(format!("{bucket:?}"), "['C', 'b']");
+
+        bucket
+            .iter_mut()
+            .for_each(|mut v| *v = v.to_ascii_uppercase());
+
+        assert_eq!(bucket.try_get(&b), Some(&'B'));
+        assert_eq!(bucket.iter().count(), 2);
+        assert!(!bucket.contains(&ValueIndex::dangling()));
+    }
+
+    #[test]
+    fn test_drops_every_value_once() {
+        let marker = Rc::new(());
+        {
+            let mut bucket: SmallBucket<Rc<()>, 2> = SmallBucket::new();
+            let handles: Vec<_> = (0..5).map(|_| bucket.insert(marker.clone())).collect();
+            bucket.remove(&handles[4]);
+            bucket.remove(&handles[3]);
+            bucket.remove(&handles[2]);
+            assert!(bucket.is_spilled());
+            bucket.remove(&handles[1]);
+            assert!(!bucket.is_spilled());
+            assert_eq!(Rc::strong_count(&marker), 2);
+
+            let mut inline: SmallBucket<Rc<()>, 2> = SmallBucket::new();
+            inline.insert(marker.clone());
+        }
+        assert_eq!(Rc::strong_count(&marker), 1);
+
+        let mut empty: SmallBucket<u8, 0> = SmallBucket::new();
+        let idx = empty.insert(1);
+        assert!(empty.is_spilled());
+        assert_eq!(empty.remove(&idx), Some(1));
+        assert!(!empty.is_spilled());
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, DefaultHasher},
+};
+
+use crate::{Bucket, Index, ValueIndex};
+
+/// A copy of a `Bucket` that is restored into a new `Bucket` with fresh handles.
+///
+/// `Snapshot` is created by [`Bucket::snapshot`]. Unlike a [`Checkpoint`](crate::Checkpoint),
+/// restoring it leaves the original `Bucket` and its handles untouched, and
+/// instead returns a [`Remap`] that translates them into handles to the
+/// restored elements.
+#[derive(Debug)]
+pub struct Snapshot<T> {
+    bucket: Bucket<T>,
+    cells: Vec<Index>,
+}
+
+/// Translates the handles of a snapshotted `Bucket` into handles to the restored one.
+///
+/// `Remap` is returned by [`Bucket::from_snapshot`]. It keeps the shared cells
+/// of the snapshotted elements alive, so that their handles are never mistaken
+/// for handles to other elements.
+#[derive(Debug)]
+pub struct Remap {
+    old: Vec<Index>,
+    new: Vec<Index>,
+    slots: HashMap<usize, usize, BuildHasherDefault<DefaultHasher>>,
+}
+
+impl<T> Snapshot<T> {
+    /// Returns the number of elements in the `Snapshot`.
+    pub fn len(&self) -> usize {
+        self.bucket.len()
+    }
+
+    /// Checks if the `Snapshot` holds no element.
+    pub fn is_empty(&self) -> bool {
+        self.bucket.is_empty()
+    }
+}
+
+impl Remap {
+    /// Returns a handle to the restored element that was in the given slot when the snapshot was taken.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot of the element at the time of the snapshot.
+    pub fn slot(&self, slot: usize) -> Option<ValueIndex> {
+        self.new.get(slot).map(|cell| ValueIndex(cell.clone()))
+    }
+
+    /// Translates a handle to the snapshotted `Bucket` into a handle to the restored element.
+    ///
+    /// Returns `None` if the element of the handle was not stored when the
+    /// snapshot was taken.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` issued by the snapshotted `Bucket`.
+    pub fn index(&self, index: &ValueIndex) -> Option<ValueIndex> {
+        let &slot = self.slots.get(&(Index::as_ptr(&index.0) as usize))?;
+        debug_assert!(Index::ptr_eq(&self.old[slot], &index.0));
+        self.slot(slot)
+    }
+}
+
+impl<T: Clone> Bucket<T> {
+    /// Captures a copy of the `Bucket`, along with the identity of its handles.
+    ///
+    /// The copy is taken as by [`Clone`], so persistent identifiers resolve to
+    /// the same values once restored.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            bucket: self.clone(),
+            cells: self.data.iter().map(|v| v.index.clone()).collect(),
+        }
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Restores a `Snapshot` into a new `Bucket`, returning it with the translation of the old handles.
+    ///
+    /// # Arguments
+    /// * `snapshot` - The `Snapshot` to restore.
+    pub fn from_snapshot(snapshot: Snapshot<T>) -> (Self, Remap) {
+        let Snapshot { bucket, cells } = snapshot;
+
+        let slots = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (Index::as_ptr(cell) as usize, i))
+            .collect();
+        let remap = Remap {
+            old: cells,
+            new: bucket.data.iter().map(|v| v.index.clone()).collect(),
+            slots,
+        };
+        (bucket, remap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_and_remap() {
+        let mut bucket = Bucket::new(4);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i.to_string())).collect();
+        let id = bucket.persist(&handles[7]).unwrap();
+        let snapshot = bucket.snapshot();
+        assert_eq!(snapshot.len(), 10);
+
+        // Mutating the original does not affect the snapshot
+        for idx in handles.iter().step_by(3) {
+            bucket.remove_index(&idx.0);
+        }
+        let later = bucket.insert(String::from("later"));
+        bucket.replace(&handles[1], String::from("changed"));
+
+        let (mut restored, remap) = Bucket::from_snapshot(snapshot);
+        assert_eq!(restored.len(), 10);
+        assert_eq!(restored.capacity(), 12);
+        assert_eq!(restored.validate(), Ok(()));
+
+        // Every old handle translates to its element at the time of the snapshot
+        let remapped: Vec<_> = handles.iter().map(|h| remap.index(h).unwrap()).collect();
+        for (i, idx) in remapped.iter().enumerate() {
+            assert_eq!(restored.try_get(idx), Some(&i.to_string()));
+            assert!(!restored.contains(&handles[i]));
+        }
+        assert!(remap.index(&later).is_none());
+        assert_eq!(restored.try_get(&remap.slot(2).unwrap()), Some(&"2".into()));
+        assert!(remap.slot(10).is_none());
+        assert_eq!(restored.resolve(id), remap.index(&handles[7]));
+
+        // The restored handles behave as usual, and the original is untouched
+        restored.remove_index(&remapped[0].0);
+        assert_eq!(restored.try_get(&remapped[9]), Some(&"9".into()));
+        assert!(remap
+            .index(&handles[0])
+            .is_some_and(|h| !restored.contains(&h)));
+        assert_eq!(bucket.try_get(&handles[1]), Some(&"changed".into()));
+        assert_eq!(bucket.len(), 7);
+    }
+
+    #[test]
+    fn test_snapshot_empty() {
+        let bucket = Bucket::<u8>::new(2);
+        let (restored, remap) = Bucket::from_snapshot(bucket.snapshot());
+        assert!(restored.is_empty());
+        assert!(remap.slot(0).is_none());
+        assert!(remap.index(&ValueIndex::dangling()).is_none());
+    }
+}
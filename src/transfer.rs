@@ -0,0 +1,153 @@
+use std::{fmt, sync::atomic::Ordering};
+
+use crate::{Bucket, ValueIndex};
+
+/// Describes why an element could not be transferred between buckets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferError {
+    /// The handle does not refer to an element of the source `Bucket`.
+    Stale,
+
+    /// The destination is full and its capacity cannot grow.
+    Full,
+}
+
+impl fmt::Display for TransferError {
+    /// Formats the error as a human-readable report.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stale => write!(f, "stale handle: the element is not stored in the source"),
+            Self::Full => write!(
+                f,
+                "full destination: the destination has no free slot and cannot grow"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+impl<T> Bucket<T> {
+    /// Moves the element at the specified index into another `Bucket`.
+    ///
+    /// The element keeps its shared cell, so every handle to it resolves
+    /// against `dest` afterwards. It is assigned a fresh sequence number there,
+    /// and the element moved into its former slot keeps its handles valid.
+    ///
+    /// A `Bucket` cannot be its own destination, as it cannot be borrowed twice.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the element to move.
+    /// * `dest` - The `Bucket` to move the element into.
+    pub fn transfer(
+        &mut self,
+        index: &ValueIndex,
+        dest: &mut Bucket<T>,
+    ) -> Result<(), TransferError> {
+        let i = self.slot(&index.0).ok_or(TransferError::Stale)?;
+
+        if dest.len() == dest.capacity() {
+            if !dest.can_grow() {
+                return Err(TransferError::Full);
+            }
+            dest.grow()
+        }
+        let mut value = self._remove_value(i);
+
+        // Perform the reservation deferred by `new_const`
+        if dest.data.capacity() == 0 {
+            dest.data.reserve_exact(dest.capacity.current)
+        }
+        value.seq = dest.next_seq();
+        #[cfg(feature = "lru")]
+        {
+            value.used = value.seq;
+        }
+        value.index.store(dest.len(), Ordering::Relaxed);
+        dest.data.push(value);
+
+        self.paranoid();
+        dest.paranoid();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer() {
+        let mut src = Bucket::new(2);
+        let mut dest = Bucket::new(2);
+        let a = src.insert('a');
+        let b = src.insert('b');
+        let c = src.insert('c');
+        let d = dest.insert('d');
+
+        assert_eq!(src.transfer(&a, &mut dest), Ok(()));
+
+        // The handle follows the element, and the survivor moved into its slot
+        assert!(!src.contains(&a));
+        assert!(dest.contains(&a));
+        assert_eq!(dest.data[dest.slot(&a.0).unwrap()].data, 'a');
+        assert_eq!(src.data[src.slot(&c.0).unwrap()].data, 'c');
+        assert_eq!(src.data[src.slot(&b.0).unwrap()].data, 'b');
+        assert!(dest.contains(&d));
+        assert_eq!((src.len(), dest.len()), (2, 2));
+        assert_eq!(src.validate(), Ok(()));
+        assert_eq!(dest.validate(), Ok(()));
+        assert_eq!(dest.current_seq(), 2);
+    }
+
+    #[test]
+    fn test_transfer_chain() {
+        let mut buckets: Vec<_> = (0..4).map(|_| Bucket::new(1)).collect();
+        let handles: Vec<_> = (0..6).map(|i| buckets[0].insert(i)).collect();
+
+        // Move every element one bucket along until all arrive in the last one
+        for step in 0..3 {
+            let [from, to] = buckets.get_disjoint_mut([step, step + 1]).unwrap();
+            for idx in &handles {
+                assert_eq!(from.transfer(idx, to), Ok(()));
+            }
+            assert!(from.is_empty());
+            assert_eq!(from.capacity(), 1);
+        }
+
+        let last = &buckets[3];
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(last.data[last.slot(&idx.0).unwrap()].data, i);
+        }
+        assert_eq!(last.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_transfer_errors() {
+        let mut src = Bucket::new(2);
+        let mut dest = Bucket::bounded_overwriting(1);
+        let a = src.insert(1);
+        let b = src.insert(2);
+
+        assert_eq!(src.transfer(&a, &mut dest), Ok(()));
+        assert_eq!(src.transfer(&a, &mut dest), Err(TransferError::Stale));
+        assert_eq!(
+            src.transfer(&ValueIndex::dangling(), &mut dest),
+            Err(TransferError::Stale)
+        );
+
+        // A full bounded destination does not evict its oldest value
+        assert_eq!(src.transfer(&b, &mut dest), Err(TransferError::Full));
+        assert!(src.contains(&b));
+        assert!(dest.contains(&a));
+
+        let mut fixed = Bucket::with_fixed_capacity(0);
+        assert_eq!(src.transfer(&b, &mut fixed), Err(TransferError::Full));
+        assert!(src.contains(&b));
+
+        // Transferring back resolves against the original bucket again
+        assert_eq!(dest.transfer(&a, &mut src), Ok(()));
+        assert!(src.contains(&a) && src.contains(&b));
+        assert!(dest.is_empty());
+    }
+}
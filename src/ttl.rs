@@ -0,0 +1,214 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use crate::{Bucket, ValueIndex, DANGLING};
+
+/// A source of the current time for expiring values.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// The `Clock` reading the system's monotonic clock, used unless another is set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// Returns `Instant::now()`.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Replaces the `Clock` used to compute deadlines.
+    ///
+    /// # Arguments
+    /// * `clock` - The new source of the current time.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Some(std::sync::Arc::new(clock))
+    }
+
+    /// Inserts a new value that expires once `ttl` has elapsed.
+    ///
+    /// Values inserted through any other method never expire.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    /// * `ttl` - How long the value lives.
+    pub fn insert_with_ttl(&mut self, data: T, ttl: Duration) -> ValueIndex {
+        let deadline = self.now() + ttl;
+        let index = self.insert(data);
+
+        if let Some(value) = self.data.last_mut() {
+            value.deadline = Some(deadline)
+        }
+        index
+    }
+
+    /// Returns the time left before the value at the specified index expires.
+    ///
+    /// Returns `None` if the value does not exist or never expires.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to check.
+    pub fn ttl_of(&self, index: &ValueIndex) -> Option<Duration> {
+        let deadline = self.data[self.slot(&index.0)?].deadline?;
+        Some(deadline.saturating_duration_since(self.now()))
+    }
+
+    /// Restarts the lifetime of the value at the specified index, returning whether it exists.
+    ///
+    /// This also makes a value that never expired start expiring.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to refresh.
+    /// * `ttl` - How long the value lives from now on.
+    pub fn refresh_ttl(&mut self, index: &ValueIndex, ttl: Duration) -> bool {
+        let Some(i) = self.slot(&index.0) else {
+            return false;
+        };
+        self.data[i].deadline = Some(self.now() + ttl);
+        true
+    }
+
+    /// Removes every value whose deadline is not after `now`, returning them in slot order.
+    ///
+    /// The remaining values keep their relative order and are compacted in a
+    /// single pass, after which the capacity shrinks by every unused chunk.
+    ///
+    /// # Arguments
+    /// * `now` - The time to compare the deadlines against.
+    pub fn purge_expired(&mut self, now: Instant) -> Vec<T> {
+        let expired: Vec<T> = self
+            .data
+            .extract_if(.., |v| v.deadline.is_some_and(|d| d <= now))
+            .map(|v| {
+                v.index.store(DANGLING, Ordering::Relaxed);
+                self.persisted.forget(&v.index);
+                self.interned.forget(&v.index);
+                v.data
+            })
+            .collect();
+
+        if !expired.is_empty() {
+            for (i, value) in self.data.iter().enumerate() {
+                value.index.store(i, Ordering::Relaxed)
+            }
+            self.shrink_unused();
+        }
+        self.paranoid();
+        expired
+    }
+
+    /// Reads the current time from the `Clock` of the `Bucket`.
+    fn now(&self) -> Instant {
+        self.clock.as_ref().map_or_else(Instant::now, |c| c.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// A `Clock` that only moves when told to.
+    #[derive(Clone)]
+    struct MockClock(Arc<Mutex<Instant>>);
+
+    impl MockClock {
+        fn advance(&self, by: Duration) -> Instant {
+            let mut now = self.0.lock().unwrap();
+            *now += by;
+            *now
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let mut bucket = Bucket::new(2);
+        bucket.set_clock(clock.clone());
+
+        let a = bucket.insert_with_ttl("a", secs(10));
+        let b = bucket.insert("b");
+        let c = bucket.insert_with_ttl("c", secs(5));
+        let d = bucket.insert_with_ttl("d", secs(20));
+        let e = bucket.insert_with_ttl("e", secs(5));
+        assert_eq!(bucket.ttl_of(&b), None);
+        assert_eq!(bucket.ttl_of(&c), Some(secs(5)));
+
+        let now = clock.advance(secs(4));
+        assert!(bucket.purge_expired(now).is_empty());
+        assert_eq!(bucket.ttl_of(&a), Some(secs(6)));
+
+        let now = clock.advance(secs(1));
+        assert_eq!(bucket.purge_expired(now), vec!["c", "e"]);
+        assert_eq!(bucket.capacity(), 4);
+        assert!(!bucket.contains(&c) && !bucket.contains(&e));
+
+        assert!(bucket.refresh_ttl(&a, secs(30)));
+        let now = clock.advance(secs(20));
+        assert_eq!(bucket.purge_expired(now), vec!["d"]);
+
+        let now = clock.advance(secs(100));
+        assert_eq!(bucket.purge_expired(now), vec!["a"]);
+        assert!(!bucket.refresh_ttl(&d, secs(1)));
+
+        // Values without a deadline never expire
+        let i = bucket.slot(&b.0).unwrap();
+        assert_eq!((bucket.len(), bucket.data[i].data), (1, "b"));
+        assert_eq!(bucket.capacity(), 2);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_purge_forgets_ids() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let mut bucket = Bucket::new(2);
+        bucket.set_clock(clock.clone());
+
+        let a = bucket.insert_with_ttl('a', secs(5));
+        let b = bucket.insert('b');
+        let ids = [bucket.persist(&a).unwrap(), bucket.persist(&b).unwrap()];
+
+        let now = clock.advance(secs(5));
+        assert_eq!(bucket.purge_expired(now), ['a']);
+        assert!(bucket.resolve(ids[0]).is_none());
+        assert_eq!(bucket.resolve(ids[1]).as_ref(), Some(&b));
+
+        // The identifier no longer keeps the shared cell of the expired value alive
+        assert_eq!(crate::Index::strong_count(&a.0), 1);
+    }
+
+    #[test]
+    fn test_purge_keeps_handles() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let mut bucket = Bucket::new(4);
+        bucket.set_clock(clock.clone());
+
+        let handles: Vec<_> = (0..12u64)
+            .map(|i| bucket.insert_with_ttl(i, secs(i % 3 + 1)))
+            .collect();
+        let now = clock.advance(secs(2));
+        assert_eq!(bucket.purge_expired(now), vec![0, 1, 3, 4, 6, 7, 9, 10]);
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.contains(idx), i % 3 == 2);
+        }
+        assert_eq!(bucket.capacity(), 4);
+    }
+}
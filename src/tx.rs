@@ -0,0 +1,413 @@
+use std::{ops::Deref, sync::atomic::Ordering};
+
+use crate::{intern::Interned, Bucket, Capacity, Index, Value, ValueIndex, DANGLING};
+
+/// A single step needed to undo a mutation performed inside a transaction.
+enum Undo<T> {
+    /// An element was pushed into the last slot, with a shared cell taken from the pool if set.
+    Insert(bool),
+
+    /// The element was removed from the given slot, along with its persistent identifier and intern entry.
+    Remove(usize, Value<T>, Option<u64>, Option<Interned>),
+
+    /// The element owning the cell held the given data and modified flag before being updated.
+    Update(Index, T, bool),
+}
+
+/// A pending set of mutations on a `Bucket`.
+///
+/// `BucketTx` is handed out by [`Bucket::transaction`] and records every
+/// mutation so that it can be undone. Read access to the underlying `Bucket`
+/// is available through `Deref`.
+pub struct BucketTx<'a, T> {
+    bucket: &'a mut Bucket<T>,
+    capacity: Capacity,
+    seq: u64,
+    log: Vec<Undo<T>>,
+    committed: bool,
+}
+
+impl<'a, T> BucketTx<'a, T> {
+    /// Starts recording mutations on the given `Bucket`.
+    ///
+    /// # Arguments
+    /// * `bucket` - The `Bucket` to mutate.
+    fn new(bucket: &'a mut Bucket<T>) -> Self {
+        Self {
+            capacity: bucket.capacity.clone(),
+            seq: bucket.seq,
+            bucket,
+            log: Vec::new(),
+            committed: false,
+        }
+    }
+
+    /// Inserts a new value into the `Bucket`.
+    ///
+    /// The returned handle becomes invalid if the transaction is rolled back,
+    /// and a shared cell taken from the pool of the `Bucket` is given back if
+    /// the handle was dropped by then.
+    /// In an overwriting `Bucket`, the value evicted to make room is restored
+    /// on rollback.
+    ///
+    /// # Arguments
+    /// * `data` - The value to insert.
+    pub fn insert(&mut self, data: T) -> ValueIndex {
+        if self.bucket.overwrite && self.bucket.is_full() {
+            if let Some(i) = self.bucket.oldest_slot() {
+                let index = &self.bucket.data[i].index;
+                let (id, entry) = (
+                    self.bucket.persisted.id(index),
+                    self.bucket.interned.entry(index),
+                );
+                let value = self.bucket._swap_remove(i);
+                self.log.push(Undo::Remove(i, value, id, entry));
+            }
+        }
+        let pooled = !self.bucket.free_cells.is_empty();
+        let index = self.bucket.insert(data);
+        self.log.push(Undo::Insert(pooled));
+        index
+    }
+
+    /// Removes the value at the specified index, returning whether it existed.
+    ///
+    /// The removed value is kept until the transaction ends, so that it can
+    /// be restored on rollback, and is dropped on commit.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to remove.
+    pub fn remove(&mut self, index: &ValueIndex) -> bool {
+        let Some(i) = self.bucket.slot(&index.0) else {
+            return false;
+        };
+        let id = self.bucket.persisted.id(&index.0);
+        let entry = self.bucket.interned.entry(&index.0);
+        let value = self.bucket._remove_value(i);
+        self.log.push(Undo::Remove(i, value, id, entry));
+        self.bucket.paranoid();
+        true
+    }
+
+    /// Updates the value at the specified index, returning whether it existed.
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` of the value to update.
+    /// * `f` - The closure applied to the value.
+    pub fn update(&mut self, index: &ValueIndex, f: impl FnOnce(&mut T)) -> bool
+    where
+        T: Clone,
+    {
+        let Some(i) = self.bucket.slot(&index.0) else {
+            return false;
+        };
+        let value = &mut self.bucket.data[i];
+        self.log.push(Undo::Update(
+            value.index.clone(),
+            value.data.clone(),
+            value.is_marked(),
+        ));
+        f(&mut value.data);
+        value.mark();
+        true
+    }
+
+    /// Undoes every recorded mutation in reverse order and restores the capacity and sequence counter.
+    fn rollback(&mut self) {
+        while let Some(undo) = self.log.pop() {
+            let data = &mut self.bucket.data;
+
+            match undo {
+                Undo::Insert(pooled) => {
+                    // Everything recorded afterwards is undone, so the element is last
+                    if let Some(mut value) = data.pop() {
+                        value.index.store(DANGLING, Ordering::Relaxed);
+                        self.bucket.persisted.forget(&value.index);
+                        self.bucket.interned.forget(&value.index);
+
+                        // A cell still referenced by a handle must keep identifying its element
+                        if pooled && Index::get_mut(&mut value.index).is_some() {
+                            self.bucket.free_cells.push(value.index)
+                        }
+                    }
+                }
+                Undo::Remove(i, value, id, entry) => {
+                    if let Some(id) = id {
+                        self.bucket.persisted.register(id, &value.index)
+                    }
+                    if let Some(entry) = entry {
+                        self.bucket.interned.insert(entry)
+                    }
+
+                    // Reverse the swap with the last element
+                    data.push(value);
+                    let j = data.len() - 1;
+
+                    if i < j {
+                        data.swap(i, j);
+                        data[j].index.store(j, Ordering::Relaxed)
+                    }
+                    data[i].index.store(i, Ordering::Relaxed)
+                }
+                Undo::Update(index, old, dirty) => {
+                    let value = &mut data[index.load(Ordering::Relaxed)];
+                    value.data = old;
+                    value.set_marked(dirty)
+                }
+            }
+        }
+
+        self.bucket.seq = self.seq;
+        self.bucket.set_capacity(self.capacity.clone())
+    }
+}
+
+impl<T> Deref for BucketTx<'_, T> {
+    type Target = Bucket<T>;
+
+    /// Provides immutable access to the `Bucket` in its current state.
+    fn deref(&self) -> &Self::Target {
+        self.bucket
+    }
+}
+
+impl<T> Drop for BucketTx<'_, T> {
+    /// Rolls back every recorded mutation unless the transaction was committed.
+    fn drop(&mut self) {
+        if !self.committed {
+            self.rollback()
+        }
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Applies a set of mutations atomically.
+    ///
+    /// If `f` returns `Err` or panics, every mutation made through the
+    /// `BucketTx` is undone, leaving the `Bucket` exactly as it was before the
+    /// call and every handle created inside the transaction invalid.
+    ///
+    /// # Arguments
+    /// * `f` - The closure performing the mutations.
+    pub fn transaction<R, E>(
+        &mut self,
+        f: impl FnOnce(&mut BucketTx<'_, T>) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let mut tx = BucketTx::new(self);
+        let result = f(&mut tx);
+        tx.committed = result.is_ok();
+        drop(tx);
+
+        self.paranoid();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::atomic::AtomicUsize,
+    };
+
+    use super::*;
+
+    /// Returns when a value was last used, or zero without the `lru` feature.
+    fn used(#[allow(unused_variables)] value: &Value<i32>) -> u64 {
+        #[cfg(feature = "lru")]
+        return value.used;
+        #[cfg(not(feature = "lru"))]
+        0
+    }
+
+    /// Captures the values, capacity, slot assignments, per-element metadata and pooled cells of a `Bucket`.
+    #[allow(clippy::type_complexity)]
+    fn state(
+        bucket: &Bucket<i32>,
+    ) -> (
+        String,
+        Vec<(usize, u64, u64, bool)>,
+        Vec<*const AtomicUsize>,
+    ) {
+        (
+            format!("{:?}", bucket),
+            bucket
+                .data
+                .iter()
+                .map(|v| {
+                    (
+                        v.index.load(Ordering::Relaxed),
+                        v.seq,
+                        used(v),
+                        v.is_marked(),
+                    )
+                })
+                .collect(),
+            bucket.free_cells.iter().map(Index::as_ptr).collect(),
+        )
+    }
+
+    fn populated() -> (Bucket<i32>, Vec<ValueIndex>) {
+        let mut bucket = Bucket::new(2);
+        let handles = (0..5).map(|i| bucket.insert(i)).collect();
+        (bucket, handles)
+    }
+
+    #[test]
+    fn test_transaction_commit() {
+        let (mut bucket, handles) = populated();
+
+        let result: Result<_, ()> = bucket.transaction(|tx| {
+            assert!(tx.remove(&handles[0]));
+            assert!(tx.update(&handles[4], |v| *v = 40));
+            Ok(tx.insert(5))
+        });
+        let idx = result.unwrap();
+
+        assert_eq!(bucket.len(), 5);
+        assert_eq!(bucket.slot(&handles[0].0), None);
+        assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, 5);
+        assert_eq!(bucket.data[bucket.slot(&handles[4].0).unwrap()].data, 40);
+    }
+
+    #[test]
+    fn test_transaction_error_rollback() {
+        let (mut bucket, handles) = populated();
+        let before = state(&bucket);
+        let mut inserted = Vec::new();
+
+        let result = bucket.transaction(|tx| {
+            for i in 10..20 {
+                inserted.push(tx.insert(i));
+            }
+            assert!(tx.remove(&handles[1]));
+            assert!(tx.remove(&inserted[3]));
+            assert!(tx.update(&handles[2], |v| *v = -1));
+            assert!(tx.remove(&handles[0]));
+            Err::<(), _>("validation failed")
+        });
+
+        assert_eq!(result, Err("validation failed"));
+        assert_eq!(state(&bucket), before);
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i as i32);
+        }
+        for idx in &inserted {
+            assert_eq!(bucket.slot(&idx.0), None);
+        }
+    }
+
+    #[test]
+    fn test_transaction_panic_rollback() {
+        let (mut bucket, handles) = populated();
+        let before = state(&bucket);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bucket.transaction(|tx| {
+                tx.remove(&handles[4]);
+                tx.remove(&handles[2]);
+                tx.insert(7);
+                tx.update(&handles[0], |v| *v = 100);
+
+                if tx.len() > 1 {
+                    panic!("interrupted");
+                }
+                Ok::<_, ()>(())
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(state(&bucket), before);
+
+        for (i, idx) in handles.iter().enumerate() {
+            assert_eq!(bucket.data[bucket.slot(&idx.0).unwrap()].data, i as i32);
+        }
+    }
+
+    #[test]
+    fn test_transaction_rollback_persisted() {
+        let (mut bucket, handles) = populated();
+        let id = bucket.persist(&handles[1]).unwrap();
+
+        let result = bucket.transaction(|tx| {
+            assert!(tx.remove(&handles[1]));
+            assert!(tx.resolve(id).is_none());
+            Err::<(), _>(())
+        });
+        assert!(result.is_err());
+        assert!(bucket.contains(&handles[1]));
+        assert_eq!(bucket.resolve(id).as_ref(), Some(&handles[1]));
+
+        // Committed removals forget the identifier for good
+        let result = bucket.transaction(|tx| Ok::<_, ()>(tx.remove(&handles[1])));
+        assert_eq!(result, Ok(true));
+        assert!(bucket.resolve(id).is_none());
+    }
+
+    #[test]
+    fn test_transaction_rollback_overwriting() {
+        let mut bucket = Bucket::bounded_overwriting(2);
+        let a = bucket.insert(1);
+        let b = bucket.insert(2);
+        let before = state(&bucket);
+
+        let result = bucket.transaction(|tx| {
+            tx.insert(3);
+            tx.insert(4);
+            assert!(!tx.contains(&a) && !tx.contains(&b));
+            Err::<(), _>(())
+        });
+        assert!(result.is_err());
+        assert_eq!(state(&bucket), before);
+        assert_eq!(bucket.try_get(&a), Some(&1));
+        assert_eq!(bucket.try_get(&b), Some(&2));
+
+        // The oldest value is still evicted first afterwards, its slot taken by the new one
+        bucket.insert(5);
+        assert!(!bucket.contains(&a));
+        assert_eq!(bucket, [5, 2]);
+    }
+
+    #[test]
+    fn test_transaction_rollback_metadata() {
+        let (mut bucket, mut handles) = populated();
+        bucket.remove(handles.pop().unwrap());
+        bucket.remove(handles.pop().unwrap());
+        #[cfg(feature = "lru")]
+        bucket.touch(&handles[0]);
+        assert_eq!(bucket.free_cells.len(), 2);
+        let before = state(&bucket);
+
+        // The pooled cells are consumed, and the updated value flagged as modified
+        let result = bucket.transaction(|tx| {
+            tx.insert(10);
+            tx.insert(11);
+            assert!(tx.update(&handles[1], |v| *v = -1));
+            assert!(tx.free_cells.is_empty());
+            Err::<(), _>(())
+        });
+        assert!(result.is_err());
+        assert_eq!(state(&bucket), before);
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_transaction_rollback_interned() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.intern(1);
+        bucket.intern(1);
+
+        let result = bucket.transaction(|tx| {
+            assert!(tx.remove(&a));
+            Err::<(), _>(())
+        });
+        assert!(result.is_err());
+
+        // The element keeps both of its references
+        assert!(Index::ptr_eq(&bucket.intern(1).0, &a.0));
+        assert_eq!(bucket.release(&a), None);
+        assert_eq!(bucket.release(&a), None);
+        assert_eq!(bucket.release(&a), Some(1));
+    }
+}
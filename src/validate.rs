@@ -0,0 +1,185 @@
+use std::{fmt, sync::atomic::Ordering};
+
+use crate::{Bucket, Index};
+
+/// Describes an internal invariant of a `Bucket` that does not hold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The shared cell of the element in `slot` points at a different slot.
+    SlotMismatch { slot: usize, stored: usize },
+
+    /// The element in `slot` shares its cell with the element in `other`.
+    DuplicateCell { slot: usize, other: usize },
+
+    /// More elements are stored than the capacity allows.
+    LengthExceedsCapacity { len: usize, capacity: usize },
+
+    /// The capacity cannot be reached from the original capacity by the growth policy and maximum.
+    InvalidCapacity { current: usize, original: usize },
+
+    /// Raw parts were given a different number of values and cells.
+    MismatchedParts { values: usize, cells: usize },
+}
+
+impl fmt::Display for InvariantViolation {
+    /// Formats the violation as a human-readable report.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SlotMismatch { slot, stored } => write!(
+                f,
+                "slot mismatch: the element in slot {slot} has a handle pointing at slot {stored}"
+            ),
+            Self::DuplicateCell { slot, other } => write!(
+                f,
+                "duplicate cell: the element in slot {slot} shares its handle with slot {other}"
+            ),
+            Self::LengthExceedsCapacity { len, capacity } => write!(
+                f,
+                "length exceeds capacity: {len} elements are stored with a capacity of {capacity}"
+            ),
+            Self::InvalidCapacity { current, original } => write!(
+                f,
+                "invalid capacity: {current} cannot be reached from {original} by the growth policy"
+            ),
+            Self::MismatchedParts { values, cells } => write!(
+                f,
+                "mismatched parts: {values} values were given with {cells} cells"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+impl<T> Bucket<T> {
+    /// Checks every internal invariant of the `Bucket`.
+    ///
+    /// Returns the first violation found, if any.
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let len = self.len();
+        let capacity = &self.capacity;
+
+        if len > capacity.current {
+            return Err(InvariantViolation::LengthExceedsCapacity {
+                len,
+                capacity: capacity.current,
+            });
+        }
+
+        if !capacity.is_reachable() {
+            return Err(InvariantViolation::InvalidCapacity {
+                current: capacity.current,
+                original: capacity.original,
+            });
+        }
+
+        for (slot, value) in self.data.iter().enumerate() {
+            let stored = value.index.load(Ordering::Relaxed);
+
+            if stored != slot {
+                // A cell pointing at another element that owns the same cell is shared
+                return Err(match self.data.get(stored) {
+                    Some(other) if Index::ptr_eq(&other.index, &value.index) => {
+                        InvariantViolation::DuplicateCell {
+                            slot,
+                            other: stored,
+                        }
+                    }
+                    _ => InvariantViolation::SlotMismatch { slot, stored },
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Panics with a report of the first violated invariant.
+    ///
+    /// This is a no-op unless the `paranoid` feature is enabled.
+    #[inline(always)]
+    pub(crate) fn paranoid(&self) {
+        #[cfg(feature = "paranoid")]
+        if let Err(e) = self.validate() {
+            panic!("bucket invariant violated: {e}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ok() {
+        let mut bucket = Bucket::new(3);
+        let handles: Vec<_> = (0..10).map(|i| bucket.insert(i)).collect();
+        bucket.remove(handles.into_iter().nth(4).unwrap());
+        assert_eq!(bucket.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_slot_mismatch() {
+        let mut bucket = Bucket::new(4);
+        for i in 0..4 {
+            bucket.insert(i);
+        }
+        bucket.data[2].index.store(7, Ordering::Relaxed);
+
+        let e = bucket.validate().unwrap_err();
+        assert_eq!(e, InvariantViolation::SlotMismatch { slot: 2, stored: 7 });
+        assert_eq!(
+            e.to_string(),
+            "slot mismatch: the element in slot 2 has a handle pointing at slot 7"
+        );
+    }
+
+    #[test]
+    fn test_validate_duplicate_cell() {
+        let mut bucket = Bucket::new(4);
+        for i in 0..4 {
+            bucket.insert(i);
+        }
+        bucket.data[3].index = bucket.data[1].index.clone();
+
+        assert_eq!(
+            bucket.validate(),
+            Err(InvariantViolation::DuplicateCell { slot: 3, other: 1 })
+        );
+    }
+
+    #[test]
+    fn test_validate_capacity() {
+        let mut bucket = Bucket::new(4);
+        for i in 0..4 {
+            bucket.insert(i);
+        }
+
+        bucket.capacity.current = 3;
+        assert_eq!(
+            bucket.validate(),
+            Err(InvariantViolation::LengthExceedsCapacity {
+                len: 4,
+                capacity: 3
+            })
+        );
+
+        bucket.capacity.current = 6;
+        assert_eq!(
+            bucket.validate(),
+            Err(InvariantViolation::InvalidCapacity {
+                current: 6,
+                original: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "paranoid")]
+    #[test]
+    #[should_panic(expected = "bucket invariant violated: slot mismatch: the element in slot 0")]
+    fn test_paranoid_report() {
+        let mut bucket = Bucket::new(4);
+        bucket.insert(0);
+        bucket.insert(1);
+        bucket.data[0].index.store(1, Ordering::Relaxed);
+        bucket.insert(2);
+    }
+}
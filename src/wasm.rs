@@ -0,0 +1,229 @@
+//! JavaScript bindings for buckets of numbers and of strings.
+//!
+//! Handles are exposed as opaque `JsHandle` objects and are validated
+//! against the bucket on every use, so a stale handle, or one issued by
+//! another bucket, is reported rather than trusted.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Bucket, ValueIndex};
+
+/// An opaque handle to an element of a `JsBucketF64` or a `JsBucketString`.
+#[wasm_bindgen]
+pub struct JsHandle(ValueIndex);
+
+#[wasm_bindgen]
+impl JsHandle {
+    /// Checks if this handle and `other` refer to the same element.
+    ///
+    /// # Arguments
+    /// * `other` - The handle to compare with.
+    #[wasm_bindgen(js_name = equals)]
+    pub fn equals(&self, other: &JsHandle) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// A bucket of numbers.
+#[wasm_bindgen]
+pub struct JsBucketF64(Bucket<f64>);
+
+#[wasm_bindgen]
+impl JsBucketF64 {
+    /// Creates a new bucket with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the bucket.
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: usize) -> Self {
+        Self(Bucket::new(capacity))
+    }
+
+    /// Returns the number of values in the bucket.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks if the bucket is empty.
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Inserts a value, returning a new handle to it.
+    ///
+    /// # Arguments
+    /// * `value` - The value to insert.
+    pub fn insert(&mut self, value: f64) -> JsHandle {
+        JsHandle(self.0.insert(value))
+    }
+
+    /// Returns the value referenced by `handle`, or `undefined` if it is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the value to retrieve.
+    pub fn get(&self, handle: &JsHandle) -> Option<f64> {
+        self.0.try_get(&handle.0).copied()
+    }
+
+    /// Checks if `handle` refers to a value of the bucket.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle to look up.
+    pub fn contains(&self, handle: &JsHandle) -> bool {
+        self.0.contains(&handle.0)
+    }
+
+    /// Removes the value referenced by `handle`, returning it, or `undefined` if it is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the value to remove.
+    pub fn remove(&mut self, handle: &JsHandle) -> Option<f64> {
+        self.0.remove(&handle.0)
+    }
+
+    /// Copies the values into a new array, in slot order.
+    pub fn values(&self) -> Vec<f64> {
+        self.0.data.iter().map(|v| v.data).collect()
+    }
+
+    /// Returns a new handle to every value, in the same order as `values`.
+    pub fn handles(&self) -> Vec<JsHandle> {
+        self.0.data.iter().map(|v| JsHandle(v.index())).collect()
+    }
+}
+
+/// A bucket of strings.
+#[wasm_bindgen]
+pub struct JsBucketString(Bucket<String>);
+
+#[wasm_bindgen]
+impl JsBucketString {
+    /// Creates a new bucket with the specified initial capacity.
+    ///
+    /// # Arguments
+    /// * `capacity` - The initial number of slots in the bucket.
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: usize) -> Self {
+        Self(Bucket::new(capacity))
+    }
+
+    /// Returns the number of values in the bucket.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks if the bucket is empty.
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Inserts a value, returning a new handle to it.
+    ///
+    /// # Arguments
+    /// * `value` - The value to insert.
+    pub fn insert(&mut self, value: String) -> JsHandle {
+        JsHandle(self.0.insert(value))
+    }
+
+    /// Returns a copy of the value referenced by `handle`, or `undefined` if it is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the value to retrieve.
+    pub fn get(&self, handle: &JsHandle) -> Option<String> {
+        self.0.try_get(&handle.0).cloned()
+    }
+
+    /// Checks if `handle` refers to a value of the bucket.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle to look up.
+    pub fn contains(&self, handle: &JsHandle) -> bool {
+        self.0.contains(&handle.0)
+    }
+
+    /// Removes the value referenced by `handle`, returning it, or `undefined` if it is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the value to remove.
+    pub fn remove(&mut self, handle: &JsHandle) -> Option<String> {
+        self.0.remove(&handle.0)
+    }
+
+    /// Copies the values into a new array, in slot order.
+    pub fn values(&self) -> Vec<String> {
+        self.0.data.iter().map(|v| v.data.clone()).collect()
+    }
+
+    /// Returns a new handle to every value, in the same order as `values`.
+    pub fn handles(&self) -> Vec<JsHandle> {
+        self.0.data.iter().map(|v| JsHandle(v.index())).collect()
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_numbers() {
+        let mut bucket = JsBucketF64::new(2);
+        assert!(bucket.is_empty());
+        let handles: Vec<_> = (0..5).map(|i| bucket.insert(i as f64 / 2.0)).collect();
+        assert_eq!(bucket.length(), 5);
+        assert_eq!(bucket.get(&handles[3]), Some(1.5));
+
+        assert_eq!(bucket.remove(&handles[1]), Some(0.5));
+        assert_eq!(bucket.remove(&handles[1]), None);
+        assert!(!bucket.contains(&handles[1]));
+        assert_eq!(bucket.get(&handles[1]), None);
+
+        // The snapshots line up, slot by slot
+        let values = bucket.values();
+        let mut sorted = values.clone();
+        sorted.sort_by(f64::total_cmp);
+        assert_eq!(sorted, [0.0, 1.0, 1.5, 2.0]);
+        for (handle, value) in bucket.handles().iter().zip(values) {
+            assert_eq!(bucket.get(handle), Some(value));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_strings() {
+        let mut bucket = JsBucketString::new(1);
+        let a = bucket.insert("a".into());
+        let b = bucket.insert("b".into());
+        assert_eq!(bucket.length(), 2);
+        assert_eq!(bucket.get(&b).as_deref(), Some("b"));
+
+        assert_eq!(bucket.remove(&a).as_deref(), Some("a"));
+        assert!(!bucket.is_empty() && bucket.contains(&b));
+        assert_eq!(bucket.values(), ["b"]);
+        assert!(bucket.handles()[0].equals(&b));
+        assert!(!b.equals(&a));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_foreign_handles() {
+        let mut numbers = JsBucketF64::new(2);
+        let mut others = JsBucketF64::new(2);
+        let a = numbers.insert(1.0);
+        let b = others.insert(2.0);
+
+        // A handle issued by another bucket never resolves, even to the same slot
+        assert_eq!(numbers.get(&b), None);
+        assert_eq!(numbers.remove(&b), None);
+        assert_eq!(others.get(&a), None);
+        assert_eq!((numbers.length(), others.length()), (1, 1));
+
+        let mut strings = JsBucketString::new(2);
+        strings.insert("a".into());
+        assert_eq!(strings.get(&a), None);
+        assert!(!strings.contains(&b));
+    }
+}
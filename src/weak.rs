@@ -0,0 +1,148 @@
+use crate::{Bucket, Index, ValueIndex};
+
+#[cfg(not(feature = "atomic"))]
+type Weak = std::rc::Weak<std::sync::atomic::AtomicUsize>;
+
+#[cfg(feature = "atomic")]
+type Weak = std::sync::Weak<std::sync::atomic::AtomicUsize>;
+
+/// A non-owning handle to an element of a `Bucket`.
+///
+/// Unlike a `ValueIndex`, a `WeakIndex` does not keep the shared cell of its
+/// element alive, so it can be stored in external structures without pinning
+/// removed elements in memory. It is turned back into a `ValueIndex` by
+/// [`WeakIndex::upgrade`], which fails once the element has been removed.
+#[derive(Clone, Debug, Default)]
+pub struct WeakIndex(Weak);
+
+impl ValueIndex {
+    /// Creates a non-owning handle to the same element.
+    pub fn downgrade(&self) -> WeakIndex {
+        WeakIndex(Index::downgrade(&self.0))
+    }
+}
+
+impl WeakIndex {
+    /// Creates a `WeakIndex` that never upgrades.
+    pub const fn new() -> Self {
+        Self(Weak::new())
+    }
+
+    /// Returns a handle to the element, if it is still stored in the `Bucket`.
+    ///
+    /// This fails once the element was removed, even if other handles keep its
+    /// shared cell alive or another element now occupies its slot.
+    ///
+    /// # Arguments
+    /// * `bucket` - The `Bucket` that issued the handle.
+    pub fn upgrade<T>(&self, bucket: &Bucket<T>) -> Option<ValueIndex> {
+        let index = self.0.upgrade()?;
+        bucket.slot(&index).map(|_| ValueIndex(index))
+    }
+}
+
+impl<T> Bucket<T> {
+    /// Checks if the handle still resolves to an element of the `Bucket`.
+    ///
+    /// This is equivalent to [`Bucket::contains`].
+    ///
+    /// # Arguments
+    /// * `index` - The `ValueIndex` to check.
+    pub fn is_valid(&self, index: &ValueIndex) -> bool {
+        self.contains(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_before_removal() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let weak_b = b.downgrade();
+        let weak_c = bucket.insert('c').downgrade();
+
+        let upgraded = weak_b.upgrade(&bucket).unwrap();
+        assert!(bucket.is_valid(&upgraded));
+        assert_eq!(bucket.try_get(&upgraded), Some(&'b'));
+
+        // The element keeps resolving after being relocated
+        bucket.remove_index(&a.0);
+        let upgraded = weak_c.upgrade(&bucket).unwrap();
+        assert!(bucket.is_valid(&upgraded));
+        assert_eq!(bucket.try_get(&upgraded), Some(&'c'));
+    }
+
+    #[test]
+    fn test_upgrade_after_removal() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert(1);
+        let weak = a.downgrade();
+        assert!(weak.upgrade(&bucket).is_some());
+
+        // Once the element is removed and its last handle dropped, the cell is freed
+        bucket.remove_index(&a.0);
+        drop(a);
+        assert!(weak.upgrade(&bucket).is_none());
+
+        // A handle kept alive elsewhere does not make the element valid again
+        let b = bucket.insert(2);
+        let weak = b.downgrade();
+        bucket.remove_index(&b.0);
+        assert!(weak.upgrade(&bucket).is_none());
+        assert!(!bucket.is_valid(&b));
+
+        // Nor does a cell that is only weakly referenced get reused by a new element
+        let c = bucket.insert(3);
+        let weak = c.downgrade();
+        bucket.remove(c);
+        let d = bucket.insert(4);
+        assert!(weak.upgrade(&bucket).is_none());
+        assert!(bucket.contains(&d));
+
+        assert!(WeakIndex::new().upgrade(&bucket).is_none());
+        assert!(ValueIndex::dangling()
+            .downgrade()
+            .upgrade(&bucket)
+            .is_none());
+    }
+
+    #[test]
+    fn test_upgrade_after_recycling() {
+        let mut bucket = Bucket::new(2);
+        let a = bucket.insert('a');
+        let b = bucket.insert('b');
+        let weak_a = a.downgrade();
+        let weak_b = b.downgrade();
+
+        // The slot of `a` is taken over by `b`, then the slot of `b` by `c`
+        bucket.remove_index(&a.0);
+        let c = bucket.insert('c');
+        assert!(weak_a.upgrade(&bucket).is_none());
+        assert_eq!(
+            bucket.try_get(&weak_b.upgrade(&bucket).unwrap()),
+            Some(&'b')
+        );
+
+        bucket.remove_index(&b.0);
+        bucket.remove_index(&c.0);
+        bucket.insert('d');
+        bucket.insert('e');
+        assert!(weak_a.upgrade(&bucket).is_none());
+        assert!(weak_b.upgrade(&bucket).is_none());
+        assert!(!bucket.is_valid(&b));
+    }
+
+    #[test]
+    fn test_upgrade_other_bucket() {
+        let mut a = Bucket::new(1);
+        let mut b = Bucket::new(1);
+        let weak = a.insert(1).downgrade();
+        b.insert(2);
+        assert!(weak.upgrade(&b).is_none());
+        assert!(weak.upgrade(&a).is_some());
+    }
+}